@@ -1,3 +1,4 @@
+use crate::commands::error::{CommandError, ErrorCode};
 use crate::commands::types::{
   CustomSkinData, PartyModeConfig, SavedConfig, SkinData, ThemePreferences,
 };
@@ -10,11 +11,11 @@ use tauri::{AppHandle, Manager};
 
 // Debug command to check what's in config
 #[tauri::command]
-pub async fn debug_config(app: tauri::AppHandle) -> Result<String, String> {
+pub async fn debug_config(app: tauri::AppHandle) -> Result<String, CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   let file = config_dir.join("config.json");
 
@@ -22,39 +23,41 @@ pub async fn debug_config(app: tauri::AppHandle) -> Result<String, String> {
     return Ok("Config file does not exist".to_string());
   }
 
-  let content = fs::read_to_string(&file).map_err(|e| format!("Failed to read config: {}", e))?;
+  let content =
+    fs::read_to_string(&file).map_err(|e| CommandError::config("Failed to read config", e))?;
 
   Ok(content)
 }
 
 // Add functions to save and load game path
 #[tauri::command]
-pub async fn save_league_path(app: tauri::AppHandle, path: String) -> Result<(), String> {
+pub async fn save_league_path(app: tauri::AppHandle, path: String) -> Result<(), CommandError> {
   println!("Saving League path: {}", path);
 
   let app_data_dir = app
     .path()
     .app_data_dir()
-    .or_else(|e| Err(format!("Failed to get app data directory: {}", e)))?;
+    .map_err(|e| CommandError::config("Failed to get app data directory", e))?;
 
   // Create config directory if it doesn't exist
   let config_dir = app_data_dir.join("config");
   fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config directory", e))?;
 
   // Save path to config file
   let config_file = config_dir.join("league_path.txt");
-  fs::write(&config_file, &path).map_err(|e| format!("Failed to write league path: {}", e))?;
+  fs::write(&config_file, &path)
+    .map_err(|e| CommandError::config("Failed to write league path", e))?;
 
   Ok(())
 }
 
 #[tauri::command]
-pub async fn load_league_path(app: tauri::AppHandle) -> Result<String, String> {
+pub async fn load_league_path(app: tauri::AppHandle) -> Result<String, CommandError> {
   let app_data_dir = app
     .path()
     .app_data_dir()
-    .or_else(|e| Err(format!("Failed to get app data directory: {}", e)))?;
+    .map_err(|e| CommandError::config("Failed to get app data directory", e))?;
 
   let config_file = app_data_dir.join("config").join("league_path.txt");
 
@@ -62,8 +65,8 @@ pub async fn load_league_path(app: tauri::AppHandle) -> Result<String, String> {
     return Ok(String::new()); // Return empty string if no saved path
   }
 
-  let path =
-    fs::read_to_string(&config_file).map_err(|e| format!("Failed to read league path: {}", e))?;
+  let path = fs::read_to_string(&config_file)
+    .map_err(|e| CommandError::config("Failed to read league path", e))?;
 
   // Verify the path still exists and contains either:
   // - Game/League of Legends.exe (game executable)
@@ -89,14 +92,14 @@ pub async fn save_selected_skins(
   favorites: Vec<u32>,
   theme: Option<ThemePreferences>,
   selected_misc_items: Option<std::collections::HashMap<String, Vec<String>>>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
   let file = config_dir.join("config.json");
 
   // Read existing config if it exists. Be tolerant of empty/malformed files by
@@ -139,18 +142,19 @@ pub async fn save_selected_skins(
 
   // Write back the merged config
   let data = serde_json::to_string_pretty(&config)
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
-  std::fs::write(&file, data).map_err(|e| format!("Failed to write config.json: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
   Ok(())
 }
 
 // New command to load config.json (league path + skins)
 #[tauri::command]
-pub async fn load_config(app: tauri::AppHandle) -> Result<SavedConfig, String> {
+pub async fn load_config(app: tauri::AppHandle) -> Result<SavedConfig, CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   let file = config_dir.join("config.json");
   if !file.exists() {
@@ -167,6 +171,10 @@ pub async fn load_config(app: tauri::AppHandle) -> Result<SavedConfig, String> {
       last_data_commit: None,
       cslol_tools_version: None,
       manual_injection_mode: false,
+      conflict_winners: std::collections::HashMap::new(),
+      load_order: crate::commands::types::default_load_order(),
+      extraction_limits: Default::default(),
+      persistent_overlay: false,
     });
   }
   let content = match std::fs::read_to_string(&file) {
@@ -189,6 +197,10 @@ pub async fn load_config(app: tauri::AppHandle) -> Result<SavedConfig, String> {
         last_data_commit: None,
         cslol_tools_version: None,
         manual_injection_mode: false,
+        conflict_winners: std::collections::HashMap::new(),
+        load_order: crate::commands::types::default_load_order(),
+        extraction_limits: Default::default(),
+        persistent_overlay: false,
       });
     }
   };
@@ -213,6 +225,10 @@ pub async fn load_config(app: tauri::AppHandle) -> Result<SavedConfig, String> {
         last_data_commit: None,
         cslol_tools_version: None,
         manual_injection_mode: false,
+        conflict_winners: std::collections::HashMap::new(),
+        load_order: crate::commands::types::default_load_order(),
+        extraction_limits: Default::default(),
+        persistent_overlay: false,
       });
     }
   };
@@ -231,22 +247,22 @@ pub async fn select_skin_for_champion(
   champion_id: u32,
   skin_data: Option<SkinData>,
   custom_skin_data: Option<CustomSkinData>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
   let file = config_dir.join("config.json");
 
   // Load existing config
   let mut config = if file.exists() {
-    let content =
-      std::fs::read_to_string(&file).map_err(|e| format!("Failed to read config.json: {}", e))?;
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| CommandError::config("Failed to read config.json", e))?;
     serde_json::from_str::<SavedConfig>(&content)
-      .map_err(|e| format!("Failed to parse config.json: {}", e))?
+      .map_err(|e| CommandError::config("Failed to parse config.json", e))?
   } else {
     SavedConfig {
       league_path: None,
@@ -261,6 +277,10 @@ pub async fn select_skin_for_champion(
       last_data_commit: None,
       cslol_tools_version: None,
       manual_injection_mode: false,
+      conflict_winners: std::collections::HashMap::new(),
+      load_order: crate::commands::types::default_load_order(),
+      extraction_limits: Default::default(),
+      persistent_overlay: false,
     }
   };
 
@@ -283,8 +303,9 @@ pub async fn select_skin_for_champion(
 
   // Save the updated config
   let data = serde_json::to_string_pretty(&config)
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
-  std::fs::write(&file, data).map_err(|e| format!("Failed to write config.json: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
 
   Ok(())
 }
@@ -294,11 +315,11 @@ pub async fn select_skin_for_champion(
 pub async fn remove_skin_for_champion(
   app: tauri::AppHandle,
   champion_id: u32,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   let file = config_dir.join("config.json");
 
@@ -307,10 +328,10 @@ pub async fn remove_skin_for_champion(
   }
 
   // Load existing config
-  let content =
-    std::fs::read_to_string(&file).map_err(|e| format!("Failed to read config.json: {}", e))?;
-  let mut config: SavedConfig =
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config.json: {}", e))?;
+  let content = std::fs::read_to_string(&file)
+    .map_err(|e| CommandError::config("Failed to read config.json", e))?;
+  let mut config: SavedConfig = serde_json::from_str(&content)
+    .map_err(|e| CommandError::config("Failed to parse config.json", e))?;
 
   // Remove selections for this champion
   config.skins.retain(|s| s.champion_id != champion_id);
@@ -318,8 +339,9 @@ pub async fn remove_skin_for_champion(
 
   // Save the updated config
   let data = serde_json::to_string_pretty(&config)
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
-  std::fs::write(&file, data).map_err(|e| format!("Failed to write config.json: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
 
   Ok(())
 }
@@ -329,22 +351,22 @@ pub async fn remove_skin_for_champion(
 pub async fn save_custom_skin(
   app: tauri::AppHandle,
   custom_skin: CustomSkinData,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
   let file = config_dir.join("config.json");
 
   // Load existing config
   let mut config = if file.exists() {
-    let content =
-      std::fs::read_to_string(&file).map_err(|e| format!("Failed to read config.json: {}", e))?;
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| CommandError::config("Failed to read config.json", e))?;
     serde_json::from_str::<SavedConfig>(&content)
-      .map_err(|e| format!("Failed to parse config.json: {}", e))?
+      .map_err(|e| CommandError::config("Failed to parse config.json", e))?
   } else {
     SavedConfig {
       league_path: None,
@@ -359,6 +381,10 @@ pub async fn save_custom_skin(
       last_data_commit: None,
       cslol_tools_version: None,
       manual_injection_mode: false,
+      conflict_winners: std::collections::HashMap::new(),
+      load_order: crate::commands::types::default_load_order(),
+      extraction_limits: Default::default(),
+      persistent_overlay: false,
     }
   };
 
@@ -370,15 +396,18 @@ pub async fn save_custom_skin(
 
   // Save the updated config
   let data = serde_json::to_string_pretty(&config)
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
-  std::fs::write(&file, data).map_err(|e| format!("Failed to write config.json: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
 
   Ok(())
 }
 
 // Command to get all custom skins
 #[tauri::command]
-pub async fn get_all_custom_skins(app: tauri::AppHandle) -> Result<Vec<CustomSkinData>, String> {
+pub async fn get_all_custom_skins(
+  app: tauri::AppHandle,
+) -> Result<Vec<CustomSkinData>, CommandError> {
   let config = load_config(app).await?;
   Ok(config.custom_skins)
 }
@@ -411,69 +440,73 @@ pub fn get_league_path_from_config(app_handle: &AppHandle) -> Option<String> {
 
 // Command to set auto_update_data in config.json
 #[tauri::command]
-pub async fn set_auto_update_data(app: tauri::AppHandle, value: bool) -> Result<(), String> {
+pub async fn set_auto_update_data(app: tauri::AppHandle, value: bool) -> Result<(), CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
   let file = config_dir.join("config.json");
 
   let mut cfg: serde_json::Value = if file.exists() {
-    let content =
-      std::fs::read_to_string(&file).map_err(|e| format!("Failed to read config.json: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config.json: {}", e))?
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| CommandError::config("Failed to read config.json", e))?;
+    serde_json::from_str(&content)
+      .map_err(|e| CommandError::config("Failed to parse config.json", e))?
   } else {
     serde_json::json!({})
   };
 
   cfg["auto_update_data"] = serde_json::json!(value);
 
-  let data =
-    serde_json::to_string_pretty(&cfg).map_err(|e| format!("Failed to serialize config: {}", e))?;
-  std::fs::write(&file, data).map_err(|e| format!("Failed to write config.json: {}", e))?;
+  let data = serde_json::to_string_pretty(&cfg)
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
 
   Ok(())
 }
 
 // Command to set whether the app should start hidden (remember last tray state)
 #[tauri::command]
-pub async fn set_start_hidden(app: tauri::AppHandle, value: bool) -> Result<(), String> {
+pub async fn set_start_hidden(app: tauri::AppHandle, value: bool) -> Result<(), CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
   let file = config_dir.join("config.json");
 
   let mut cfg: serde_json::Value = if file.exists() {
-    let content =
-      std::fs::read_to_string(&file).map_err(|e| format!("Failed to read config.json: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config.json: {}", e))?
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| CommandError::config("Failed to read config.json", e))?;
+    serde_json::from_str(&content)
+      .map_err(|e| CommandError::config("Failed to parse config.json", e))?
   } else {
     serde_json::json!({})
   };
 
   cfg["start_hidden"] = serde_json::json!(value);
 
-  let data =
-    serde_json::to_string_pretty(&cfg).map_err(|e| format!("Failed to serialize config: {}", e))?;
-  std::fs::write(&file, data).map_err(|e| format!("Failed to write config.json: {}", e))?;
+  let data = serde_json::to_string_pretty(&cfg)
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
 
   Ok(())
 }
 
 // Command to get whether the app should start hidden (remember last tray state)
 #[tauri::command]
-pub async fn get_start_hidden(app: tauri::AppHandle) -> Result<bool, String> {
+pub async fn get_start_hidden(app: tauri::AppHandle) -> Result<bool, CommandError> {
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   let file = config_dir.join("config.json");
 
@@ -482,11 +515,11 @@ pub async fn get_start_hidden(app: tauri::AppHandle) -> Result<bool, String> {
     return Ok(false);
   }
 
-  let content =
-    std::fs::read_to_string(&file).map_err(|e| format!("Failed to read config.json: {}", e))?;
+  let content = std::fs::read_to_string(&file)
+    .map_err(|e| CommandError::config("Failed to read config.json", e))?;
 
-  let cfg: serde_json::Value =
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config.json: {}", e))?;
+  let cfg: serde_json::Value = serde_json::from_str(&content)
+    .map_err(|e| CommandError::config("Failed to parse config.json", e))?;
 
   let v = cfg
     .get("start_hidden")
@@ -501,31 +534,33 @@ pub async fn get_start_hidden(app: tauri::AppHandle) -> Result<bool, String> {
 pub async fn set_manual_injection_mode(
   app: tauri::AppHandle,
   value: bool,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   println!("[Config] Setting manual_injection_mode to: {}", value);
-  
+
   let config_dir = app
     .path()
     .app_data_dir()
-    .map_err(|e| format!("Failed to get app data dir: {}", e))?
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
     .join("config");
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
   let file = config_dir.join("config.json");
 
   let mut cfg: serde_json::Value = if file.exists() {
-    let content =
-      std::fs::read_to_string(&file).map_err(|e| format!("Failed to read config.json: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config.json: {}", e))?
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| CommandError::config("Failed to read config.json", e))?;
+    serde_json::from_str(&content)
+      .map_err(|e| CommandError::config("Failed to parse config.json", e))?
   } else {
     serde_json::json!({})
   };
 
   cfg["manual_injection_mode"] = serde_json::json!(value);
 
-  let data =
-    serde_json::to_string_pretty(&cfg).map_err(|e| format!("Failed to serialize config: {}", e))?;
-  std::fs::write(&file, data).map_err(|e| format!("Failed to write config.json: {}", e))?;
+  let data = serde_json::to_string_pretty(&cfg)
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
 
   // If manual mode is being enabled, stop any active automatic injection
   if value {
@@ -544,7 +579,223 @@ pub async fn set_manual_injection_mode(
 
 // Command to get manual injection mode preference
 #[tauri::command]
-pub async fn get_manual_injection_mode(app: tauri::AppHandle) -> Result<bool, String> {
+pub async fn get_manual_injection_mode(app: tauri::AppHandle) -> Result<bool, CommandError> {
   let config = load_config(app).await?;
   Ok(config.manual_injection_mode)
-}
\ No newline at end of file
+}
+// Helper to read the saved overlay conflict choices, keyed by parsed path hash
+pub fn get_conflict_winners_from_config(
+  app_handle: &AppHandle,
+) -> std::collections::HashMap<u64, String> {
+  let mut winners = std::collections::HashMap::new();
+  if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+    let config_file = app_data_dir.join("config").join("config.json");
+    if let Ok(content) = fs::read_to_string(&config_file) {
+      if let Ok(config) = serde_json::from_str::<SavedConfig>(&content) {
+        for (hash, mod_name) in config.conflict_winners {
+          if let Some(parsed) = crate::injection::overlay::parse_path_hash(&hash) {
+            winners.insert(parsed, mod_name);
+          }
+        }
+      }
+    }
+  }
+  winners
+}
+
+// Command to choose which mod wins a conflicting WAD entry (None clears the choice)
+#[tauri::command]
+pub async fn set_conflict_winner(
+  app: tauri::AppHandle,
+  path_hash: String,
+  mod_name: Option<String>,
+) -> Result<(), CommandError> {
+  let parsed = crate::injection::overlay::parse_path_hash(&path_hash).ok_or_else(|| {
+    CommandError::new(
+      ErrorCode::InvalidArgument,
+      format!("Invalid path hash: {}", path_hash),
+    )
+    .with_context("path_hash", &path_hash)
+  })?;
+  let key = crate::injection::overlay::format_path_hash(parsed);
+
+  let config_dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
+    .join("config");
+  std::fs::create_dir_all(&config_dir)
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
+  let file = config_dir.join("config.json");
+
+  let mut cfg: serde_json::Value = if file.exists() {
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| CommandError::config("Failed to read config.json", e))?;
+    serde_json::from_str(&content)
+      .map_err(|e| CommandError::config("Failed to parse config.json", e))?
+  } else {
+    serde_json::json!({})
+  };
+
+  if !cfg
+    .get("conflict_winners")
+    .map(|v| v.is_object())
+    .unwrap_or(false)
+  {
+    cfg["conflict_winners"] = serde_json::json!({});
+  }
+  let winners = cfg["conflict_winners"].as_object_mut().unwrap();
+  match mod_name {
+    Some(name) => {
+      winners.insert(key, serde_json::json!(name));
+    }
+    None => {
+      winners.remove(&key);
+    }
+  }
+
+  let data = serde_json::to_string_pretty(&cfg)
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
+
+  Ok(())
+}
+
+// Helper to read the overlay load order (lowest layer first) for the injector
+pub fn get_load_order_from_config(app_handle: &AppHandle) -> Vec<String> {
+  if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+    let config_file = app_data_dir.join("config").join("config.json");
+    if let Ok(content) = fs::read_to_string(&config_file) {
+      if let Ok(config) = serde_json::from_str::<SavedConfig>(&content) {
+        return config.load_order;
+      }
+    }
+  }
+  crate::commands::types::default_load_order()
+}
+
+// Helper to read the archive extraction limits for the injector
+pub fn get_extraction_limits_from_config(
+  app_handle: &AppHandle,
+) -> crate::injection::skin_file::ExtractionLimits {
+  if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+    let config_file = app_data_dir.join("config").join("config.json");
+    if let Ok(content) = fs::read_to_string(&config_file) {
+      if let Ok(config) = serde_json::from_str::<SavedConfig>(&content) {
+        return config.extraction_limits;
+      }
+    }
+  }
+  Default::default()
+}
+
+// Helper to read whether the overlay should stay up between games in automatic mode
+pub fn is_persistent_overlay_enabled(app_handle: &AppHandle) -> bool {
+  if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+    let config_file = app_data_dir.join("config").join("config.json");
+    if let Ok(content) = fs::read_to_string(&config_file) {
+      if let Ok(config) = serde_json::from_str::<SavedConfig>(&content) {
+        return config.persistent_overlay;
+      }
+    }
+  }
+  false
+}
+
+// Command to keep the overlay running between games in automatic mode
+#[tauri::command]
+pub async fn set_persistent_overlay(
+  app: tauri::AppHandle,
+  value: bool,
+) -> Result<(), CommandError> {
+  let config_dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
+    .join("config");
+  std::fs::create_dir_all(&config_dir)
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
+  let file = config_dir.join("config.json");
+
+  let mut cfg: serde_json::Value = if file.exists() {
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| CommandError::config("Failed to read config.json", e))?;
+    serde_json::from_str(&content)
+      .map_err(|e| CommandError::config("Failed to parse config.json", e))?
+  } else {
+    serde_json::json!({})
+  };
+
+  cfg["persistent_overlay"] = serde_json::json!(value);
+
+  let data = serde_json::to_string_pretty(&cfg)
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
+
+  Ok(())
+}
+
+// Command to get whether the overlay stays running between games in automatic mode
+#[tauri::command]
+pub async fn get_persistent_overlay(app: tauri::AppHandle) -> Result<bool, CommandError> {
+  let config = load_config(app).await?;
+  Ok(config.persistent_overlay)
+}
+
+// Command to get the overlay load order by mod category, lowest layer first
+#[tauri::command]
+pub async fn get_load_order(app: tauri::AppHandle) -> Result<Vec<String>, CommandError> {
+  let config = load_config(app).await?;
+  Ok(config.load_order)
+}
+
+// Command to reorder overlay layering. Categories are "skin", "custom_skin" and misc item
+// types ("map", "font", "hud", "misc"); later entries are layered on top.
+#[tauri::command]
+pub async fn set_load_order(
+  app: tauri::AppHandle,
+  categories: Vec<String>,
+) -> Result<(), CommandError> {
+  let mut order: Vec<String> = Vec::new();
+  for category in categories {
+    let category = category.trim().to_lowercase();
+    if !category.is_empty() && !order.contains(&category) {
+      order.push(category);
+    }
+  }
+  if order.is_empty() {
+    return Err(CommandError::new(
+      ErrorCode::InvalidArgument,
+      "Load order cannot be empty",
+    ));
+  }
+
+  let config_dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| CommandError::config("Failed to get app data dir", e))?
+    .join("config");
+  std::fs::create_dir_all(&config_dir)
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
+  let file = config_dir.join("config.json");
+
+  let mut cfg: serde_json::Value = if file.exists() {
+    let content = std::fs::read_to_string(&file)
+      .map_err(|e| CommandError::config("Failed to read config.json", e))?;
+    serde_json::from_str(&content)
+      .map_err(|e| CommandError::config("Failed to parse config.json", e))?
+  } else {
+    serde_json::json!({})
+  };
+
+  cfg["load_order"] = serde_json::json!(order);
+
+  let data = serde_json::to_string_pretty(&cfg)
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
+  std::fs::write(&file, data)
+    .map_err(|e| CommandError::config("Failed to write config.json", e))?;
+
+  Ok(())
+}
@@ -1,4 +1,6 @@
 use crate::commands::champion_data::get_champion_name;
+use crate::commands::config::get_extraction_limits_from_config;
+use crate::commands::file_operations::ensure_valid_skin_package;
 use crate::commands::types::CustomSkinData;
 use serde_json;
 #[cfg(target_os = "windows")]
@@ -82,6 +84,10 @@ pub async fn upload_custom_skin(
 
   println!("Selected file: {}", file_path);
 
+  // Reject packages that could never be injected before copying them in
+  let limits = get_extraction_limits_from_config(&app);
+  ensure_valid_skin_package(std::path::Path::new(&file_path), &limits)?;
+
   // Get the app data directory
   let app_data_dir = app
     .path()
@@ -275,9 +281,18 @@ pub async fn upload_multiple_custom_skins(
     .map_err(|e| format!("Failed to create champion directory: {}", e))?;
 
   let mut uploaded_skins = Vec::new();
+  let mut rejected = Vec::new();
 
   // Process each selected file
+  let limits = get_extraction_limits_from_config(&app);
   for file_path in files {
+    // Skip invalid packages instead of failing the whole batch
+    if let Err(e) = ensure_valid_skin_package(&file_path, &limits) {
+      println!("Skipping custom skin: {}", e);
+      rejected.push(e);
+      continue;
+    }
+
     let file_name = file_path
       .file_stem()
       .and_then(|s| s.to_str())
@@ -332,6 +347,10 @@ pub async fn upload_multiple_custom_skins(
     uploaded_skins.push(custom_skin);
   }
 
+  if uploaded_skins.is_empty() && !rejected.is_empty() {
+    return Err(rejected.join("; "));
+  }
+
   println!(
     "Successfully uploaded {} custom skins for champion {}",
     uploaded_skins.len(),
@@ -1,3 +1,4 @@
+use crate::commands::error::{CommandError, ErrorCode, RemediationAction};
 use futures_util::StreamExt;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -118,11 +119,10 @@ static HTTP_CLIENT: Lazy<Arc<reqwest::Client>> = Lazy::new(|| {
   )
 });
 
-async fn champions_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-  let app_data_dir = app
-    .path()
-    .app_data_dir()
-    .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+async fn champions_dir(app: &tauri::AppHandle) -> Result<PathBuf, CommandError> {
+  let app_data_dir = app.path().app_data_dir().map_err(|e| {
+    CommandError::caused(ErrorCode::FileSystem, "Failed to get app data directory", e)
+  })?;
   Ok(app_data_dir.join("champions"))
 }
 
@@ -134,7 +134,7 @@ pub async fn download_file_to_champion_with_progress(
   url: String,
   champion_name: String,
   file_name: String,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
   let id = Uuid::new_v4().to_string();
   let category = "skin".to_string();
 
@@ -170,24 +170,32 @@ pub async fn download_file_to_champion_with_progress(
   // Prepare destination
   let champions_root = champions_dir(&app).await?;
   let champion_dir = champions_root.join(&champion_name);
-  async_fs::create_dir_all(&champion_dir)
-    .await
-    .map_err(|e| format!("Failed to create champion directory: {}", e))?;
+  async_fs::create_dir_all(&champion_dir).await.map_err(|e| {
+    CommandError::from(e)
+      .prefixed("Failed to create champion directory")
+      .with_context("path", champion_dir.display())
+  })?;
   let file_path = champion_dir.join(&file_name);
 
   // Perform the download
   let result = async {
-    let response = client
-      .get(&url)
-      .send()
-      .await
-      .map_err(|e| format!("Failed to start download: {}", e))?;
-
-    if !response.status().is_success() {
-      return Err(format!(
-        "Download failed with status: {}",
-        response.status()
-      ));
+    let response = client.get(&url).send().await.map_err(|e| {
+      CommandError::caused(ErrorCode::DownloadFailed, "Failed to start download", e)
+        .with_remediation(RemediationAction::CheckConnection)
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+      // Not every skin exists in the repo, the frontend tells these apart by code
+      let code = if status == reqwest::StatusCode::NOT_FOUND {
+        ErrorCode::DownloadNotFound
+      } else {
+        ErrorCode::DownloadFailed
+      };
+      return Err(
+        CommandError::new(code, format!("Download failed with status: {}", status))
+          .with_context("status", status.as_u16()),
+      );
     }
 
     let total = response.content_length();
@@ -196,7 +204,7 @@ pub async fn download_file_to_champion_with_progress(
     // Use 1MB buffer for better I/O performance
     let file = async_fs::File::create(&file_path)
       .await
-      .map_err(|e| format!("Failed to create destination file: {}", e))?;
+      .map_err(|e| CommandError::from(e).prefixed("Failed to create destination file"))?;
     let mut file = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_SIZE, file);
 
     let mut downloaded: u64 = 0;
@@ -223,16 +231,19 @@ pub async fn download_file_to_champion_with_progress(
     loop {
       tokio::select! {
         _ = cancel.cancelled() => {
-          return Err("canceled".into());
+          return Err(CommandError::new(ErrorCode::Cancelled, "canceled"));
         }
         maybe_chunk = stream.next() => {
           match maybe_chunk {
             Some(chunk) => {
-              let bytes = chunk.map_err(|e| format!("Download stream error: {}", e))?;
+              let bytes = chunk.map_err(|e| {
+                CommandError::caused(ErrorCode::DownloadFailed, "Download stream error", e)
+                  .with_remediation(RemediationAction::CheckConnection)
+              })?;
               file
                 .write_all(&bytes)
                 .await
-                .map_err(|e| format!("Failed to write chunk: {}", e))?;
+                .map_err(|e| CommandError::from(e).prefixed("Failed to write chunk"))?;
 
               downloaded += bytes.len() as u64;
 
@@ -268,12 +279,12 @@ pub async fn download_file_to_champion_with_progress(
     file
       .flush()
       .await
-      .map_err(|e| format!("Failed to finalize file: {}", e))?;
+      .map_err(|e| CommandError::from(e).prefixed("Failed to finalize file"))?;
     
     // Skip sync_all for speed - OS will handle eventual sync
     // This significantly improves download performance
 
-    Ok::<(), String>(())
+    Ok::<(), CommandError>(())
   }
   .await;
 
@@ -299,7 +310,7 @@ pub async fn download_file_to_champion_with_progress(
       TASKS.lock().await.remove(&id);
       Ok(id)
     }
-    Err(err) if err == "canceled" => {
+    Err(err) if err.code == ErrorCode::Cancelled => {
       // remove partial file if present
       let _ = async_fs::remove_file(&file_path).await;
       emit(
@@ -319,11 +330,14 @@ pub async fn download_file_to_champion_with_progress(
         },
       );
       TASKS.lock().await.remove(&id);
-      Err("Download canceled".into())
+      Err(CommandError::new(ErrorCode::Cancelled, "Download canceled"))
     }
     Err(err) => {
       // remove partial file if present
       let _ = async_fs::remove_file(&file_path).await;
+      let err = err
+        .with_context("url", &url)
+        .with_context("path", file_path.display());
       emit(
         &app,
         DownloadProgressPayload {
@@ -337,7 +351,7 @@ pub async fn download_file_to_champion_with_progress(
           champion_name: Some(champion_name),
           file_name: Some(file_name),
           dest_path: Some(file_path.to_string_lossy().to_string()),
-          error: Some(err.clone()),
+          error: Some(err.message.clone()),
         },
       );
       TASKS.lock().await.remove(&id);
@@ -347,7 +361,7 @@ pub async fn download_file_to_champion_with_progress(
 }
 
 #[tauri::command]
-pub async fn cancel_download(id: String) -> Result<bool, String> {
+pub async fn cancel_download(id: String) -> Result<bool, CommandError> {
   let tasks = TASKS.clone();
   let map = tasks.lock().await;
   if let Some(ctrl) = map.get(&id) {
@@ -389,9 +403,14 @@ pub async fn download_skin_by_id(
   form_id: Option<u32>,
   champion_name: String,
   file_name: String,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
   let url = build_skin_download_url(champion_id, skin_id, chroma_id, form_id);
-  download_file_to_champion_with_progress(app, url, champion_name, file_name).await
+  download_file_to_champion_with_progress(app, url, champion_name, file_name)
+    .await
+    .map_err(|e| {
+      e.with_context("champion_id", champion_id)
+        .with_context("skin_id", skin_id)
+    })
 }
 
 /// High-performance batch download for multiple skins
@@ -400,7 +419,7 @@ pub async fn download_skin_by_id(
 pub async fn batch_download_skins(
   app: tauri::AppHandle,
   requests: Vec<SkinDownloadRequest>,
-) -> Result<BatchDownloadResult, String> {
+) -> Result<BatchDownloadResult, CommandError> {
   let batch_id = Uuid::new_v4().to_string();
   let total_items = requests.len();
   let started = Instant::now();
@@ -531,7 +550,7 @@ pub async fn batch_download_skins(
         }
         Err(e) => {
           failed_count_clone.fetch_add(1, Ordering::Relaxed);
-          failed_clone.lock().await.push((item_id, e));
+          failed_clone.lock().await.push((item_id, e.message));
         }
       }
     });
@@ -595,15 +614,20 @@ async fn download_with_progress(
   cancel: CancellationToken,
   downloaded_bytes: Arc<AtomicU64>,
   total_bytes: Arc<AtomicU64>,
-) -> Result<u64, String> {
-  let response = client
-    .get(url)
-    .send()
-    .await
-    .map_err(|e| format!("Request failed: {}", e))?;
-
-  if !response.status().is_success() {
-    return Err(format!("HTTP {}", response.status()));
+) -> Result<u64, CommandError> {
+  let response = client.get(url).send().await.map_err(|e| {
+    CommandError::caused(ErrorCode::DownloadFailed, "Request failed", e)
+      .with_remediation(RemediationAction::CheckConnection)
+  })?;
+
+  let status = response.status();
+  if !status.is_success() {
+    let code = if status == reqwest::StatusCode::NOT_FOUND {
+      ErrorCode::DownloadNotFound
+    } else {
+      ErrorCode::DownloadFailed
+    };
+    return Err(CommandError::new(code, format!("HTTP {}", status)).with_context("url", url));
   }
 
   let content_length = response.content_length().unwrap_or(0);
@@ -612,7 +636,7 @@ async fn download_with_progress(
   let mut stream = response.bytes_stream();
   let file = async_fs::File::create(file_path)
     .await
-    .map_err(|e| format!("Failed to create file: {}", e))?;
+    .map_err(|e| CommandError::from(e).prefixed("Failed to create file"))?;
   // Use 1MB buffer for better I/O throughput
   let mut file = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_SIZE, file);
 
@@ -627,12 +651,15 @@ async fn download_with_progress(
       _ = cancel.cancelled() => {
         drop(file); // Drop writer before removing file
         let _ = async_fs::remove_file(file_path).await;
-        return Err("Canceled".into());
+        return Err(CommandError::new(ErrorCode::Cancelled, "Canceled"));
       }
       maybe_chunk = stream.next() => {
         match maybe_chunk {
           Some(Ok(bytes)) => {
-            file.write_all(&bytes).await.map_err(|e| format!("Write error: {}", e))?;
+            file
+              .write_all(&bytes)
+              .await
+              .map_err(|e| CommandError::from(e).prefixed("Write error"))?;
             let len = bytes.len() as u64;
             item_downloaded += len;
             pending_bytes += len;
@@ -646,7 +673,10 @@ async fn download_with_progress(
           Some(Err(e)) => {
             drop(file);
             let _ = async_fs::remove_file(file_path).await;
-            return Err(format!("Stream error: {}", e));
+            return Err(
+              CommandError::caused(ErrorCode::DownloadFailed, "Stream error", e)
+                .with_remediation(RemediationAction::CheckConnection),
+            );
           }
           None => break,
         }
@@ -659,7 +689,10 @@ async fn download_with_progress(
     downloaded_bytes.fetch_add(pending_bytes, Ordering::Relaxed);
   }
 
-  file.flush().await.map_err(|e| format!("Flush error: {}", e))?;
+  file
+    .flush()
+    .await
+    .map_err(|e| CommandError::from(e).prefixed("Flush error"))?;
   // Skip sync_all for speed - OS will handle eventual sync
   // file.into_inner().sync_all().await.map_err(|e| format!("Sync error: {}", e))?;
 
@@ -673,15 +706,14 @@ pub async fn check_skin_exists(
   skin_id: u32,
   chroma_id: Option<u32>,
   form_id: Option<u32>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
   let url = build_skin_download_url(champion_id, skin_id, chroma_id, form_id);
   let client = HTTP_CLIENT.clone();
 
-  let response = client
-    .head(&url)
-    .send()
-    .await
-    .map_err(|e| format!("Request failed: {}", e))?;
+  let response = client.head(&url).send().await.map_err(|e| {
+    CommandError::caused(ErrorCode::DownloadFailed, "Request failed", e)
+      .with_remediation(RemediationAction::CheckConnection)
+  })?;
 
   Ok(response.status().is_success())
 }
@@ -693,15 +725,14 @@ pub async fn get_skin_file_size(
   skin_id: u32,
   chroma_id: Option<u32>,
   form_id: Option<u32>,
-) -> Result<Option<u64>, String> {
+) -> Result<Option<u64>, CommandError> {
   let url = build_skin_download_url(champion_id, skin_id, chroma_id, form_id);
   let client = HTTP_CLIENT.clone();
 
-  let response = client
-    .head(&url)
-    .send()
-    .await
-    .map_err(|e| format!("Request failed: {}", e))?;
+  let response = client.head(&url).send().await.map_err(|e| {
+    CommandError::caused(ErrorCode::DownloadFailed, "Request failed", e)
+      .with_remediation(RemediationAction::CheckConnection)
+  })?;
 
   if !response.status().is_success() {
     return Ok(None);
@@ -712,6 +743,6 @@ pub async fn get_skin_file_size(
 
 /// Cancel a batch download
 #[tauri::command]
-pub async fn cancel_batch_download(batch_id: String) -> Result<bool, String> {
+pub async fn cancel_batch_download(batch_id: String) -> Result<bool, CommandError> {
   cancel_download(batch_id).await
 }
@@ -0,0 +1,235 @@
+use crate::injection::InjectionError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+
+// Errors returned by commands and emitted on skin-injection-error
+//
+// A CommandError carries a stable code the frontend can branch on, the message to show, the
+// paths and ids it concerns, and what the user can do about it. Code that still produces
+// String errors converts with `?` and ends up as ErrorCode::Internal.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+  ModToolsMissing,
+  InvalidGamePath,
+  SkinFileMissing,
+  OverlayDirLocked,
+  PermissionDenied,
+  FileSystem,
+  ProcessFailed,
+  Config,
+  InvalidArgument,
+  Timeout,
+  Cancelled,
+  Superseded,
+  ArchiveInvalid,
+  ArchiveLimitExceeded,
+  DownloadFailed,
+  DownloadNotFound,
+  LeagueClientUnavailable,
+  PartyMode,
+  Internal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemediationAction {
+  InstallModTools,
+  SelectGameDirectory,
+  RedownloadSkin,
+  AddAntivirusExclusion,
+  RaiseExtractionLimits,
+  CheckConnection,
+  StartLeagueClient,
+}
+
+impl RemediationAction {
+  pub fn hint(self) -> &'static str {
+    match self {
+      Self::InstallModTools => {
+        "Install CSLOL Manager or reinstall osskins to restore mod-tools.exe"
+      }
+      Self::SelectGameDirectory => "Select your League of Legends installation folder",
+      Self::RedownloadSkin => "Download the skin again",
+      Self::AddAntivirusExclusion => {
+        "Add the osskins data folder to your antivirus exclusions and try again"
+      }
+      Self::RaiseExtractionLimits => {
+        "Raise extraction_limits in config.json if you trust the archive"
+      }
+      Self::CheckConnection => "Check your internet connection and try again",
+      Self::StartLeagueClient => "Start the League client and log in",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Remediation {
+  pub action: RemediationAction,
+  pub hint: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandError {
+  pub code: ErrorCode,
+  pub message: String,
+  // Paths, ids and other values the error is about
+  #[serde(default)]
+  pub context: BTreeMap<String, String>,
+  #[serde(default)]
+  pub remediation: Option<Remediation>,
+}
+
+impl CommandError {
+  pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+    Self {
+      code,
+      message: message.into(),
+      context: BTreeMap::new(),
+      remediation: None,
+    }
+  }
+
+  // `what` failed because of `cause`
+  pub fn caused(code: ErrorCode, what: &str, cause: impl fmt::Display) -> Self {
+    Self::new(code, format!("{}: {}", what, cause))
+  }
+
+  // Reading or writing the saved configuration failed
+  pub fn config(what: &str, cause: impl fmt::Display) -> Self {
+    Self::caused(ErrorCode::Config, what, cause)
+  }
+
+  pub fn with_context(mut self, key: &str, value: impl ToString) -> Self {
+    self.context.insert(key.to_string(), value.to_string());
+    self
+  }
+
+  pub fn with_remediation(mut self, action: RemediationAction) -> Self {
+    self.remediation = Some(Remediation {
+      action,
+      hint: action.hint().to_string(),
+    });
+    self
+  }
+
+  // Put `prefix` in front of the message, e.g. "Injection failed"
+  pub fn prefixed(mut self, prefix: &str) -> Self {
+    self.message = format!("{}: {}", prefix, self.message);
+    self
+  }
+
+  fn with_message(mut self, message: String) -> Self {
+    self.message = message;
+    self
+  }
+}
+
+impl fmt::Display for CommandError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<InjectionError> for CommandError {
+  fn from(err: InjectionError) -> Self {
+    let message = err.to_string();
+    match err {
+      InjectionError::IoError(e) => Self::from(e).with_message(message),
+      InjectionError::InvalidGamePath(_) => Self::new(ErrorCode::InvalidGamePath, message)
+        .with_remediation(RemediationAction::SelectGameDirectory),
+      InjectionError::MissingFantomeFile(_) => Self::new(ErrorCode::SkinFileMissing, message)
+        .with_remediation(RemediationAction::RedownloadSkin),
+      InjectionError::ProcessError(_) => Self::new(ErrorCode::ProcessFailed, message),
+      InjectionError::ModToolsMissing(_) => Self::new(ErrorCode::ModToolsMissing, message)
+        .with_remediation(RemediationAction::InstallModTools),
+      InjectionError::OverlayDirLocked(_) => Self::new(ErrorCode::OverlayDirLocked, message)
+        .with_remediation(RemediationAction::AddAntivirusExclusion),
+      InjectionError::ConfigError(_) => Self::new(ErrorCode::Config, message),
+      InjectionError::Timeout(_) => Self::new(ErrorCode::Timeout, message),
+      InjectionError::Aborted(_) => Self::new(ErrorCode::Cancelled, message),
+      InjectionError::Superseded(_) => Self::new(ErrorCode::Superseded, message),
+      InjectionError::WalkdirError(_) => Self::new(ErrorCode::FileSystem, message),
+      InjectionError::ZipError(_) => Self::new(ErrorCode::ArchiveInvalid, message)
+        .with_remediation(RemediationAction::RedownloadSkin),
+      InjectionError::ArchiveLimitExceeded(_) => {
+        Self::new(ErrorCode::ArchiveLimitExceeded, message)
+          .with_remediation(RemediationAction::RaiseExtractionLimits)
+      }
+    }
+  }
+}
+
+impl From<io::Error> for CommandError {
+  fn from(err: io::Error) -> Self {
+    match err.kind() {
+      // On Windows this is most often an antivirus holding the file
+      io::ErrorKind::PermissionDenied => Self::new(ErrorCode::PermissionDenied, err.to_string())
+        .with_remediation(RemediationAction::AddAntivirusExclusion),
+      _ => Self::new(ErrorCode::FileSystem, err.to_string()),
+    }
+  }
+}
+
+impl From<String> for CommandError {
+  fn from(message: String) -> Self {
+    Self::new(ErrorCode::Internal, message)
+  }
+}
+
+impl From<&str> for CommandError {
+  fn from(message: &str) -> Self {
+    Self::new(ErrorCode::Internal, message)
+  }
+}
+
+// For helpers that still return String errors
+impl From<CommandError> for String {
+  fn from(err: CommandError) -> Self {
+    err.message
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn injection_errors_keep_their_message_and_get_a_code() {
+    let err = CommandError::from(InjectionError::ModToolsMissing("not found".into()));
+    assert_eq!(err.code, ErrorCode::ModToolsMissing);
+    assert_eq!(err.message, "mod-tools unavailable: not found");
+    assert_eq!(
+      err.remediation.map(|r| r.action),
+      Some(RemediationAction::InstallModTools)
+    );
+
+    let denied = io::Error::new(io::ErrorKind::PermissionDenied, "access denied");
+    let err = CommandError::from(InjectionError::IoError(denied)).prefixed("Injection failed");
+    assert_eq!(err.code, ErrorCode::PermissionDenied);
+    assert_eq!(err.message, "Injection failed: IO Error: access denied");
+  }
+
+  #[test]
+  fn serializes_for_the_frontend() {
+    let err = CommandError::from(InjectionError::MissingFantomeFile("ahri.zip".into()))
+      .with_context("champion_id", 103);
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(json["code"], "skin_file_missing");
+    assert_eq!(json["context"]["champion_id"], "103");
+    assert_eq!(json["remediation"]["action"], "redownload_skin");
+    assert_eq!(
+      json["remediation"]["hint"],
+      RemediationAction::RedownloadSkin.hint()
+    );
+
+    let plain = serde_json::to_value(CommandError::from("boom")).unwrap();
+    assert_eq!(plain["code"], "internal");
+    assert!(plain["remediation"].is_null());
+  }
+}
@@ -1,5 +1,9 @@
+use crate::commands::config::get_extraction_limits_from_config;
+use crate::injection::package::{validate_skin_package as inspect_skin_package, PackageReport};
+use crate::injection::skin_file::ExtractionLimits;
 use futures_util::StreamExt;
 use once_cell::sync::Lazy;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::Manager;
@@ -23,6 +27,44 @@ static HTTP_CLIENT: Lazy<Arc<reqwest::Client>> = Lazy::new(|| {
   )
 });
 
+/// Inspect a skin package without installing it
+#[tauri::command]
+pub async fn validate_skin_package(
+  app: tauri::AppHandle,
+  path: String,
+) -> Result<PackageReport, String> {
+  let limits = get_extraction_limits_from_config(&app);
+  tokio::task::spawn_blocking(move || {
+    inspect_skin_package(Path::new(&path), &limits)
+      .map_err(|e| format!("Failed to read {}: {}", path, e))
+  })
+  .await
+  .map_err(|e| format!("Validation task failed: {}", e))?
+}
+
+/// Validate a package before accepting it, turning a failed report into an error message
+pub(crate) fn ensure_valid_skin_package(
+  path: &Path,
+  limits: &ExtractionLimits,
+) -> Result<PackageReport, String> {
+  let report = inspect_skin_package(path, limits)
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  if !report.is_acceptable() {
+    return Err(format!(
+      "{} is not a valid skin package: {}",
+      path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string()),
+      report.error_summary()
+    ));
+  }
+  for warning in &report.warnings {
+    println!("[Package] {}: {}", path.display(), warning);
+  }
+  Ok(report)
+}
+
 #[tauri::command]
 /// Save a skin ZIP file to the champions directory
 pub async fn save_zip_file(
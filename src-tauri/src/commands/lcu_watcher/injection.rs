@@ -2,15 +2,209 @@
 
 use serde_json;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
 use super::utils::is_in_champ_select;
+use crate::commands::config::get_extraction_limits_from_config;
+use crate::commands::error::CommandError;
+use crate::commands::file_operations::ensure_valid_skin_package;
 use crate::commands::misc_items::get_selected_misc_items;
-use crate::commands::party_mode::RECEIVED_SKINS;
+use crate::commands::party_mode::{
+  record_rejected_skin_path, InMemoryReceivedSkin, RECEIVED_SKINS,
+};
 use crate::commands::types::SavedConfig;
 use crate::commands::skin_injection::{record_injection_state, InjectionStatusValue};
-use crate::injection::Skin;
+use crate::injection::path_policy::SkinPathPolicy;
+use crate::injection::skin_file::ExtractionLimits;
+use crate::injection::{InjectionError, InjectionTrigger, Skin};
+
+// Friend skins resolve to local files; only inject ones inside our skin directories that
+// pass package validation
+fn accept_received_package(
+  policy: &SkinPathPolicy,
+  limits: &ExtractionLimits,
+  path: &Path,
+  received_skin: &InMemoryReceivedSkin,
+) -> bool {
+  if let Err(reason) = policy.check(path) {
+    record_rejected_skin_path(received_skin, path, reason);
+    return false;
+  }
+  match ensure_valid_skin_package(path, limits) {
+    Ok(_) => true,
+    Err(e) => {
+      println!(
+        "[Party Mode] Rejecting skin from {}: {}",
+        received_skin.from_summoner_name, e
+      );
+      false
+    }
+  }
+}
+
+// Existing files `src` may refer to inside `dir`: the relative path itself, then by basename
+fn dir_variants(dir: &Path, src: &Path) -> Vec<PathBuf> {
+  let mut candidates = Vec::new();
+  if !src.is_absolute() {
+    candidates.push(dir.join(src));
+  }
+  if let Some(name) = src.file_name() {
+    candidates.push(dir.join(name));
+    if let Some(stem) = Path::new(name).file_stem().and_then(|s| s.to_str()) {
+      for ext in ["zip", "skin_file"] {
+        candidates.push(dir.join(format!("{}.{}", stem, ext)));
+      }
+    }
+  }
+  candidates.retain(|p| p.exists());
+  candidates
+}
+
+// Files under `dir` and its direct subdirectories whose stem is `stem`
+fn shallow_matches(dir: &Path, stem: &str) -> Vec<PathBuf> {
+  let matches_stem = |path: &Path| {
+    path.is_file()
+      && path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|s| s.eq_ignore_ascii_case(stem))
+  };
+  let mut found = Vec::new();
+  for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+    let path = entry.path();
+    if path.is_dir() {
+      for inner in std::fs::read_dir(&path).into_iter().flatten().flatten() {
+        if matches_stem(&inner.path()) {
+          found.push(inner.path());
+        }
+      }
+    } else if matches_stem(&path) {
+      found.push(path);
+    }
+  }
+  found
+}
+
+// Local file for a skin a friend shared: their path first (as is, portable or mapped under
+// champions/), then our own selection for the same skin, then anything we use for that
+// champion. Every candidate goes through `accept` with the policy it has to meet, so a refused
+// path falls through to the next step instead of dropping the skin. Paths derived from the
+// friend's are held to `policy`; our own selections may also live in League's ASSETS/Skins,
+// which stays the last-resort fallback for them.
+fn resolve_received_skin_path(
+  received_skin: &InMemoryReceivedSkin,
+  requested: &str,
+  config: &SavedConfig,
+  champions_dir: &Path,
+  assets_skins_dir: &Path,
+  policy: &SkinPathPolicy,
+  accept: impl Fn(&Path, &SkinPathPolicy) -> bool,
+) -> Option<PathBuf> {
+  let local_policy = policy.clone().with_root(assets_skins_dir);
+  let accepted_by = |policy: &SkinPathPolicy, candidates: Vec<PathBuf>, how: &str| {
+    let found = candidates.into_iter().find(|p| accept(p, policy))?;
+    println!("[Party Mode][DEBUG] {}: {}", how, found.display());
+    Some(found)
+  };
+  let accepted = |candidates: Vec<PathBuf>, how: &str| accepted_by(policy, candidates, how);
+  let fp_norm = requested.replace('\\', "/");
+  let fp = PathBuf::from(requested);
+  let fp_rel = PathBuf::from(fp_norm.trim_start_matches('/'));
+
+  // 1) Direct absolute path
+  if fp.is_absolute() && fp.exists() {
+    if let Some(p) = accepted(vec![fp.clone()], "Using absolute skin_file from friend") {
+      return Some(p);
+    }
+  }
+
+  // 2) Portable prefix (/ezrea/...) or any other rooted path, under champions_dir
+  let mapped = match policy.map_portable(requested) {
+    Some(mapped) => {
+      let mut candidates = vec![mapped.clone()];
+      if let Some(base) = mapped.file_name() {
+        candidates.push(champions_dir.join(base));
+      }
+      candidates
+    }
+    None if fp_norm.starts_with('/') => vec![champions_dir.join(&fp_rel)],
+    None => Vec::new(),
+  };
+  let mapped = mapped.into_iter().filter(|p| p.exists()).collect();
+  if let Some(p) = accepted(mapped, "Mapped friend path to champions dir") {
+    return Some(p);
+  }
+
+  // 3) Variants in champions_dir, for both the relative-friendly and the original path
+  for src in [&fp_rel, &fp] {
+    let candidates = dir_variants(champions_dir, src);
+    if let Some(p) = accepted(candidates, "Found in champions dir variants") {
+      return Some(p);
+    }
+  }
+
+  // 4) Shallow search by stem
+  if let Some(stem) = fp.file_stem().and_then(|s| s.to_str()) {
+    let candidates = shallow_matches(champions_dir, stem);
+    if let Some(p) = accepted(candidates, "Found by shallow scan in champions dir") {
+      return Some(p);
+    }
+  }
+
+  let local_candidates = |file: &str| {
+    let file = PathBuf::from(file);
+    let mut candidates = Vec::new();
+    if file.is_absolute() && file.exists() {
+      candidates.push(file.clone());
+    }
+    candidates.extend(dir_variants(champions_dir, &file));
+    candidates.extend(dir_variants(assets_skins_dir, &file));
+    candidates
+  };
+
+  // 5) Our own selection for the same champion_id and skin_id
+  if let Some(file) = config
+    .skins
+    .iter()
+    .find(|s| s.champion_id == received_skin.champion_id && s.skin_id == received_skin.skin_id)
+    .and_then(|s| s.skin_file.as_deref())
+  {
+    let candidates = local_candidates(file);
+    if let Some(p) = accepted_by(
+      &local_policy,
+      candidates,
+      "Fallback matched local config skin",
+    ) {
+      return Some(p);
+    }
+  }
+
+  // 6) Last resort: anything we selected for the same champion (official, else custom), so the
+  // friend's champion still gets a skin even if theirs isn't available locally
+  let local_any = config
+    .skins
+    .iter()
+    .find(|s| s.champion_id == received_skin.champion_id);
+  let candidates = match local_any {
+    Some(local) => local
+      .skin_file
+      .as_deref()
+      .map(local_candidates)
+      .unwrap_or_default(),
+    None => config
+      .custom_skins
+      .iter()
+      .find(|s| s.champion_id == received_skin.champion_id)
+      .map(|custom| local_candidates(&custom.file_path))
+      .unwrap_or_default(),
+  };
+  accepted_by(
+    &local_policy,
+    candidates,
+    "Fallback mapped by champion to local selection",
+  )
+}
 
 // Helper function to inject skins for multiple champions (used in instant-assign)
 // Kept for backward compatibility and manual calls (not referenced by watcher now).
@@ -80,6 +274,7 @@ pub fn inject_skins_for_champions(app: &AppHandle, league_path: &str, champion_i
           &skins_to_inject,
           &misc_items,
           &champions_dir,
+          InjectionTrigger::InstantAssign,
         ) {
           Ok(_) => {
             // Emit success once for the whole batch
@@ -92,15 +287,16 @@ pub fn inject_skins_for_champions(app: &AppHandle, league_path: &str, champion_i
               );
             }
           }
+          Err(InjectionError::Superseded(reason)) => {
+            println!("[Enhanced] Instant-assign injection superseded: {}", reason);
+          }
           Err(e) => {
-              let message = format!(
-                "Failed to inject instant-assign skins and misc items: {}",
-                e
-              );
+              let error = CommandError::from(e)
+                .prefixed("Failed to inject instant-assign skins and misc items");
               record_injection_state(
                 app,
                 InjectionStatusValue::Error,
-                Some(message.clone()),
+                Some(error),
               );
           }
         }
@@ -157,8 +353,11 @@ pub async fn trigger_party_mode_injection(app: &AppHandle, champion_id: u32) ->
       println!("[Party Mode] Failed to create champions directory: {}", e);
     }
   }
-  // Keep League's ASSETS/Skins as a last-resort fallback only
+  // Whatever a friend's path resolves to has to be inside our champions/custom_skins dirs
+  let path_policy = SkinPathPolicy::for_app_dir(champions_dir.parent().unwrap_or(Path::new(".")));
+  // Keep League's ASSETS/Skins as a last-resort fallback for our own selections only
   let assets_skins_dir = PathBuf::from(league_path).join("ASSETS/Skins");
+  let limits = get_extraction_limits_from_config(app);
 
   // Collect friend skins from received skins; keep friend identity alongside skin
   let mut skins_with_source: Vec<(Skin, Option<String>)> = Vec::new();
@@ -199,283 +398,15 @@ pub async fn trigger_party_mode_injection(app: &AppHandle, champion_id: u32) ->
         skin_file_path, received_skin.from_summoner_name
       );
 
-      // Normalize the path string for easier matching (use forward slashes)
-      let fp_raw = skin_file_path.clone();
-      let fp_norm = fp_raw.replace('\\', "/");
-      // Create a PathBuf for the original string and a "relative-friendly" one without leading slash
-      let fp = PathBuf::from(&fp_raw);
-      let fp_rel = PathBuf::from(fp_norm.trim_start_matches('/'));
-
-      let mut found_path: Option<PathBuf> = None;
-
-      // 1) Direct absolute exists
-      if fp.is_absolute() && fp.exists() {
-        println!(
-          "[Party Mode][DEBUG] Using absolute skin_file from friend: {}",
-          fp.display()
-        );
-        found_path = Some(fp.clone());
-      }
-
-      // 2) Map known portable prefixes (e.g., /ezrea/) under champions_dir even if not treated as absolute on Windows
-      if found_path.is_none() {
-        let fp_str = fp_norm.as_str();
-        // Support both '/ezrea/...' and 'ezrea/...'
-        if fp_str.starts_with("/ezrea/") || fp_str.starts_with("ezrea/") {
-          let tail = if fp_str.starts_with("/ezrea/") {
-            &fp_str["/ezrea/".len()..]
-          } else {
-            &fp_str["ezrea/".len()..]
-          };
-          let mapped = champions_dir.join(tail);
-          if mapped.exists() {
-            println!(
-              "[Party Mode][DEBUG] Mapped /ezrea path to champions dir: {}",
-              mapped.display()
-            );
-            found_path = Some(mapped);
-          } else {
-            if let Some(base) = PathBuf::from(tail).file_name() {
-              let by_name = champions_dir.join(base);
-              if by_name.exists() {
-                println!(
-                  "[Party Mode][DEBUG] Fallback by basename in champions dir: {}",
-                  by_name.display()
-                );
-                found_path = Some(by_name);
-              }
-            }
-          }
-        } else if fp_str.starts_with('/') {
-          // Generic: drop the leading root and try under champions_dir
-          let rel_parts: PathBuf = fp_rel.iter().collect();
-          let mapped = champions_dir.join(&rel_parts);
-          if mapped.exists() {
-            println!(
-              "[Party Mode][DEBUG] Generic mapped absolute to champions dir: {}",
-              mapped.display()
-            );
-            found_path = Some(mapped);
-          }
-        }
-      }
-
-      // Helper: try variants in a directory - try both original and relative-friendly src
-      let try_in_dir = |dir: &PathBuf, src: &PathBuf| -> Option<PathBuf> {
-        // If relative src, try full relative join
-        if !src.is_absolute() {
-          let rel = dir.join(src);
-          if rel.exists() {
-            return Some(rel);
-          }
-        }
-        // Try by basename
-        if let Some(name) = src.file_name() {
-          let by_name = dir.join(name);
-          if by_name.exists() {
-            return Some(by_name);
-          }
-          if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-            for ext in ["zip", "skin_file"] {
-              let cand = dir.join(format!("{}.{}", stem, ext));
-              if cand.exists() {
-                return Some(cand);
-              }
-            }
-          }
-        }
-        None
-      };
-
-      // 3) Check champions and assets dirs with variants (try both fp and fp_rel)
-      if found_path.is_none() {
-        if let Some(p) = try_in_dir(&champions_dir, &fp_rel) {
-          println!(
-            "[Party Mode][DEBUG] Found in champions dir variants: {}",
-            p.display()
-          );
-          found_path = Some(p);
-        }
-      }
-      if found_path.is_none() {
-        if let Some(p) = try_in_dir(&champions_dir, &fp) {
-          println!(
-            "[Party Mode][DEBUG] Found in champions dir variants: {}",
-            p.display()
-          );
-          found_path = Some(p);
-        }
-      }
-      if found_path.is_none() {
-        if let Some(p) = try_in_dir(&assets_skins_dir, &fp_rel) {
-          println!(
-            "[Party Mode][DEBUG] Found in ASSETS/Skins variants: {}",
-            p.display()
-          );
-          found_path = Some(p);
-        }
-      }
-      if found_path.is_none() {
-        if let Some(p) = try_in_dir(&assets_skins_dir, &fp) {
-          println!(
-            "[Party Mode][DEBUG] Found in ASSETS/Skins variants: {}",
-            p.display()
-          );
-          found_path = Some(p);
-        }
-      }
-
-      // 4) Shallow recursive search by stem
-      if found_path.is_none() {
-        if let Some(stem) = fp.file_stem().and_then(|s| s.to_str()) {
-          for dir in [&champions_dir, &assets_skins_dir] {
-            if let Ok(mut rd) = std::fs::read_dir(dir) {
-              while let Some(Ok(entry)) = rd.next() {
-                let path = entry.path();
-                if path.is_dir() {
-                  if let Ok(mut inner) = std::fs::read_dir(&path) {
-                    while let Some(Ok(e2)) = inner.next() {
-                      let p2 = e2.path();
-                      if p2.is_file() {
-                        if let Some(s) = p2.file_stem().and_then(|x| x.to_str()) {
-                          if s.eq_ignore_ascii_case(stem) {
-                            println!(
-                              "[Party Mode][DEBUG] Found by shallow scan in {}: {}",
-                              dir.display(),
-                              p2.display()
-                            );
-                            found_path = Some(p2.clone());
-                            break;
-                          }
-                        }
-                      }
-                    }
-                  }
-                } else if path.is_file() {
-                  if let Some(s) = path.file_stem().and_then(|x| x.to_str()) {
-                    if s.eq_ignore_ascii_case(stem) {
-                      println!(
-                        "[Party Mode][DEBUG] Found by file match in {}: {}",
-                        dir.display(),
-                        path.display()
-                      );
-                      found_path = Some(path.clone());
-                      break;
-                    }
-                  }
-                }
-                if found_path.is_some() {
-                  break;
-                }
-              }
-            }
-            if found_path.is_some() {
-              break;
-            }
-          }
-        }
-      }
-
-      // 5) Fallback: use local config skin mapping for same champion_id and skin_id
-      if found_path.is_none() {
-        if let Some(local_skin) = config.skins.iter().find(|s| {
-          s.champion_id == received_skin.champion_id && s.skin_id == received_skin.skin_id
-        }) {
-          if let Some(ref f) = local_skin.skin_file {
-            let cand_abs = PathBuf::from(f);
-            let mut candidates: Vec<PathBuf> = Vec::new();
-            if cand_abs.is_absolute() {
-              candidates.push(cand_abs.clone());
-            }
-            candidates.push(champions_dir.join(&cand_abs));
-            if let Some(name) = cand_abs.file_name() {
-              candidates.push(champions_dir.join(name));
-              if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-                candidates.push(champions_dir.join(format!("{}.zip", stem)));
-                candidates.push(champions_dir.join(format!("{}.skin_file", stem)));
-              }
-            }
-            candidates.push(assets_skins_dir.join(&cand_abs));
-            if let Some(name) = cand_abs.file_name() {
-              candidates.push(assets_skins_dir.join(name));
-              if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-                candidates.push(assets_skins_dir.join(format!("{}.zip", stem)));
-                candidates.push(assets_skins_dir.join(format!("{}.skin_file", stem)));
-              }
-            }
-            if let Some(found) = candidates.into_iter().find(|p| p.exists()) {
-              println!(
-                "[Party Mode][DEBUG] Fallback matched local config skin: {}",
-                found.display()
-              );
-              found_path = Some(found);
-            }
-          }
-        }
-      }
-
-      // 6) Last-resort fallback: if still not found, use ANY local skin selected for the same champion
-      // This ensures we still inject something visually for that friend/champion even if their exact skin isn't available locally
-      if found_path.is_none() {
-        // Prefer official selection, else custom skin
-        if let Some(local_skin_any) = config
-          .skins
-          .iter()
-          .find(|s| s.champion_id == received_skin.champion_id)
-        {
-          if let Some(ref f) = local_skin_any.skin_file {
-            let cand_abs = PathBuf::from(f);
-            let mut candidates: Vec<PathBuf> = Vec::new();
-            if cand_abs.is_absolute() {
-              candidates.push(cand_abs.clone());
-            }
-            candidates.push(champions_dir.join(&cand_abs));
-            if let Some(name) = cand_abs.file_name() {
-              candidates.push(champions_dir.join(name));
-              if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-                candidates.push(champions_dir.join(format!("{}.zip", stem)));
-                candidates.push(champions_dir.join(format!("{}.skin_file", stem)));
-              }
-            }
-            candidates.push(assets_skins_dir.join(&cand_abs));
-            if let Some(name) = cand_abs.file_name() {
-              candidates.push(assets_skins_dir.join(name));
-              if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-                candidates.push(assets_skins_dir.join(format!("{}.zip", stem)));
-                candidates.push(assets_skins_dir.join(format!("{}.skin_file", stem)));
-              }
-            }
-            if let Some(found) = candidates.into_iter().find(|p| p.exists()) {
-              println!(
-                "[Party Mode][DEBUG] Fallback mapped by champion {} to local selection: {}",
-                received_skin.champion_id,
-                found.display()
-              );
-              found_path = Some(found);
-            }
-          }
-        } else if let Some(custom_any) = config
-          .custom_skins
-          .iter()
-          .find(|s| s.champion_id == received_skin.champion_id)
-        {
-          let cand_abs = PathBuf::from(&custom_any.file_path);
-          if cand_abs.exists() {
-            println!(
-              "[Party Mode][DEBUG] Fallback mapped by champion {} to local custom skin: {}",
-              received_skin.champion_id,
-              cand_abs.display()
-            );
-            found_path = Some(cand_abs);
-          } else {
-            let cand_rel = champions_dir.join(&cand_abs);
-            if cand_rel.exists() {
-              found_path = Some(cand_rel);
-            }
-          }
-        }
-      }
-
+      let found_path = resolve_received_skin_path(
+        received_skin,
+        skin_file_path,
+        &config,
+        &champions_dir,
+        &assets_skins_dir,
+        &path_policy,
+        |path, policy| accept_received_package(policy, &limits, path, received_skin),
+      );
       if let Some(resolved) = found_path {
         skins_with_source.push((
           Skin {
@@ -501,13 +432,9 @@ pub async fn trigger_party_mode_injection(app: &AppHandle, champion_id: u32) ->
           "[Party Mode][DEBUG] Champions dir: {}",
           champions_dir.display()
         );
-        println!(
-          "[Party Mode][DEBUG] Assets skins dir: {}",
-          assets_skins_dir.display()
-        );
         println!(
           "[Party Mode][DEBUG] Tried to resolve from: {}",
-          fp.display()
+          skin_file_path
         );
       }
     }
@@ -518,6 +445,7 @@ pub async fn trigger_party_mode_injection(app: &AppHandle, champion_id: u32) ->
 
   if skins_with_source.is_empty() {
     println!("[Party Mode] No skins (local or friend) with available files to inject");
+    super::watcher::release_overlay_for_empty_loadout(app);
     return Ok(());
   }
 
@@ -553,6 +481,7 @@ pub async fn trigger_party_mode_injection(app: &AppHandle, champion_id: u32) ->
     &skins_to_inject,
     &misc_items,
     &champions_dir,
+    InjectionTrigger::Party,
   ) {
     Ok(_) => {
       let total = skins_to_inject.len();
@@ -589,15 +518,21 @@ pub async fn trigger_party_mode_injection(app: &AppHandle, champion_id: u32) ->
 
       Ok(())
     }
+    Err(InjectionError::Superseded(reason)) => {
+      println!("[Party Mode] Friend skin injection superseded: {}", reason);
+      Ok(())
+    }
     Err(e) => {
       println!("[Party Mode] ❌ Failed to inject friend skins: {}", e);
-      let message = format!("Failed to inject friend skins: {}", e);
+      let error = CommandError::from(e)
+        .prefixed("Failed to inject friend skins")
+        .with_context("champion_id", champion_id);
       record_injection_state(
         app,
         InjectionStatusValue::Error,
-        Some(message.clone()),
+        Some(error.clone()),
       );
-      Err(message)
+      Err(error.into())
     }
   }
 }
@@ -643,7 +578,9 @@ pub async fn trigger_party_mode_injection_for_champions(
   if !champions_dir.exists() {
     let _ = std::fs::create_dir_all(&champions_dir);
   }
+  let path_policy = SkinPathPolicy::for_app_dir(champions_dir.parent().unwrap_or(Path::new(".")));
   let assets_skins_dir = PathBuf::from(league_path).join("ASSETS/Skins");
+  let limits = get_extraction_limits_from_config(app);
 
   // Keep friend identity so we don't collapse multiple friends sharing the same skin/path
   let mut skins_with_source: Vec<(Skin, Option<String>)> = Vec::new();
@@ -681,175 +618,15 @@ pub async fn trigger_party_mode_injection_for_champions(
   let received_skins_map = RECEIVED_SKINS.lock().unwrap();
   for received_skin in received_skins_map.values() {
     if let Some(skin_file_path) = &received_skin.skin_file_path {
-      let fp_raw = skin_file_path.clone();
-      let fp_norm = fp_raw.replace('\\', "/");
-      let fp = PathBuf::from(&fp_raw);
-      let fp_rel = PathBuf::from(fp_norm.trim_start_matches('/'));
-      let mut found_path: Option<PathBuf> = None;
-
-      if fp.is_absolute() && fp.exists() {
-  // Nothing to inject? Reset to idle so UI reflects no active injection
-  if skins_with_source.is_empty() && misc_items.is_empty() {
-    record_injection_state(app, InjectionStatusValue::Idle, None);
-    return Ok(());
-  }
-
-        found_path = Some(fp.clone());
-      }
-
-      if found_path.is_none() {
-        let fp_str = fp_norm.as_str();
-        if fp_str.starts_with("/ezrea/") || fp_str.starts_with("ezrea/") {
-          let tail = if fp_str.starts_with("/ezrea/") {
-            &fp_str["/ezrea/".len()..]
-          } else {
-            &fp_str["ezrea/".len()..]
-          };
-          let mapped = champions_dir.join(tail);
-          if mapped.exists() {
-            found_path = Some(mapped);
-          } else if let Some(base) = PathBuf::from(tail).file_name() {
-            let by_name = champions_dir.join(base);
-            if by_name.exists() {
-              found_path = Some(by_name);
-            }
-          }
-        } else if fp_str.starts_with('/') {
-          let mapped = champions_dir.join(&fp_rel);
-          if mapped.exists() {
-            found_path = Some(mapped);
-          }
-        }
-      }
-
-      let try_in_dir = |dir: &PathBuf, src: &PathBuf| -> Option<PathBuf> {
-        if !src.is_absolute() {
-          let rel = dir.join(src);
-          if rel.exists() {
-            return Some(rel);
-          }
-        }
-        if let Some(name) = src.file_name() {
-          let by_name = dir.join(name);
-          if by_name.exists() {
-            return Some(by_name);
-          }
-          if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-            for ext in ["zip", "skin_file"] {
-              let cand = dir.join(format!("{}.{}", stem, ext));
-              if cand.exists() {
-                return Some(cand);
-              }
-            }
-          }
-        }
-        None
-      };
-
-      if found_path.is_none() {
-        if let Some(p) = try_in_dir(&champions_dir, &fp_rel) {
-          found_path = Some(p);
-        }
-      }
-      if found_path.is_none() {
-        if let Some(p) = try_in_dir(&champions_dir, &fp) {
-          found_path = Some(p);
-        }
-      }
-      if found_path.is_none() {
-        if let Some(p) = try_in_dir(&assets_skins_dir, &fp_rel) {
-          found_path = Some(p);
-        }
-      }
-      if found_path.is_none() {
-        if let Some(p) = try_in_dir(&assets_skins_dir, &fp) {
-          found_path = Some(p);
-        }
-      }
-
-      if found_path.is_none() {
-        if let Some(local_skin) = config.skins.iter().find(|s| {
-          s.champion_id == received_skin.champion_id && s.skin_id == received_skin.skin_id
-        }) {
-          if let Some(ref f) = local_skin.skin_file {
-            let cand = PathBuf::from(f);
-            let mut cands = vec![];
-            if cand.is_absolute() {
-              cands.push(cand.clone());
-            }
-            cands.push(champions_dir.join(&cand));
-            if let Some(name) = cand.file_name() {
-              cands.push(champions_dir.join(name));
-              if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-                cands.push(champions_dir.join(format!("{}.zip", stem)));
-                cands.push(champions_dir.join(format!("{}.skin_file", stem)));
-              }
-            }
-            cands.push(assets_skins_dir.join(&cand));
-            if let Some(name) = cand.file_name() {
-              cands.push(assets_skins_dir.join(name));
-              if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-                cands.push(assets_skins_dir.join(format!("{}.zip", stem)));
-                cands.push(assets_skins_dir.join(format!("{}.skin_file", stem)));
-              }
-            }
-            if let Some(found) = cands.into_iter().find(|p| p.exists()) {
-              found_path = Some(found);
-            }
-          }
-        }
-      }
-
-      // 6) Last-resort fallback by champion: use any local selection (official or custom) for this champion
-      if found_path.is_none() {
-        if let Some(local_any) = config
-          .skins
-          .iter()
-          .find(|s| s.champion_id == received_skin.champion_id)
-        {
-          if let Some(ref f) = local_any.skin_file {
-            let cand = PathBuf::from(f);
-            let mut cands = vec![];
-            if cand.is_absolute() {
-              cands.push(cand.clone());
-            }
-            cands.push(champions_dir.join(&cand));
-            if let Some(name) = cand.file_name() {
-              cands.push(champions_dir.join(name));
-              if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-                cands.push(champions_dir.join(format!("{}.zip", stem)));
-                cands.push(champions_dir.join(format!("{}.skin_file", stem)));
-              }
-            }
-            cands.push(assets_skins_dir.join(&cand));
-            if let Some(name) = cand.file_name() {
-              cands.push(assets_skins_dir.join(name));
-              if let Some(stem) = PathBuf::from(name).file_stem().and_then(|s| s.to_str()) {
-                cands.push(assets_skins_dir.join(format!("{}.zip", stem)));
-                cands.push(assets_skins_dir.join(format!("{}.skin_file", stem)));
-              }
-            }
-            if let Some(found) = cands.into_iter().find(|p| p.exists()) {
-              found_path = Some(found);
-            }
-          }
-        } else if let Some(custom_any) = config
-          .custom_skins
-          .iter()
-          .find(|s| s.champion_id == received_skin.champion_id)
-        {
-          let cand = PathBuf::from(&custom_any.file_path);
-          if cand.exists() {
-            found_path = Some(cand);
-          } else {
-            let rel = champions_dir.join(&cand);
-            if rel.exists() {
-              found_path = Some(rel);
-            }
-          }
-        }
-      }
-
+      let found_path = resolve_received_skin_path(
+        received_skin,
+        skin_file_path,
+        &config,
+        &champions_dir,
+        &assets_skins_dir,
+        &path_policy,
+        |path, policy| accept_received_package(policy, &limits, path, received_skin),
+      );
       if let Some(resolved) = found_path {
         skins_with_source.push((
           Skin {
@@ -872,6 +649,7 @@ pub async fn trigger_party_mode_injection_for_champions(
   drop(received_skins_map);
 
   if skins_with_source.is_empty() && misc_items.is_empty() {
+    super::watcher::release_overlay_for_empty_loadout(app);
     return Ok(());
   }
   // Dedup but keep per-friend duplicates by including friend id in key
@@ -899,6 +677,7 @@ pub async fn trigger_party_mode_injection_for_champions(
     &unique_skins,
     &misc_items,
     &champions_dir,
+    InjectionTrigger::Party,
   ) {
     Ok(_) => {
       let total = unique_skins.len();
@@ -911,15 +690,125 @@ pub async fn trigger_party_mode_injection_for_champions(
       record_injection_state(app, InjectionStatusValue::Success, None);
       Ok(())
     }
+    Err(InjectionError::Superseded(reason)) => {
+      println!(
+        "[Party Mode] Multi-champion injection superseded: {}",
+        reason
+      );
+      Ok(())
+    }
     Err(e) => {
       eprintln!("[Party Mode] ❌ Multi-champion injection failed: {}", e);
-      let message = format!("Multi-champion injection failed: {}", e);
+      let error = CommandError::from(e).prefixed("Multi-champion injection failed");
       record_injection_state(
         app,
         InjectionStatusValue::Error,
-        Some(message.clone()),
+        Some(error.clone()),
       );
-      Err(message)
+      Err(error.into())
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+
+  fn received(champion_id: u32, skin_id: u32, path: &Path) -> InMemoryReceivedSkin {
+    InMemoryReceivedSkin {
+      from_summoner_id: "friend".into(),
+      from_summoner_name: "Friend".into(),
+      champion_id,
+      skin_id,
+      chroma_id: None,
+      skin_file_path: Some(path.display().to_string()),
+      received_at: 0,
     }
   }
+
+  #[test]
+  fn refused_friend_path_falls_back_to_the_local_skin() {
+    let root = std::env::temp_dir().join(format!("osskins-received-{}", uuid::Uuid::new_v4()));
+    let app_dir = root.join("app");
+    let champions_dir = app_dir.join("champions");
+    let assets_skins_dir = root.join("League").join("ASSETS").join("Skins");
+    fs::create_dir_all(champions_dir.join("Ahri")).unwrap();
+    fs::create_dir_all(&assets_skins_dir).unwrap();
+    let local = champions_dir.join("Ahri").join("local-ahri.zip");
+    fs::write(&local, b"zip").unwrap();
+    // The friend's archive only exists in the game's ASSETS/Skins, which the policy refuses
+    let friend = assets_skins_dir.join("friend-ahri.zip");
+    fs::write(&friend, b"zip").unwrap();
+
+    // One of our own selections only exists in ASSETS/Skins
+    let assets_local = assets_skins_dir.join("local-ashe.zip");
+    fs::write(&assets_local, b"zip").unwrap();
+
+    let config: SavedConfig = serde_json::from_value(serde_json::json!({
+      "league_path": root.join("League"),
+      "skins": [
+        {
+          "champion_id": 103,
+          "skin_id": 103001,
+          "chroma_id": null,
+          "skin_file": "Ahri/local-ahri.zip"
+        },
+        {
+          "champion_id": 22,
+          "skin_id": 22001,
+          "chroma_id": null,
+          "skin_file": "local-ashe.zip"
+        }
+      ],
+      "favorites": []
+    }))
+    .unwrap();
+    let policy = SkinPathPolicy::for_app_dir(&app_dir);
+    let resolve = |received_skin: &InMemoryReceivedSkin, requested: &str| {
+      resolve_received_skin_path(
+        received_skin,
+        requested,
+        &config,
+        &champions_dir,
+        &assets_skins_dir,
+        &policy,
+        |path: &Path, policy: &SkinPathPolicy| policy.allows(path),
+      )
+    };
+
+    let friend_path = friend.display().to_string();
+    assert_eq!(
+      resolve(&received(103, 103001, &friend), &friend_path),
+      Some(local.clone())
+    );
+
+    // A different skin of the same champion still gets our selection for it
+    let relative = Path::new("friend-ahri.zip");
+    assert_eq!(
+      resolve(&received(103, 103005, relative), "friend-ahri.zip"),
+      Some(local.clone())
+    );
+
+    // Portable paths map into champions/ the same way local skins do
+    let portable = Path::new("/ezrea/Ahri/local-ahri.zip");
+    assert_eq!(
+      resolve(
+        &received(103, 103005, portable),
+        "/ezrea/Ahri/local-ahri.zip"
+      ),
+      Some(local)
+    );
+
+    // Our own selection may still come from ASSETS/Skins, the friend's path never does
+    assert_eq!(
+      resolve(&received(22, 22001, &friend), &friend_path),
+      Some(assets_local)
+    );
+
+    // Nothing local for the champion: the skin is skipped rather than taken from ASSETS/Skins
+    assert_eq!(resolve(&received(99, 99001, &friend), &friend_path), None);
+
+    let _ = fs::remove_dir_all(root);
+  }
 }
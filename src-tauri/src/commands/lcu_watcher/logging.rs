@@ -1,5 +1,6 @@
 // Logging utilities for LCU watcher
 
+use crate::injection::injection_log::{bundle_recent_logs, injection_logs_dir};
 use chrono::Utc;
 use copypasta::{ClipboardContext, ClipboardProvider};
 use once_cell::sync::Lazy;
@@ -11,6 +12,9 @@ use std::thread;
 use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
+// Number of injection logs print_logs bundles when the caller doesn't choose
+const DEFAULT_BUNDLED_INJECTION_LOGS: usize = 3;
+
 // Global in-memory log buffer
 pub static LOG_BUFFER: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
@@ -81,8 +85,9 @@ pub fn append_global_log(message: &str) {
 }
 
 /// Write buffered logs to a timestamped temp file and copy contents to clipboard.
+/// The logs of the last `injection_logs` injections are appended after the live log.
 #[tauri::command]
-pub fn print_logs(app: AppHandle) -> Result<String, String> {
+pub fn print_logs(app: AppHandle, injection_logs: Option<usize>) -> Result<String, String> {
   // Prefer the on-disk live log (captures everything appended since app start).
   let app_dir = app
     .path()
@@ -96,7 +101,7 @@ pub fn print_logs(app: AppHandle) -> Result<String, String> {
   let live_log = out_dir.join("osskins-live.log");
 
   // Read from the live log file if it exists and is readable. Otherwise fall back to in-memory buffer.
-  let full_contents = if live_log.exists() {
+  let mut full_contents = if live_log.exists() {
     match std::fs::read_to_string(&live_log) {
       Ok(s) if !s.is_empty() => s,
       _ => {
@@ -120,6 +125,13 @@ pub fn print_logs(app: AppHandle) -> Result<String, String> {
     buf.join("\n")
   };
 
+  let count = injection_logs.unwrap_or(DEFAULT_BUNDLED_INJECTION_LOGS);
+  let bundle = bundle_recent_logs(&injection_logs_dir(&app_dir), count);
+  if !bundle.is_empty() {
+    full_contents.push_str("\n\n");
+    full_contents.push_str(&bundle);
+  }
+
   // Write exported timestamped copy
   let filename = format!("osskins-logs-{}.txt", Utc::now().format("%Y%m%d-%H%M%S"));
   let out_path = out_dir.join(&filename);
@@ -11,5 +11,5 @@ mod watcher;
 // Re-export public types and functions
 pub use logging::{append_global_log, print_logs};
 pub use party_mode::start_party_mode_chat_monitor;
-pub use utils::is_in_champ_select;
+pub use utils::{is_in_champ_select, phase_needs_overlay};
 pub use watcher::start_lcu_watcher;
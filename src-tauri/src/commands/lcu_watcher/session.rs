@@ -103,6 +103,70 @@ pub fn get_selected_champion_id(session_json: &serde_json::Value) -> Option<i64>
   None
 }
 
+// Champions the local player may still end up with: the current hover or pick intent, the
+// champion already assigned, then the ARAM bench. Used to prepare mods before lock-in.
+pub fn get_prebuild_champion_ids(session_json: &serde_json::Value) -> Vec<i64> {
+  let mut candidates = Vec::new();
+
+  if let Some(local_player_cell_id) = session_json
+    .get("localPlayerCellId")
+    .and_then(|v| v.as_i64())
+  {
+    // The champion hovered during our pick
+    if let Some(actions) = session_json.get("actions").and_then(|v| v.as_array()) {
+      for action in actions
+        .iter()
+        .filter_map(|group| group.as_array())
+        .flatten()
+      {
+        let is_own_pick = action.get("actorCellId").and_then(|v| v.as_i64())
+          == Some(local_player_cell_id)
+          && action.get("type").and_then(|v| v.as_str()) == Some("pick");
+        let is_in_progress = action
+          .get("isInProgress")
+          .and_then(|v| v.as_bool())
+          .unwrap_or(false);
+        if is_own_pick && is_in_progress {
+          candidates.push(action.get("championId").and_then(|v| v.as_i64()));
+        }
+      }
+    }
+
+    if let Some(player) = session_json
+      .get("myTeam")
+      .and_then(|v| v.as_array())
+      .and_then(|team| {
+        team
+          .iter()
+          .find(|p| p.get("cellId").and_then(|v| v.as_i64()) == Some(local_player_cell_id))
+      })
+    {
+      candidates.push(player.get("championPickIntent").and_then(|v| v.as_i64()));
+      candidates.push(player.get("championId").and_then(|v| v.as_i64()));
+    }
+  }
+
+  // ARAM bench champions can be swapped in at any time
+  if let Some(bench) = session_json
+    .get("benchChampions")
+    .and_then(|v| v.as_array())
+  {
+    candidates.extend(
+      bench
+        .iter()
+        .map(|c| c.get("championId").and_then(|v| v.as_i64())),
+    );
+  }
+
+  let mut champion_ids = Vec::new();
+  for champion_id in candidates.into_iter().flatten() {
+    if champion_id > 0 && !champion_ids.contains(&champion_id) {
+      champion_ids.push(champion_id);
+    }
+  }
+  champion_ids
+}
+
 // Helper function to get instant-assign champion selections from session JSON
 pub fn get_swift_play_champion_selections(json: &serde_json::Value) -> Vec<i64> {
   let mut champion_ids = Vec::new();
@@ -242,3 +306,41 @@ pub fn extract_swift_play_champions_from_lobby(json: &serde_json::Value) -> Vec<
 
   champion_ids
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn prebuild_champions_cover_hover_assignment_and_bench() {
+    let session = json!({
+      "localPlayerCellId": 2,
+      "actions": [[
+        { "actorCellId": 1, "type": "pick", "isInProgress": true, "championId": 11 },
+        { "actorCellId": 2, "type": "ban", "isInProgress": true, "championId": 12 },
+        { "actorCellId": 2, "type": "pick", "isInProgress": true, "championId": 103 }
+      ]],
+      "myTeam": [
+        { "cellId": 1, "championPickIntent": 21, "championId": 22 },
+        { "cellId": 2, "championPickIntent": 103, "championId": 99 }
+      ],
+      "benchChampions": [
+        { "championId": 99 },
+        { "championId": 0 },
+        { "championId": 51 }
+      ]
+    });
+    assert_eq!(get_prebuild_champion_ids(&session), vec![103, 99, 51]);
+
+    // Nothing hovered or assigned yet
+    let session = json!({
+      "localPlayerCellId": 2,
+      "actions": [[
+        { "actorCellId": 2, "type": "pick", "isInProgress": true, "championId": 0 }
+      ]],
+      "myTeam": [{ "cellId": 2, "championPickIntent": 0, "championId": 0 }]
+    });
+    assert!(get_prebuild_champion_ids(&session).is_empty());
+  }
+}
@@ -14,6 +14,9 @@ pub enum InjectionMode {
 // 0 = Unknown, 1 = ChampSelect, 2 = Other
 pub static PHASE_STATE: Lazy<AtomicU8> = Lazy::new(|| AtomicU8::new(0));
 
+// Whether the gameflow phase needs the overlay (ChampSelect, InProgress or Reconnect)
+pub static OVERLAY_PHASE_ACTIVE: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
+
 // Prevent repeated injections in the same ChampSelect phase
 pub static LAST_PARTY_INJECTION_SIGNATURE: Lazy<std::sync::Mutex<Option<String>>> =
   Lazy::new(|| std::sync::Mutex::new(None));
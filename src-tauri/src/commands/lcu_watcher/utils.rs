@@ -6,7 +6,7 @@ use std::sync::OnceLock;
 use tauri::{AppHandle, Manager};
 
 use super::types::{
-  InjectionMode, LAST_SHARED_CHAMPION_ID, PHASE_STATE,
+  InjectionMode, LAST_SHARED_CHAMPION_ID, OVERLAY_PHASE_ACTIVE, PHASE_STATE,
 };
 use crate::commands::party_mode::RECEIVED_SKINS;
 use crate::commands::types::SavedConfig;
@@ -17,6 +17,10 @@ pub fn is_in_champ_select() -> bool {
   PHASE_STATE.load(Ordering::Relaxed) == 1
 }
 
+pub fn phase_needs_overlay() -> bool {
+  OVERLAY_PHASE_ACTIVE.load(Ordering::Relaxed)
+}
+
 pub fn read_injection_mode(app: &AppHandle) -> InjectionMode {
   let config_dir = app
     .path()
@@ -13,25 +13,26 @@ use super::injection::{trigger_party_mode_injection, trigger_party_mode_injectio
 use super::logging::emit_terminal_log;
 use super::party_mode::check_for_party_mode_messages_with_connection;
 use super::session::{
-  extract_swift_play_champions_from_lobby, get_selected_champion_id,
+  extract_swift_play_champions_from_lobby, get_prebuild_champion_ids, get_selected_champion_id,
   get_swift_play_champion_selections,
 };
 use super::types::{
   generate_watcher_instance_id, is_current_watcher_instance, start_new_champ_select_session,
   InjectionMode, LAST_CHAMPION_SHARE_TIME, LAST_INSTANT_ASSIGN_CHAMPIONS,
-  LAST_PARTY_INJECTION_SIGNATURE, LCU_WATCHER_ACTIVE, PARTY_INJECTION_DONE_THIS_PHASE,
-  PHASE_STATE,
+  LAST_PARTY_INJECTION_SIGNATURE, LCU_WATCHER_ACTIVE, OVERLAY_PHASE_ACTIVE,
+  PARTY_INJECTION_DONE_THIS_PHASE, PHASE_STATE,
 };
 use super::utils::{
   compute_instant_assign_signature, compute_party_injection_signature, read_injection_mode,
 };
+use crate::commands::error::CommandError;
 use crate::commands::misc_items::get_selected_misc_items;
 use crate::commands::party_mode::{
   clear_received_skins, clear_sent_shares, PARTY_MODE_VERBOSE, RECEIVED_SKINS,
 };
 use crate::commands::types::{SavedConfig, SkinData};
 use crate::commands::skin_injection::{record_injection_state, InjectionStatusValue};
-use crate::injection::{inject_skins_and_misc, Skin};
+use crate::injection::{inject_skins_and_misc, InjectionError, InjectionTrigger, Skin};
 
 use futures_util::{SinkExt, StreamExt};
 use native_tls::TlsConnector;
@@ -752,6 +753,18 @@ fn handle_phase_change(
   if new_phase == *last_phase {
     return;
   }
+  // Stored before anything below can return early, the watchdog reads it to decide restarts
+  OVERLAY_PHASE_ACTIVE.store(
+    matches!(new_phase, "ChampSelect" | "InProgress" | "Reconnect"),
+    Ordering::Relaxed,
+  );
+  // The deadline stays until the game is on, a late champ select injection still has to meet it
+  if !matches!(new_phase, "ChampSelect" | "GameStart" | "InProgress") {
+    crate::injection::deadline::clear_champ_select_deadline();
+  }
+  if new_phase != "ChampSelect" {
+    crate::injection::prebuild::cancel_prebuild();
+  }
   println!(
     "[LCU Watcher] LCU status changed: {} -> {}",
     last_phase, new_phase
@@ -801,34 +814,57 @@ fn handle_phase_change(
       return;
     }
 
-    record_injection_state(app_handle, InjectionStatusValue::Idle, None);
-    match crate::injection::needs_injection_cleanup(app_handle, league_path) {
-      Ok(needs_cleanup) => {
-        if needs_cleanup {
-          let log_msg = format!(
-            "[LCU Watcher] Injection cleanup needed for phase transition {} -> {}, cleaning up...",
-            last_phase, new_phase
-          );
-          println!("{}", log_msg);
-          emit_terminal_log(app_handle, &log_msg);
-          if let Err(e) = crate::injection::cleanup_injection(app_handle, league_path) {
-            let error_msg = format!(
-              "[LCU Watcher] Error cleaning up injection on phase change: {}",
-              e
+    // An injection still being prepared for the phase we just left would only be undone again
+    if crate::injection::queue::cancel_injections() > 0 {
+      let log_msg = format!(
+        "[LCU Watcher] Cancelled in-flight injection on phase transition {} -> {}",
+        last_phase, new_phase
+      );
+      println!("{}", log_msg);
+      emit_terminal_log(app_handle, &log_msg);
+    }
+
+    // Persistent overlay: runoverlay waits for the next game, and the next injection reuses
+    // it when the loadout is unchanged
+    let keep_overlay = crate::commands::config::is_persistent_overlay_enabled(app_handle)
+      && crate::injection::core::is_global_overlay_running();
+    if keep_overlay {
+      let log_msg = format!(
+        "[LCU Watcher] Phase transition {} -> {} detected, but the overlay is persistent. Keeping it running for next game.",
+        last_phase, new_phase
+      );
+      println!("{}", log_msg);
+      emit_terminal_log(app_handle, &log_msg);
+    } else {
+      record_injection_state(app_handle, InjectionStatusValue::Idle, None);
+      match crate::injection::needs_injection_cleanup(app_handle, league_path) {
+        Ok(needs_cleanup) => {
+          if needs_cleanup {
+            let log_msg = format!(
+              "[LCU Watcher] Injection cleanup needed for phase transition {} -> {}, cleaning up...",
+              last_phase, new_phase
             );
-            println!("{}", error_msg);
-            emit_terminal_log(app_handle, &error_msg);
-          } else {
-            let success_msg = "[LCU Watcher] ✅ Injection cleanup completed successfully";
-            println!("{}", success_msg);
-            emit_terminal_log(app_handle, success_msg);
+            println!("{}", log_msg);
+            emit_terminal_log(app_handle, &log_msg);
+            if let Err(e) = crate::injection::cleanup_injection(app_handle, league_path) {
+              let error_msg = format!(
+                "[LCU Watcher] Error cleaning up injection on phase change: {}",
+                e
+              );
+              println!("{}", error_msg);
+              emit_terminal_log(app_handle, &error_msg);
+            } else {
+              let success_msg = "[LCU Watcher] ✅ Injection cleanup completed successfully";
+              println!("{}", success_msg);
+              emit_terminal_log(app_handle, success_msg);
+            }
           }
         }
-      }
-      Err(e) => {
-        let error_msg = format!("[LCU Watcher] Error checking if cleanup is needed: {}", e);
-        println!("{}", error_msg);
-        emit_terminal_log(app_handle, &error_msg);
+        Err(e) => {
+          let error_msg = format!("[LCU Watcher] Error checking if cleanup is needed: {}", e);
+          println!("{}", error_msg);
+          emit_terminal_log(app_handle, &error_msg);
+        }
       }
     }
   } else {
@@ -870,7 +906,8 @@ fn handle_phase_change(
       .app_data_dir()
       .unwrap_or_else(|_| PathBuf::from("."))
       .join("overlay");
-    if overlay_dir.exists() {
+    // A persistent overlay may still be running from it
+    if overlay_dir.exists() && !crate::injection::core::is_global_overlay_running() {
       let _ = fs::remove_dir_all(&overlay_dir);
     }
 
@@ -917,6 +954,25 @@ fn handle_phase_change(
   }
 }
 
+// A persistent overlay kept from the last game would still apply its skins and misc mods, so
+// an empty loadout stops it instead of leaving it for reuse. Returns whether one was running.
+fn stop_overlay_for_empty_loadout() -> bool {
+  if !crate::injection::core::is_global_overlay_running() {
+    return false;
+  }
+  // Also forgets the signature, so nothing can be reused from it
+  crate::injection::core::stop_global_overlay_process("empty_loadout");
+  true
+}
+
+pub(super) fn release_overlay_for_empty_loadout(app_handle: &AppHandle) {
+  if stop_overlay_for_empty_loadout() {
+    let log_msg = "[LCU Watcher] Nothing to inject, stopped the overlay kept from the last game";
+    println!("{}", log_msg);
+    emit_terminal_log(app_handle, log_msg);
+  }
+}
+
 fn handle_champ_select_event_data(
   app_handle: &AppHandle,
   league_path: &str,
@@ -926,6 +982,20 @@ fn handle_champ_select_event_data(
   last_party_injection_check: &mut Instant,
   last_party_injection_time: &mut Instant,
 ) {
+  // Champ select injections have to finish before the game starts loading
+  crate::injection::deadline::note_champ_select_session(data);
+
+  // Convert the skins of the champions we may lock in ahead of time, see prebuild.rs
+  let prebuild_ids: Vec<u32> = get_prebuild_champion_ids(data)
+    .into_iter()
+    .map(|id| id as u32)
+    .collect();
+  if !prebuild_ids.is_empty()
+    && !crate::commands::skin_injection::should_skip_automatic_injection(app_handle)
+  {
+    crate::injection::prebuild::start_prebuild(app_handle, league_path, prebuild_ids);
+  }
+
   if let Some(selected_champ_id) = get_selected_champion_id(data) {
     let current_champion_id = selected_champ_id as u32;
     let champion_changed = if let Some(last_champ) = *last_champion_id {
@@ -1195,6 +1265,7 @@ fn handle_champ_select_event_data(
                   .collect();
                 if filtered_skins.is_empty() && misc_items.is_empty() {
                   // Nothing to inject; reset status to idle so UI reflects no active injection
+                  release_overlay_for_empty_loadout(app_handle);
                   record_injection_state(app_handle, InjectionStatusValue::Idle, None);
                   last_selected_skins.insert(champ_id, skin.clone());
                   continue;
@@ -1212,6 +1283,7 @@ fn handle_champ_select_event_data(
                   &filtered_skins,
                   &misc_items,
                   &champions_dir,
+                  InjectionTrigger::ChampSelect,
                 ) {
                   Ok(_) => {
                     record_injection_state(app_handle, InjectionStatusValue::Success, None);
@@ -1222,15 +1294,21 @@ fn handle_champ_select_event_data(
                       champ_id
                     );
                   }
-                  Err(e) => {
-                    let message = format!(
-                      "Failed to inject skins and misc items for champion {}: {}",
-                      champ_id, e
+                  Err(InjectionError::Superseded(reason)) => {
+                    println!(
+                      "[Enhanced] Injection for champion {} superseded: {}",
+                      champ_id, reason
                     );
+                  }
+                  Err(e) => {
+                    let error = CommandError::from(e).prefixed(&format!(
+                      "Failed to inject skins and misc items for champion {}",
+                      champ_id
+                    ));
                     record_injection_state(
                       app_handle,
                       InjectionStatusValue::Error,
-                      Some(message.clone()),
+                      Some(error),
                     );
                   }
                 }
@@ -1646,3 +1724,32 @@ fn run_polling_loop(
     std::thread::sleep(Duration::from_millis(500));
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::injection::backend::fake::FakeBackend;
+  use crate::injection::backend::OverlayBackend;
+  use crate::injection::core::{
+    is_global_overlay_running, running_overlay_signature, set_global_overlay_process,
+    set_running_overlay_signature, OVERLAY_TEST_LOCK,
+  };
+
+  #[test]
+  fn an_empty_loadout_tears_down_the_persistent_overlay() {
+    let _lock = OVERLAY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = std::env::temp_dir();
+    let process = FakeBackend::new(Vec::new())
+      .run_overlay(&dir, &dir, &dir)
+      .unwrap();
+    // Left running for the next game by a persistent-overlay injection
+    set_global_overlay_process(process);
+    set_running_overlay_signature(Some("ahri".to_string()));
+
+    assert!(stop_overlay_for_empty_loadout());
+    assert!(!is_global_overlay_running());
+    assert_eq!(running_overlay_signature(), None);
+    // Nothing left to stop
+    assert!(!stop_overlay_for_empty_loadout());
+  }
+}
@@ -1,3 +1,5 @@
+use crate::commands::config::get_extraction_limits_from_config;
+use crate::commands::file_operations::ensure_valid_skin_package;
 use crate::commands::types::SavedConfig;
 use crate::injection::MiscItem;
 use serde::{Deserialize, Serialize};
@@ -54,6 +56,9 @@ pub async fn upload_misc_item(
     .pick_file()
     .ok_or_else(|| "No file selected".to_string())?;
 
+  // Reject packages that could never be injected before copying them in
+  ensure_valid_skin_package(&selected_file, &get_extraction_limits_from_config(&app))?;
+
   // Generate unique ID for the misc item
   let item_id = format!(
     "{}_{}",
@@ -221,9 +226,18 @@ pub async fn upload_multiple_misc_items(
   };
 
   let mut uploaded_items = Vec::new();
+  let mut rejected = Vec::new();
 
   // Process each selected file
+  let limits = get_extraction_limits_from_config(&app);
   for file_path in files {
+    // Skip invalid packages instead of failing the whole batch
+    if let Err(e) = ensure_valid_skin_package(&file_path, &limits) {
+      println!("Skipping misc item: {}", e);
+      rejected.push(e);
+      continue;
+    }
+
     let file_name = file_path
       .file_stem()
       .and_then(|s| s.to_str())
@@ -272,6 +286,10 @@ pub async fn upload_multiple_misc_items(
     uploaded_items.push(misc_item);
   }
 
+  if uploaded_items.is_empty() && !rejected.is_empty() {
+    return Err(rejected.join("; "));
+  }
+
   // Save updated misc items
   let json_content = serde_json::to_string_pretty(&misc_items)
     .map_err(|e| format!("Failed to serialize misc items: {}", e))?;
@@ -10,6 +10,7 @@ pub mod config;
 pub mod config_lock;
 pub mod custom_skins;
 pub mod download_manager;
+pub mod error;
 pub mod file_operations;
 pub mod lcu_watcher;
 pub mod league_detection;
@@ -26,6 +27,7 @@ pub use config::*;
 pub use config_lock::*;
 pub use custom_skins::*;
 pub use download_manager::*;
+pub use error::*;
 pub use file_operations::*;
 pub use lcu_watcher::*;
 pub use league_detection::*;
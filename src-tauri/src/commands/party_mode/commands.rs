@@ -1,7 +1,8 @@
 // Tauri commands for party mode
 
 use super::lcu::{get_friend_display_name, get_friends_with_connection, get_lcu_connection};
-use super::types::{PARTY_MODE_VERBOSE, RECEIVED_SKINS, SENT_SKIN_SHARES};
+use super::types::{PARTY_MODE_VERBOSE, RECEIVED_SKINS, REJECTED_SKIN_PATHS, SENT_SKIN_SHARES};
+use crate::commands::error::{CommandError, ErrorCode, RemediationAction};
 use crate::commands::lcu_watcher::types::{
   current_time_ms, CHAMP_SELECT_SESSION_COUNTER, CHAMP_SELECT_START_TIME_MS,
   LAST_SHARED_CHAMPION_ID, LCU_WATCHER_ACTIVE, LCU_WATCHER_INSTANCE_ID,
@@ -16,9 +17,18 @@ use tauri::{AppHandle, Emitter, Manager, State};
 
 // Tauri command to get friends list from LCU
 #[tauri::command]
-pub async fn get_lcu_friends(app: AppHandle) -> Result<Vec<FriendInfo>, String> {
-  let lcu_connection = get_lcu_connection(&app).await?;
-  return get_friends_with_connection(&lcu_connection.port, &lcu_connection.token).await;
+pub async fn get_lcu_friends(app: AppHandle) -> Result<Vec<FriendInfo>, CommandError> {
+  let lcu_connection = get_lcu_connection(&app).await.map_err(|e| {
+    CommandError::caused(
+      ErrorCode::LeagueClientUnavailable,
+      "Failed to connect to the League client",
+      e,
+    )
+    .with_remediation(RemediationAction::StartLeagueClient)
+  })?;
+  get_friends_with_connection(&lcu_connection.port, &lcu_connection.token)
+    .await
+    .map_err(|e| CommandError::caused(ErrorCode::PartyMode, "Failed to get friends", e))
 }
 
 // Tauri command to add a friend directly to party mode
@@ -27,7 +37,7 @@ pub async fn add_party_friend(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
   friend_summoner_id: String,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
   println!(
     "[DEBUG] Adding friend to party mode: {}",
     friend_summoner_id
@@ -58,11 +68,11 @@ pub async fn remove_paired_friend(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
   friend_summoner_id: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   let _lock = config_lock
     .0
     .lock()
-    .map_err(|_| "Failed to lock config".to_string())?;
+    .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
   let config_dir = app
     .path()
@@ -75,11 +85,11 @@ pub async fn remove_paired_friend(
     return Ok(());
   }
 
-  let config_data =
-    std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
+  let config_data = std::fs::read_to_string(&config_file)
+    .map_err(|e| CommandError::config("Failed to read config", e))?;
 
-  let mut config: SavedConfig =
-    serde_json::from_str(&config_data).map_err(|e| format!("Failed to parse config: {}", e))?;
+  let mut config: SavedConfig = serde_json::from_str(&config_data)
+    .map_err(|e| CommandError::config("Failed to parse config", e))?;
 
   config
     .party_mode
@@ -87,10 +97,10 @@ pub async fn remove_paired_friend(
     .retain(|f| f.summoner_id != friend_summoner_id);
 
   let updated_config = serde_json::to_string_pretty(&config)
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
 
   std::fs::write(&config_file, updated_config)
-    .map_err(|e| format!("Failed to save config: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to save config", e))?;
 
   // Emit event to update UI components
   let _ = app.emit("party-mode-paired-friends-updated", ());
@@ -103,11 +113,11 @@ pub async fn set_party_mode_verbose_logging(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
   enabled: bool,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
   let _lock = config_lock
     .0
     .lock()
-    .map_err(|_| "Failed to lock config".to_string())?;
+    .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
   let config_dir = app
     .path()
@@ -117,9 +127,9 @@ pub async fn set_party_mode_verbose_logging(
   let config_file = config_dir.join("config.json");
 
   let mut config: SavedConfig = if config_file.exists() {
-    let raw =
-      std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
-    serde_json::from_str(&raw).map_err(|e| format!("Failed to parse config: {}", e))?
+    let raw = std::fs::read_to_string(&config_file)
+      .map_err(|e| CommandError::config("Failed to read config", e))?;
+    serde_json::from_str(&raw).map_err(|e| CommandError::config("Failed to parse config", e))?
   } else {
     SavedConfig {
       league_path: None,
@@ -134,6 +144,10 @@ pub async fn set_party_mode_verbose_logging(
       last_data_commit: None,
       cslol_tools_version: None,
       manual_injection_mode: false,
+      conflict_winners: std::collections::HashMap::new(),
+      load_order: crate::commands::types::default_load_order(),
+      extraction_limits: Default::default(),
+      persistent_overlay: false,
     }
   };
 
@@ -141,11 +155,11 @@ pub async fn set_party_mode_verbose_logging(
   PARTY_MODE_VERBOSE.store(enabled, Ordering::Relaxed);
 
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
   let serialized = serde_json::to_string_pretty(&config)
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
   std::fs::write(&config_file, serialized)
-    .map_err(|e| format!("Failed to persist config: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to persist config", e))?;
 
   let _ = app.emit(
     "party-mode-config-updated",
@@ -155,7 +169,7 @@ pub async fn set_party_mode_verbose_logging(
 }
 
 #[tauri::command]
-pub async fn get_party_mode_verbose_logging(_app: AppHandle) -> Result<bool, String> {
+pub async fn get_party_mode_verbose_logging(_app: AppHandle) -> Result<bool, CommandError> {
   Ok(PARTY_MODE_VERBOSE.load(Ordering::Relaxed))
 }
 
@@ -164,11 +178,11 @@ pub async fn set_party_mode_max_share_age(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
   seconds: u64,
-) -> Result<u64, String> {
+) -> Result<u64, CommandError> {
   let _lock = config_lock
     .0
     .lock()
-    .map_err(|_| "Failed to lock config".to_string())?;
+    .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
   let config_dir = app
     .path()
@@ -178,9 +192,9 @@ pub async fn set_party_mode_max_share_age(
   let config_file = config_dir.join("config.json");
 
   let mut config: SavedConfig = if config_file.exists() {
-    let raw =
-      std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
-    serde_json::from_str(&raw).map_err(|e| format!("Failed to parse config: {}", e))?
+    let raw = std::fs::read_to_string(&config_file)
+      .map_err(|e| CommandError::config("Failed to read config", e))?;
+    serde_json::from_str(&raw).map_err(|e| CommandError::config("Failed to parse config", e))?
   } else {
     SavedConfig {
       league_path: None,
@@ -195,17 +209,21 @@ pub async fn set_party_mode_max_share_age(
       last_data_commit: None,
       cslol_tools_version: None,
       manual_injection_mode: false,
+      conflict_winners: std::collections::HashMap::new(),
+      load_order: crate::commands::types::default_load_order(),
+      extraction_limits: Default::default(),
+      persistent_overlay: false,
     }
   };
 
   config.party_mode.max_share_age_secs = seconds;
 
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config dir", e))?;
   let serialized = serde_json::to_string_pretty(&config)
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
   std::fs::write(&config_file, serialized)
-    .map_err(|e| format!("Failed to persist config: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to persist config", e))?;
 
   let _ = app.emit(
     "party-mode-config-updated",
@@ -219,11 +237,11 @@ pub async fn set_party_mode_max_share_age(
 pub async fn get_paired_friends(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
-) -> Result<Vec<PairedFriend>, String> {
+) -> Result<Vec<PairedFriend>, CommandError> {
   let _lock = config_lock
     .0
     .lock()
-    .map_err(|_| "Failed to lock config".to_string())?;
+    .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
   let config_dir = app
     .path()
@@ -242,11 +260,11 @@ pub async fn get_paired_friends(
     return Ok(Vec::new());
   }
 
-  let config_data =
-    std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
+  let config_data = std::fs::read_to_string(&config_file)
+    .map_err(|e| CommandError::config("Failed to read config", e))?;
 
-  let config: SavedConfig =
-    serde_json::from_str(&config_data).map_err(|e| format!("Failed to parse config: {}", e))?;
+  let config: SavedConfig = serde_json::from_str(&config_data)
+    .map_err(|e| CommandError::config("Failed to parse config", e))?;
 
   println!(
     "[Party Mode] Loaded {} paired friends from config",
@@ -266,11 +284,11 @@ pub async fn get_paired_friends(
 pub async fn get_party_mode_settings(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
   let _lock = config_lock
     .0
     .lock()
-    .map_err(|_| "Failed to lock config".to_string())?;
+    .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
   let config_dir = app
     .path()
@@ -283,11 +301,11 @@ pub async fn get_party_mode_settings(
     return Ok(true); // Default notifications enabled
   }
 
-  let config_data =
-    std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
+  let config_data = std::fs::read_to_string(&config_file)
+    .map_err(|e| CommandError::config("Failed to read config", e))?;
 
-  let config: SavedConfig =
-    serde_json::from_str(&config_data).map_err(|e| format!("Failed to parse config: {}", e))?;
+  let config: SavedConfig = serde_json::from_str(&config_data)
+    .map_err(|e| CommandError::config("Failed to parse config", e))?;
 
   Ok(config.party_mode.notifications)
 }
@@ -297,11 +315,11 @@ pub async fn update_party_mode_settings(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
   notifications: bool,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   let _lock = config_lock
     .0
     .lock()
-    .map_err(|_| "Failed to lock config".to_string())?;
+    .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
   let config_dir = app
     .path()
@@ -311,13 +329,13 @@ pub async fn update_party_mode_settings(
   let config_file = config_dir.join("config.json");
 
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config directory", e))?;
 
   let mut config = if config_file.exists() {
-    let config_data =
-      std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
+    let config_data = std::fs::read_to_string(&config_file)
+      .map_err(|e| CommandError::config("Failed to read config", e))?;
     serde_json::from_str::<SavedConfig>(&config_data)
-      .map_err(|e| format!("Failed to parse config: {}", e))?
+      .map_err(|e| CommandError::config("Failed to parse config", e))?
   } else {
     SavedConfig {
       league_path: None,
@@ -332,16 +350,20 @@ pub async fn update_party_mode_settings(
       last_data_commit: None,
       cslol_tools_version: None,
       manual_injection_mode: false,
+      conflict_winners: std::collections::HashMap::new(),
+      load_order: crate::commands::types::default_load_order(),
+      extraction_limits: Default::default(),
+      persistent_overlay: false,
     }
   };
 
   config.party_mode.notifications = notifications;
 
   let updated_config = serde_json::to_string_pretty(&config)
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to serialize config", e))?;
 
   std::fs::write(&config_file, updated_config)
-    .map_err(|e| format!("Failed to save config: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to save config", e))?;
 
   Ok(())
 }
@@ -352,11 +374,11 @@ pub async fn update_party_mode_settings(
 pub async fn get_party_mode_diagnostic_state(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, CommandError> {
   let _lock = config_lock
     .0
     .lock()
-    .map_err(|_| "Failed to lock config".to_string())?;
+    .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
   // Gather session state
   let now_ms = current_time_ms();
@@ -407,6 +429,17 @@ pub async fn get_party_mode_diagnostic_state(
     set.iter().cloned().collect()
   };
 
+  // Gather friend skin paths refused by the path policy
+  let rejected_paths: Vec<serde_json::Value> = {
+    let rejected = REJECTED_SKIN_PATHS
+      .lock()
+      .map_err(|e| format!("Failed to lock REJECTED_SKIN_PATHS: {}", e))?;
+    rejected
+      .iter()
+      .map(|r| serde_json::to_value(r).unwrap_or_default())
+      .collect()
+  };
+
   // Get paired friends from config
   let config_dir = app
     .path()
@@ -416,10 +449,10 @@ pub async fn get_party_mode_diagnostic_state(
   let config_file = config_dir.join("config.json");
 
   let paired_friends: Vec<serde_json::Value> = if config_file.exists() {
-    let config_data =
-      std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
-    let config: SavedConfig =
-      serde_json::from_str(&config_data).map_err(|e| format!("Failed to parse config: {}", e))?;
+    let config_data = std::fs::read_to_string(&config_file)
+      .map_err(|e| CommandError::config("Failed to read config", e))?;
+    let config: SavedConfig = serde_json::from_str(&config_data)
+      .map_err(|e| CommandError::config("Failed to parse config", e))?;
     config
       .party_mode
       .paired_friends
@@ -460,6 +493,8 @@ pub async fn get_party_mode_diagnostic_state(
       "received_skins": received_skins,
       "sent_shares_count": sent_shares.len(),
       "sent_shares": sent_shares,
+      "rejected_paths_count": rejected_paths.len(),
+      "rejected_paths": rejected_paths,
     },
   });
 
@@ -481,7 +516,7 @@ pub async fn get_party_mode_diagnostic_state(
 pub async fn resend_skin_to_friends(
   app: AppHandle,
   config_lock: State<'_, ConfigLock>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, CommandError> {
   use crate::commands::party_mode::send_skin_share_to_paired_friends;
 
   println!("[Party Mode] Manual resend triggered by user");
@@ -491,7 +526,7 @@ pub async fn resend_skin_to_friends(
     let _lock = config_lock
       .0
       .lock()
-      .map_err(|_| "Failed to lock config".to_string())?;
+      .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
     // Get config to find current champion and skin selection
     let config_dir = app
@@ -502,21 +537,25 @@ pub async fn resend_skin_to_friends(
     let config_file = config_dir.join("config.json");
 
     if !config_file.exists() {
-      return Err("Config file not found".to_string());
+      return Err(CommandError::new(
+        ErrorCode::Config,
+        "Config file not found",
+      ));
     }
 
-    let config_data =
-      std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
-    let config: SavedConfig =
-      serde_json::from_str(&config_data).map_err(|e| format!("Failed to parse config: {}", e))?;
+    let config_data = std::fs::read_to_string(&config_file)
+      .map_err(|e| CommandError::config("Failed to read config", e))?;
+    let config: SavedConfig = serde_json::from_str(&config_data)
+      .map_err(|e| CommandError::config("Failed to parse config", e))?;
 
     // Get the last shared champion ID
     let last_shared = LAST_SHARED_CHAMPION_ID.load(Ordering::SeqCst) as u32;
 
     if last_shared == 0 {
-      return Err(
-        "No champion has been shared yet in this session. Select a champion first.".to_string(),
-      );
+      return Err(CommandError::new(
+        ErrorCode::PartyMode,
+        "No champion has been shared yet in this session. Select a champion first.",
+      ));
     }
 
     // Find the skin for this champion
@@ -524,7 +563,13 @@ pub async fn resend_skin_to_friends(
       .skins
       .iter()
       .find(|s| s.champion_id == last_shared)
-      .ok_or_else(|| format!("No skin configured for champion {}", last_shared))?;
+      .ok_or_else(|| {
+        CommandError::new(
+          ErrorCode::PartyMode,
+          format!("No skin configured for champion {}", last_shared),
+        )
+        .with_context("champion_id", last_shared)
+      })?;
 
     println!(
       "[Party Mode] Resending skin {} for champion {} to all friends (force_send_to_all=true)",
@@ -574,7 +619,11 @@ pub async fn resend_skin_to_friends(
     }
     Err(e) => {
       println!("[Party Mode] Manual resend failed: {}", e);
-      Err(format!("Failed to resend skin: {}", e))
+      Err(
+        CommandError::caused(ErrorCode::PartyMode, "Failed to resend skin", e)
+          .with_context("champion_id", champion_id)
+          .with_context("skin_id", skin_id),
+      )
     }
   }
 }
@@ -586,11 +635,11 @@ async fn add_paired_friend(
   friend_summoner_id: &str,
   friend_name: &str,
   share_enabled: bool,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   let _lock = config_lock
     .0
     .lock()
-    .map_err(|_| "Failed to lock config".to_string())?;
+    .map_err(|_| CommandError::new(ErrorCode::Config, "Failed to lock config"))?;
 
   let config_dir = app
     .path()
@@ -600,13 +649,13 @@ async fn add_paired_friend(
   let config_file = config_dir.join("config.json");
 
   std::fs::create_dir_all(&config_dir)
-    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    .map_err(|e| CommandError::config("Failed to create config directory", e))?;
 
   let mut config = if config_file.exists() {
-    let config_data =
-      std::fs::read_to_string(&config_file).map_err(|e| format!("Failed to read config: {}", e))?;
+    let config_data = std::fs::read_to_string(&config_file)
+      .map_err(|e| CommandError::config("Failed to read config", e))?;
     serde_json::from_str::<SavedConfig>(&config_data)
-      .map_err(|e| format!("Failed to parse config: {}", e))?
+      .map_err(|e| CommandError::config("Failed to parse config", e))?
   } else {
     SavedConfig {
       league_path: None,
@@ -621,6 +670,10 @@ async fn add_paired_friend(
       last_data_commit: None,
       cslol_tools_version: None,
       manual_injection_mode: false,
+      conflict_winners: std::collections::HashMap::new(),
+      load_order: crate::commands::types::default_load_order(),
+      extraction_limits: Default::default(),
+      persistent_overlay: false,
     }
   };
 
@@ -640,10 +693,10 @@ async fn add_paired_friend(
     });
 
     let updated_config = serde_json::to_string_pretty(&config)
-      .map_err(|e| format!("Failed to serialize config: {}", e))?;
+      .map_err(|e| CommandError::config("Failed to serialize config", e))?;
 
     std::fs::write(&config_file, updated_config)
-      .map_err(|e| format!("Failed to save config: {}", e))?;
+      .map_err(|e| CommandError::config("Failed to save config", e))?;
 
     // Emit event to update UI components
     let _ = app.emit("party-mode-paired-friends-updated", ());
@@ -13,11 +13,11 @@ pub mod utils;
 mod tests;
 
 // Re-export public types and functions
-pub use types::{PARTY_MODE_VERBOSE, RECEIVED_SKINS};
+pub use types::{InMemoryReceivedSkin, PARTY_MODE_VERBOSE, RECEIVED_SKINS};
 
 pub use utils::clear_sent_shares;
 
-pub use session::clear_received_skins;
+pub use session::{clear_received_skins, record_rejected_skin_path};
 
 pub use commands::{
   add_party_friend, get_lcu_friends, get_paired_friends, get_party_mode_diagnostic_state,
@@ -1,10 +1,16 @@
 // Session tracking and skin management
 
 use super::lcu::get_lcu_connection;
-use super::types::{CURRENT_SESSION_ID, RECEIVED_SKINS};
+use super::types::{
+  InMemoryReceivedSkin, RejectedSkinPath, CURRENT_SESSION_ID, MAX_REJECTED_SKIN_PATHS,
+  RECEIVED_SKINS, REJECTED_SKIN_PATHS,
+};
+use crate::commands::lcu_watcher::types::current_time_ms;
+use crate::injection::path_policy::PathRejection;
 use super::utils::get_configured_max_share_age_secs;
 use crate::normal_log;
 use base64::{engine::general_purpose, Engine};
+use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::AppHandle;
 
@@ -105,3 +111,31 @@ pub fn clear_received_skins() {
   map.clear();
   normal_log!("[Party Mode][STATE] cleared received skins {} -> 0", before);
 }
+
+// Remember a friend's skin path that the path policy refused
+pub fn record_rejected_skin_path(
+  received_skin: &InMemoryReceivedSkin,
+  resolved_path: &Path,
+  reason: PathRejection,
+) {
+  normal_log!(
+    "[Party Mode][WARN] Rejecting skin path {} from {}: {}",
+    resolved_path.display(),
+    received_skin.from_summoner_name,
+    reason
+  );
+  let mut rejected = REJECTED_SKIN_PATHS.lock().unwrap();
+  if rejected.len() >= MAX_REJECTED_SKIN_PATHS {
+    rejected.remove(0);
+  }
+  rejected.push(RejectedSkinPath {
+    from_summoner_id: received_skin.from_summoner_id.clone(),
+    from_summoner_name: received_skin.from_summoner_name.clone(),
+    champion_id: received_skin.champion_id,
+    skin_id: received_skin.skin_id,
+    requested_path: received_skin.skin_file_path.clone(),
+    resolved_path: resolved_path.display().to_string(),
+    reason,
+    rejected_at: current_time_ms(),
+  });
+}
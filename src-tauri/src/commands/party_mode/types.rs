@@ -1,6 +1,8 @@
 // Types and static variables for party mode
 
+use crate::injection::path_policy::PathRejection;
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
@@ -28,6 +30,26 @@ pub static PARTY_MODE_VERBOSE: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(f
 pub static SENT_SKIN_SHARES: Lazy<Mutex<std::collections::HashSet<String>>> =
   Lazy::new(|| Mutex::new(std::collections::HashSet::new()));
 
+// Friend skin paths refused by the path policy, newest last; shown in the diagnostic state
+pub static REJECTED_SKIN_PATHS: Lazy<Mutex<Vec<RejectedSkinPath>>> =
+  Lazy::new(|| Mutex::new(Vec::new()));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedSkinPath {
+  pub from_summoner_id: String,
+  pub from_summoner_name: String,
+  pub champion_id: u32,
+  pub skin_id: u32,
+  // Path as sent by the friend
+  pub requested_path: Option<String>,
+  // Local path it resolved to
+  pub resolved_path: String,
+  pub reason: PathRejection,
+  pub rejected_at: u64,
+}
+
+pub const MAX_REJECTED_SKIN_PATHS: usize = 50;
+
 pub const PARTY_MODE_MESSAGE_PREFIX: &str = "OSS:";
 pub const MAX_SHARE_AGE_SECS: u64 = 300;
 
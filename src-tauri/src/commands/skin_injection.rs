@@ -1,8 +1,18 @@
 use crate::commands::config::save_league_path;
+use crate::commands::error::{CommandError, ErrorCode, RemediationAction};
 use crate::commands::lcu_watcher::start_lcu_watcher;
 use crate::commands::types::{SavedConfig, SkinData, SkinInjectionRequest};
 use crate::commands::{ensure_mod_tools, load_league_path};
-use crate::injection::{inject_skins as inject_skins_impl, inject_skins_and_misc, MiscItem, Skin};
+use crate::injection::deadline::TimeoutReason;
+use crate::injection::overlay::EntryConflict;
+use crate::injection::plan::{plan_injection as plan_injection_impl, InjectionPlan};
+use crate::injection::queue::queue_status;
+use crate::injection::timing::{TimingReport, TimingStage};
+use crate::injection::history::{InjectionJournal, InjectionRecord};
+use crate::injection::{
+  inject_skins as inject_skins_impl, inject_skins_and_misc, InjectionError, InjectionTrigger,
+  MiscItem, Skin,
+};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -44,7 +54,24 @@ pub struct InjectionStateSnapshot {
   pub status: InjectionStatusValue,
   pub status_message: Option<String>,  // Detailed status like "Waiting for league match to start"
   pub last_error: Option<String>,
+  // Code, context and remediation of last_error
+  #[serde(default)]
+  pub last_error_detail: Option<CommandError>,
   pub updated_at_ms: u64,
+  // WAD entries written by more than one mod in the current overlay
+  #[serde(default)]
+  pub conflicts: Vec<EntryConflict>,
+  // Stage timings of the latest injection
+  #[serde(default)]
+  pub timing: Option<TimingReport>,
+  // Set when the latest injection was cut down or gave up because champ select ran short
+  #[serde(default)]
+  pub timeout: Option<TimeoutReason>,
+  // The injection job being worked on and how many wait behind it, see queue.rs
+  #[serde(default)]
+  pub current_job_id: Option<String>,
+  #[serde(default)]
+  pub queue_depth: usize,
 }
 
 static INJECTION_STATE: Lazy<RwLock<InjectionStateSnapshot>> = Lazy::new(|| RwLock::new(
@@ -52,7 +79,13 @@ static INJECTION_STATE: Lazy<RwLock<InjectionStateSnapshot>> = Lazy::new(|| RwLo
     status: InjectionStatusValue::Idle,
     status_message: None,
     last_error: None,
+    last_error_detail: None,
     updated_at_ms: current_millis(),
+    conflicts: Vec::new(),
+    timing: None,
+    timeout: None,
+    current_job_id: None,
+    queue_depth: 0,
   },
 ));
 
@@ -66,7 +99,7 @@ fn current_millis() -> u64 {
 pub fn record_injection_state(
   app: &AppHandle,
   status: InjectionStatusValue,
-  error: Option<String>,
+  error: Option<CommandError>,
 ) {
   record_injection_state_with_message(app, status, None, error);
 }
@@ -75,7 +108,7 @@ pub fn record_injection_state_with_message(
   app: &AppHandle,
   status: InjectionStatusValue,
   message: Option<String>,
-  error: Option<String>,
+  error: Option<CommandError>,
 ) {
   {
     let mut guard = INJECTION_STATE
@@ -83,7 +116,8 @@ pub fn record_injection_state_with_message(
       .expect("INJECTION_STATE poisoned");
     guard.status = status;
     guard.status_message = message.clone();
-    guard.last_error = error.clone();
+    guard.last_error = error.as_ref().map(|e| e.message.clone());
+    guard.last_error_detail = error.clone();
     guard.updated_at_ms = current_millis();
   }
 
@@ -106,14 +140,129 @@ pub fn record_injection_state_with_message(
   }
 }
 
+// Store the conflict report for the overlay being built and notify the frontend
+pub fn record_mod_conflicts(app: &AppHandle, conflicts: Vec<EntryConflict>) {
+  {
+    let mut guard = INJECTION_STATE
+      .write()
+      .expect("INJECTION_STATE poisoned");
+    guard.conflicts = conflicts.clone();
+    guard.updated_at_ms = current_millis();
+  }
+
+  let _ = app.emit("injection-conflicts", conflicts);
+}
+
+// Store the timing report of the latest injection and notify the frontend
+pub fn record_injection_timing(app: &AppHandle, report: TimingReport) {
+  {
+    let mut guard = INJECTION_STATE
+      .write()
+      .expect("INJECTION_STATE poisoned");
+    guard.timing = Some(report.clone());
+    guard.updated_at_ms = current_millis();
+  }
+
+  let _ = app.emit("injection-timing", report);
+}
+
+// Store why the running injection ran short of time (None when it starts) and notify the frontend
+pub fn record_injection_timeout(app: &AppHandle, reason: Option<TimeoutReason>) {
+  {
+    let mut guard = INJECTION_STATE
+      .write()
+      .expect("INJECTION_STATE poisoned");
+    guard.timeout = reason.clone();
+    guard.updated_at_ms = current_millis();
+  }
+
+  let _ = app.emit("injection-timeout", reason);
+}
+
+// Add a stage measured after the injection returned, such as the overlay reaching
+// "Waiting for league". Only the first measurement for the latest injection counts.
+pub fn record_late_injection_timing(
+  app: &AppHandle,
+  injection_id: &str,
+  stage: TimingStage,
+  duration: std::time::Duration,
+) {
+  let report = {
+    let mut guard = INJECTION_STATE
+      .write()
+      .expect("INJECTION_STATE poisoned");
+    match guard.timing.as_mut() {
+      Some(report)
+        if report.injection_id == injection_id && report.duration_of(stage).is_none() =>
+      {
+        report.add(stage, duration);
+        report.clone()
+      }
+      _ => return,
+    }
+  };
+
+  let _ = app.emit("injection-timing", report);
+}
+
 #[tauri::command]
-pub fn get_injection_state() -> InjectionStateSnapshot {
+pub fn get_mod_conflicts() -> Vec<EntryConflict> {
   INJECTION_STATE
     .read()
     .expect("INJECTION_STATE poisoned")
+    .conflicts
     .clone()
 }
 
+#[tauri::command]
+pub fn get_injection_state() -> InjectionStateSnapshot {
+  let mut snapshot = INJECTION_STATE
+    .read()
+    .expect("INJECTION_STATE poisoned")
+    .clone();
+  let queue = queue_status();
+  snapshot.current_job_id = queue.current_job_id;
+  snapshot.queue_depth = queue.queue_depth;
+  snapshot
+}
+
+// Abort the injection that is running or waiting for its turn, e.g. after a dodge.
+// Returns false when there was nothing to cancel.
+#[tauri::command]
+pub fn cancel_injection() -> bool {
+  let cancelled = crate::injection::queue::cancel_injections();
+  if cancelled > 0 {
+    crate::commands::lcu_watcher::append_global_log(&format!(
+      "[Injection] Cancelling {} in-flight injection(s)",
+      cancelled
+    ));
+  }
+  cancelled > 0
+}
+
+// Past injections, newest first, with whether mod-tools patched the game for each
+#[tauri::command]
+pub fn get_injection_history(app: AppHandle) -> Result<Vec<InjectionRecord>, CommandError> {
+  let app_data_dir = app.path().app_data_dir().map_err(|e| {
+    CommandError::caused(ErrorCode::FileSystem, "Failed to get app data directory", e)
+  })?;
+  let mut history = InjectionJournal::for_app_dir(&app_data_dir)
+    .load()
+    .map_err(|e| CommandError::from(e).prefixed("Failed to read injection history"))?;
+  history.reverse();
+  Ok(history)
+}
+
+#[tauri::command]
+pub fn clear_injection_history(app: AppHandle) -> Result<(), CommandError> {
+  let app_data_dir = app.path().app_data_dir().map_err(|e| {
+    CommandError::caused(ErrorCode::FileSystem, "Failed to get app data directory", e)
+  })?;
+  InjectionJournal::for_app_dir(&app_data_dir)
+    .clear()
+    .map_err(|e| CommandError::from(e).prefixed("Failed to clear injection history"))
+}
+
 pub fn get_injection_status() -> InjectionStatusValue {
   INJECTION_STATE
     .read()
@@ -124,7 +273,10 @@ pub fn get_injection_status() -> InjectionStatusValue {
 // Skin injection related commands
 
 #[tauri::command]
-pub fn inject_skins(app: tauri::AppHandle, request: SkinInjectionRequest) -> Result<(), String> {
+pub fn inject_skins(
+  app: tauri::AppHandle,
+  request: SkinInjectionRequest,
+) -> Result<(), CommandError> {
   println!("Starting skin injection process");
   println!("League path: {}", request.league_path);
   println!("Number of skins to inject: {}", request.skins.len());
@@ -148,6 +300,7 @@ pub fn inject_skins(app: tauri::AppHandle, request: SkinInjectionRequest) -> Res
     &request.league_path,
     &request.skins,
     &skin_file_files_dir,
+    InjectionTrigger::Manual,
   );
 
   // Handle result with proper error propagation to frontend
@@ -162,11 +315,17 @@ pub fn inject_skins(app: tauri::AppHandle, request: SkinInjectionRequest) -> Res
       );
       Ok(())
     }
+    Err(InjectionError::Superseded(reason)) => {
+      println!("Skin injection superseded: {}", reason);
+      Ok(())
+    }
     Err(err) => {
       println!("Skin injection failed: {}", err);
-      let message = format!("Injection failed: {}", err);
-      record_injection_state(&app, InjectionStatusValue::Error, Some(message.clone()));
-      Err(message)
+      let error = CommandError::from(err)
+        .prefixed("Injection failed")
+        .with_context("league_path", &request.league_path);
+      record_injection_state(&app, InjectionStatusValue::Error, Some(error.clone()));
+      Err(error)
     }
   }
 }
@@ -177,7 +336,7 @@ pub async fn inject_game_skins(
   game_path: String,
   skins: Vec<SkinData>,
   skin_file_files_dir: String,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
   println!("Starting skin injection process");
   println!("League path: {}", game_path);
   println!("Number of skins to inject: {}", skins.len());
@@ -188,9 +347,14 @@ pub async fn inject_game_skins(
 
   // Validate game path exists
   if !Path::new(&game_path).exists() {
-    let message = format!("League of Legends directory not found: {}", game_path);
-    record_injection_state(&app_handle, InjectionStatusValue::Error, Some(message.clone()));
-    return Err(message);
+    let error = CommandError::new(
+      ErrorCode::InvalidGamePath,
+      format!("League of Legends directory not found: {}", game_path),
+    )
+    .with_context("game_path", &game_path)
+    .with_remediation(RemediationAction::SelectGameDirectory);
+    record_injection_state(&app_handle, InjectionStatusValue::Error, Some(error.clone()));
+    return Err(error);
   }
 
   // Validate skin_file directory exists
@@ -202,9 +366,11 @@ pub async fn inject_game_skins(
       base_path.display()
     );
     fs::create_dir_all(base_path).map_err(|e| {
-      let message = format!("Failed to create skin_file directory: {}", e);
-      record_injection_state(&app_handle, InjectionStatusValue::Error, Some(message.clone()));
-      message
+      let error = CommandError::from(e)
+        .prefixed("Failed to create skin_file directory")
+        .with_context("path", base_path.display());
+      record_injection_state(&app_handle, InjectionStatusValue::Error, Some(error.clone()));
+      error
     })?;
   }
 
@@ -223,7 +389,13 @@ pub async fn inject_game_skins(
     .collect();
 
   // Call the injection function
-  let result = match inject_skins_impl(&app_handle, &game_path, &internal_skins, base_path) {
+  let result = match inject_skins_impl(
+    &app_handle,
+    &game_path,
+    &internal_skins,
+    base_path,
+    InjectionTrigger::Manual,
+  ) {
     Ok(_) => {
       println!("Skin injection completed successfully");
       record_injection_state_with_message(
@@ -234,15 +406,21 @@ pub async fn inject_game_skins(
       );
       Ok("Skin injection completed successfully".to_string())
     }
+    Err(InjectionError::Superseded(reason)) => {
+      println!("Skin injection superseded: {}", reason);
+      Ok(format!("Skin injection superseded: {}", reason))
+    }
     Err(e) => {
       println!("Skin injection failed: {}", e);
-      let message = format!("Skin injection failed: {}", e);
+      let error = CommandError::from(e)
+        .prefixed("Skin injection failed")
+        .with_context("game_path", &game_path);
       record_injection_state(
         &app_handle,
         InjectionStatusValue::Error,
-        Some(message.clone()),
+        Some(error.clone()),
       );
-      Err(message)
+      Err(error)
     }
   };
 
@@ -257,7 +435,7 @@ pub async fn inject_skins_with_misc(
   skins: Vec<SkinData>,
   misc_items: Vec<MiscItem>,
   skin_file_files_dir: String,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
   println!("Starting enhanced skin injection process");
   println!("League path: {}", game_path);
   println!("Number of skins to inject: {}", skins.len());
@@ -268,9 +446,14 @@ pub async fn inject_skins_with_misc(
 
   // Validate game path exists
   if !Path::new(&game_path).exists() {
-    let message = format!("League of Legends directory not found: {}", game_path);
-    record_injection_state(&app_handle, InjectionStatusValue::Error, Some(message.clone()));
-    return Err(message);
+    let error = CommandError::new(
+      ErrorCode::InvalidGamePath,
+      format!("League of Legends directory not found: {}", game_path),
+    )
+    .with_context("game_path", &game_path)
+    .with_remediation(RemediationAction::SelectGameDirectory);
+    record_injection_state(&app_handle, InjectionStatusValue::Error, Some(error.clone()));
+    return Err(error);
   }
 
   // Validate skin_file directory exists
@@ -282,9 +465,11 @@ pub async fn inject_skins_with_misc(
       base_path.display()
     );
     fs::create_dir_all(base_path).map_err(|e| {
-      let message = format!("Failed to create skin_file directory: {}", e);
-      record_injection_state(&app_handle, InjectionStatusValue::Error, Some(message.clone()));
-      message
+      let error = CommandError::from(e)
+        .prefixed("Failed to create skin_file directory")
+        .with_context("path", base_path.display());
+      record_injection_state(&app_handle, InjectionStatusValue::Error, Some(error.clone()));
+      error
     })?;
   }
 
@@ -309,6 +494,7 @@ pub async fn inject_skins_with_misc(
     &internal_skins,
     &misc_items,
     base_path,
+    InjectionTrigger::Manual,
   ) {
     Ok(_) => {
       println!("Enhanced skin injection completed successfully");
@@ -320,23 +506,62 @@ pub async fn inject_skins_with_misc(
       );
       Ok("Enhanced skin injection completed successfully".to_string())
     }
+    Err(InjectionError::Superseded(reason)) => {
+      println!("Enhanced skin injection superseded: {}", reason);
+      Ok(format!("Enhanced skin injection superseded: {}", reason))
+    }
     Err(e) => {
       println!("Enhanced skin injection failed: {}", e);
-      let message = format!("Enhanced skin injection failed: {}", e);
+      let error = CommandError::from(e)
+        .prefixed("Enhanced skin injection failed")
+        .with_context("game_path", &game_path);
       record_injection_state(
         &app_handle,
         InjectionStatusValue::Error,
-        Some(message.clone()),
+        Some(error.clone()),
       );
-      Err(message)
+      Err(error)
     }
   };
 
   result
 }
 
+// Dry run of inject_skins_with_misc: resolves and validates every item without touching
+// Game/mods or starting mod-tools
 #[tauri::command]
-pub async fn start_auto_inject(app: AppHandle, league_path: String) -> Result<(), String> {
+pub async fn plan_injection(
+  app_handle: AppHandle,
+  game_path: String,
+  skins: Vec<SkinData>,
+  misc_items: Vec<MiscItem>,
+  skin_file_files_dir: String,
+) -> Result<InjectionPlan, CommandError> {
+  let internal_skins: Vec<Skin> = skins
+    .iter()
+    .map(|s| Skin {
+      champion_id: s.champion_id,
+      skin_id: s.skin_id,
+      chroma_id: s.chroma_id,
+      skin_file_path: s.skin_file.clone(),
+    })
+    .collect();
+
+  tokio::task::spawn_blocking(move || {
+    plan_injection_impl(
+      &app_handle,
+      &game_path,
+      &internal_skins,
+      &misc_items,
+      Path::new(&skin_file_files_dir),
+    )
+  })
+  .await
+  .map_err(|e| format!("Planning task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn start_auto_inject(app: AppHandle, league_path: String) -> Result<(), CommandError> {
   println!("Starting auto-inject for path: {}", league_path);
 
   // Start the LCU watcher in a separate thread
@@ -372,23 +597,28 @@ pub fn get_all_skins_for_injection(config: &SavedConfig) -> Vec<Skin> {
   all_skins
 }
 
+fn no_league_path() -> CommandError {
+  CommandError::new(ErrorCode::InvalidGamePath, "No League path configured")
+    .with_remediation(RemediationAction::SelectGameDirectory)
+}
+
 // Command to inject all selected skins from config
 #[tauri::command]
-pub async fn inject_all_selected_skins(app: AppHandle) -> Result<(), String> {
+pub async fn inject_all_selected_skins(app: AppHandle) -> Result<(), CommandError> {
   // Load config
   let config = crate::commands::config::load_config(app.clone()).await?;
 
   // Get league path
-  let league_path = config
-    .league_path
-    .clone()
-    .ok_or("No League path configured")?;
+  let league_path = config.league_path.clone().ok_or_else(no_league_path)?;
 
   // Convert config to injection-ready skins
   let skins = get_all_skins_for_injection(&config);
 
   if skins.is_empty() {
-    return Err("No skins selected for injection".to_string());
+    return Err(CommandError::new(
+      ErrorCode::InvalidArgument,
+      "No skins selected for injection",
+    ));
   }
 
   // Get the app data directory for skin_file files
@@ -412,6 +642,7 @@ pub async fn inject_all_selected_skins(app: AppHandle) -> Result<(), String> {
     &skins,
     &misc_items,
     &skin_file_files_dir,
+    InjectionTrigger::Manual,
   );
 
   match result {
@@ -429,14 +660,18 @@ pub async fn inject_all_selected_skins(app: AppHandle) -> Result<(), String> {
       );
       Ok(())
     }
+    Err(InjectionError::Superseded(reason)) => {
+      println!("Injection superseded: {}", reason);
+      Ok(())
+    }
     Err(e) => {
-      let message = e.to_string();
+      let error = CommandError::from(e).with_context("league_path", &league_path);
       record_injection_state(
         &app,
         InjectionStatusValue::Error,
-        Some(message.clone()),
+        Some(error.clone()),
       );
-      Err(message)
+      Err(error)
     }
   }
 }
@@ -455,8 +690,8 @@ fn run_mod_tools_warmup(
   mods_dir: &Path,
   overlay_dir: &Path,
 ) -> Result<(), String> {
-  // Best-effort warmup: build a minimal overlay to warm caches and AV
-  // This pre-warms Windows Defender by exercising both mkoverlay and runoverlay
+  // Best-effort warmup: overlays are built natively now, so only runoverlay needs
+  // to be exercised to pre-warm Windows Defender
   println!("[Warmup] Starting mod-tools warmup...");
 
   if overlay_dir.exists() {
@@ -475,41 +710,11 @@ fn run_mod_tools_warmup(
   let game_dir_str = game_dir
     .to_str()
     .ok_or_else(|| "Invalid game path".to_string())?;
-  let mods_dir_str = mods_dir
-    .to_str()
-    .ok_or_else(|| "Invalid mods path".to_string())?;
   let overlay_dir_str = overlay_dir
     .to_str()
     .ok_or_else(|| "Invalid overlay path".to_string())?;
 
-  // Step 1: Run mkoverlay to warm that code path
-  let mut cmd = std::process::Command::new(mod_tools_path);
-  cmd.args([
-    "mkoverlay",
-    mods_dir_str,
-    overlay_dir_str,
-    &format!("--game:{}", game_dir_str),
-    "--mods:", // empty mods list just to warm binary and filesystem
-    "--noTFT",
-    "--ignoreConflict",
-  ]);
-
-  #[cfg(target_os = "windows")]
-  cmd.creation_flags(crate::injection::core::CREATE_NO_WINDOW);
-
-  match cmd.status() {
-    Ok(status) if status.success() => {
-      println!("[Warmup] mod-tools mkoverlay warmup succeeded");
-    }
-    Ok(status) => {
-      println!("[Warmup] mod-tools mkoverlay warmup completed with status {}", status);
-    }
-    Err(e) => {
-      println!("[Warmup] Failed to spawn mod-tools mkoverlay for warmup: {}", e);
-    }
-  }
-
-  // Step 2: Also warm up runoverlay code path (it will exit quickly since game isn't running)
+  // Warm up the runoverlay code path (it will exit quickly since game isn't running)
   // This pre-loads the DLL injection code and warms Windows Defender for that path too
   let config_path = overlay_dir.join("warmup-config.json");
   let _ = fs::write(&config_path, r#"{"enableMods":true}"#);
@@ -549,7 +754,10 @@ fn run_mod_tools_warmup(
 }
 
 #[tauri::command]
-pub async fn warmup_injection(app: AppHandle, game_path: Option<String>) -> Result<(), String> {
+pub async fn warmup_injection(
+  app: AppHandle,
+  game_path: Option<String>,
+) -> Result<(), CommandError> {
   // Ensure tools exist and load once to warm Windows Defender caches
   let ensured = ensure_mod_tools(app.clone(), Some(false)).await?;
 
@@ -611,7 +819,7 @@ pub async fn start_manual_injection(
   app: AppHandle,
   skins: Vec<SkinData>,
   misc_items: Vec<MiscItem>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   println!("[Manual Injection] Starting manual injection mode");
   println!("[Manual Injection] Skins to inject: {}", skins.len());
   println!(
@@ -666,7 +874,7 @@ pub async fn start_manual_injection(
 
 // Stop manual injection mode
 #[tauri::command]
-pub async fn stop_manual_injection(app: AppHandle) -> Result<(), String> {
+pub async fn stop_manual_injection(app: AppHandle) -> Result<(), CommandError> {
   println!("[Manual Injection] Stopping manual injection mode");
 
   // Deactivate manual injection
@@ -679,7 +887,8 @@ pub async fn stop_manual_injection(app: AppHandle) -> Result<(), String> {
     *guard = None;
   }
 
-  // Clean up any active injection
+  // Stop an injection that is still being prepared, then clean up
+  cancel_injection();
   let config = crate::commands::config::load_config(app.clone()).await?;
   if let Some(league_path) = config.league_path {
     let _ = crate::injection::cleanup_injection(&app, &league_path);
@@ -729,7 +938,7 @@ pub fn is_manual_injection_triggered() -> bool {
 }
 
 // Trigger manual injection (called by LCU watcher when entering champ select or manually)
-pub async fn trigger_manual_injection(app: &AppHandle) -> Result<(), String> {
+pub async fn trigger_manual_injection(app: &AppHandle) -> Result<(), CommandError> {
   println!("[Manual Injection] Triggering manual injection");
 
   // Check if manual injection is active
@@ -761,18 +970,19 @@ pub async fn trigger_manual_injection(app: &AppHandle) -> Result<(), String> {
     Some(d) => d,
     None => {
       println!("[Manual Injection] No injection data found");
+      let error = CommandError::new(ErrorCode::InvalidArgument, "No injection data found");
       record_injection_state(
         app,
         InjectionStatusValue::Error,
-        Some("No injection data found".to_string()),
+        Some(error.clone()),
       );
-      return Err("No injection data found".to_string());
+      return Err(error);
     }
   };
 
   // Get league path from config
   let config = crate::commands::config::load_config(app.clone()).await?;
-  let league_path = config.league_path.ok_or("No League path configured")?;
+  let league_path = config.league_path.ok_or_else(no_league_path)?;
 
   // Get skin_file files directory
   let app_data_dir = app
@@ -792,6 +1002,7 @@ pub async fn trigger_manual_injection(app: &AppHandle) -> Result<(), String> {
     &data.skins,
     &data.misc_items,
     &skin_file_files_dir,
+    InjectionTrigger::Manual,
   );
 
   match result {
@@ -813,11 +1024,15 @@ pub async fn trigger_manual_injection(app: &AppHandle) -> Result<(), String> {
       );
       Ok(())
     }
+    Err(InjectionError::Superseded(reason)) => {
+      println!("[Manual Injection] Superseded: {}", reason);
+      Ok(())
+    }
     Err(e) => {
-      let message = e.to_string();
-      record_injection_state(app, InjectionStatusValue::Error, Some(message.clone()));
+      let error = CommandError::from(e).with_context("league_path", &league_path);
+      record_injection_state(app, InjectionStatusValue::Error, Some(error.clone()));
       let _ = app.emit("manual-injection-status", "error");
-      Err(message)
+      Err(error)
     }
   }
 }
@@ -80,6 +80,25 @@ pub struct SavedConfig {
   pub cslol_tools_version: Option<String>,
   #[serde(default = "default_manual_injection_mode")]
   pub manual_injection_mode: bool,
+  // Overlay conflict choices: WAD entry path hash (hex) -> mod directory name that wins
+  #[serde(default)]
+  pub conflict_winners: std::collections::HashMap<String, String>,
+  // Overlay layering priority by mod category, lowest first
+  #[serde(default = "default_load_order")]
+  pub load_order: Vec<String>,
+  // Caps on archive conversion (total/entry bytes, entry count, compression ratio)
+  #[serde(default)]
+  pub extraction_limits: crate::injection::skin_file::ExtractionLimits,
+  // Automatic mode keeps runoverlay waiting between games and reuses it for the same loadout
+  #[serde(default)]
+  pub persistent_overlay: bool,
+}
+
+pub fn default_load_order() -> Vec<String> {
+  crate::injection::overlay::DEFAULT_LOAD_ORDER
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
 }
 
 fn default_manual_injection_mode() -> bool {
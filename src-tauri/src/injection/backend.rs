@@ -0,0 +1,583 @@
+#[cfg(target_os = "windows")]
+use crate::injection::core::CREATE_NO_WINDOW;
+use crate::injection::error::InjectionError;
+use std::env;
+use std::io::{self, Read, Write};
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+// Overlay process operations
+//
+// Everything the injector does with an external overlay runner goes through OverlayBackend:
+// starting runoverlay, stopping it and killing strays left over from earlier runs. Overlays
+// themselves are built in-process (see overlay.rs). ModToolsBackend wraps mod-tools.exe; the
+// fake backend in the tests below lets the injection flow run without it.
+
+// Exit of an overlay process; `code` is None when it was killed by a signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OverlayExit {
+  pub(crate) code: Option<i32>,
+}
+
+// A running overlay, whatever started it
+pub(crate) trait OverlayProcess: Send {
+  // Status output, e.g. "Status: Waiting for league match to start"
+  fn take_stdout(&mut self) -> Option<Box<dyn Read + Send>>;
+  fn take_stderr(&mut self) -> Option<Box<dyn Read + Send>>;
+  fn try_wait(&mut self) -> io::Result<Option<OverlayExit>>;
+  // Ask the overlay to exit on its own, like cslol-manager does with a newline on stdin
+  fn request_stop(&mut self);
+  fn kill(&mut self);
+  fn wait(&mut self);
+}
+
+pub(crate) trait OverlayBackend: Send + Sync {
+  // Shown in the injection log
+  fn describe(&self) -> String;
+  // Fail early when the backend can't run at all
+  fn check_available(&self) -> Result<(), InjectionError>;
+  fn run_overlay(
+    &self,
+    overlay_dir: &Path,
+    config_path: &Path,
+    game_path: &Path,
+  ) -> Result<Box<dyn OverlayProcess>, InjectionError>;
+  // Kill overlay processes we didn't start (or lost the handle to)
+  fn kill_stray_processes(&self) -> Result<(), String>;
+  // Ids of overlay processes still running
+  fn stray_process_ids(&self) -> Vec<u32>;
+  fn kill_process(&self, pid: u32);
+}
+
+struct ChildProcess(Child);
+
+impl OverlayProcess for ChildProcess {
+  fn take_stdout(&mut self) -> Option<Box<dyn Read + Send>> {
+    self
+      .0
+      .stdout
+      .take()
+      .map(|s| Box::new(s) as Box<dyn Read + Send>)
+  }
+
+  fn take_stderr(&mut self) -> Option<Box<dyn Read + Send>> {
+    self
+      .0
+      .stderr
+      .take()
+      .map(|s| Box::new(s) as Box<dyn Read + Send>)
+  }
+
+  fn try_wait(&mut self) -> io::Result<Option<OverlayExit>> {
+    Ok(self.0.try_wait()?.map(|status| OverlayExit {
+      code: status.code(),
+    }))
+  }
+
+  fn request_stop(&mut self) {
+    if let Some(ref mut stdin) = self.0.stdin {
+      let _ = stdin.write_all(b"\n");
+      let _ = stdin.flush();
+    }
+  }
+
+  fn kill(&mut self) {
+    let _ = self.0.kill();
+  }
+
+  fn wait(&mut self) {
+    let _ = self.0.wait();
+  }
+}
+
+// Overlay runner backed by cslol's mod-tools.exe
+pub(crate) struct ModToolsBackend {
+  path: PathBuf,
+}
+
+impl ModToolsBackend {
+  // Look for mod-tools.exe in the bundled resources, the app data directories and next to
+  // the executable, waiting a few seconds for a download in progress to finish
+  pub(crate) fn locate(app_handle: &AppHandle) -> Result<Self, InjectionError> {
+    let mut mod_tools_path = None;
+
+    // Prefer bundled resources shipped with the app first (force using packaged tools)
+    if let Ok(resource_dir) = app_handle.path().resource_dir() {
+      let direct = resource_dir.join("mod-tools.exe");
+      if direct.exists() {
+        mod_tools_path = Some(direct);
+      }
+      if mod_tools_path.is_none() {
+        let bundled = resource_dir.join("cslol-tools").join("mod-tools.exe");
+        if bundled.exists() {
+          mod_tools_path = Some(bundled);
+        }
+      }
+    }
+
+    // Prefer tools downloaded/managed by the app in app data directories (fallback)
+    if mod_tools_path.is_none() {
+      if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+        let candidate = app_data_dir.join("cslol-tools").join("mod-tools.exe");
+
+        if candidate.exists() {
+          mod_tools_path = Some(candidate);
+        }
+      }
+    }
+
+    // Tauri's app-local directory is used by some installers; check there as well
+    if mod_tools_path.is_none() {
+      if let Ok(app_local_dir) = app_handle.path().app_local_data_dir() {
+        let managed = app_local_dir.join("cslol-tools").join("mod-tools.exe");
+
+        if managed.exists() {
+          mod_tools_path = Some(managed);
+        }
+      }
+    }
+
+    // Legacy installers might have dropped mod-tools.exe directly in app-local data
+    if mod_tools_path.is_none() {
+      if let Ok(app_local_dir) = app_handle.path().app_local_data_dir() {
+        let legacy_single = app_local_dir.join("mod-tools.exe");
+        if legacy_single.exists() {
+          mod_tools_path = Some(legacy_single);
+        }
+      }
+    }
+
+    // Finally search near the running executable (useful in dev environments)
+    if mod_tools_path.is_none() {
+      if let Ok(exe_path) = env::current_exe() {
+        if let Some(exe_dir) = exe_path.parent() {
+          let candidates = [
+            exe_dir.join("cslol-tools").join("mod-tools.exe"),
+            exe_dir
+              .join("resources")
+              .join("cslol-tools")
+              .join("mod-tools.exe"),
+            exe_dir.join("mod-tools.exe"),
+          ];
+          for candidate in &candidates {
+            if candidate.exists() {
+              mod_tools_path = Some(candidate.clone());
+
+              break;
+            }
+          }
+        }
+      }
+    }
+
+    if !mod_tools_path
+      .as_ref()
+      .map(|path| path.exists())
+      .unwrap_or(false)
+    {
+      const MOD_TOOLS_WAIT_ATTEMPTS: usize = 25;
+      for _ in 0..MOD_TOOLS_WAIT_ATTEMPTS {
+        if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+          let candidate = app_data_dir.join("cslol-tools").join("mod-tools.exe");
+          if candidate.exists() {
+            mod_tools_path = Some(candidate);
+            break;
+          }
+        }
+        if mod_tools_path
+          .as_ref()
+          .map(|path| path.exists())
+          .unwrap_or(false)
+        {
+          break;
+        }
+        thread::sleep(Duration::from_millis(200));
+      }
+    }
+
+    match mod_tools_path {
+      Some(path) if path.exists() => Ok(Self { path }),
+      _ => Err(InjectionError::ModToolsMissing(
+        "mod-tools.exe not found. Please install CSLOL Manager before injecting.".into(),
+      )),
+    }
+  }
+}
+
+impl OverlayBackend for ModToolsBackend {
+  fn describe(&self) -> String {
+    format!("mod-tools.exe from: {}", self.path.display())
+  }
+
+  fn check_available(&self) -> Result<(), InjectionError> {
+    if !self.path.exists() {
+      return Err(InjectionError::ModToolsMissing(format!(
+        "mod-tools.exe was found during initialization but is no longer at path: {}. Please reinstall the application or obtain mod-tools.exe from CSLOL Manager.",
+        self.path.display()
+      )));
+    }
+    Ok(())
+  }
+
+  fn run_overlay(
+    &self,
+    overlay_dir: &Path,
+    config_path: &Path,
+    game_path: &Path,
+  ) -> Result<Box<dyn OverlayProcess>, InjectionError> {
+    // Run the overlay process - EXACT format from CSLOL
+    // Capture stdout to read status messages like "Waiting for league match to start"
+    // Capture stdin so we can send newline to stop it gracefully (like cslol does)
+    let mut command = std::process::Command::new(&self.path);
+    command.args([
+      "runoverlay",
+      overlay_dir.to_str().unwrap(),
+      config_path.to_str().unwrap(),
+      &format!("--game:{}", game_path.to_str().unwrap()),
+      "--opts:none",
+    ]);
+
+    // Capture stdin for graceful stop, stdout/stderr for status updates
+    command.stdin(Stdio::piped());
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    match command.spawn() {
+      Ok(child) => Ok(Box::new(ChildProcess(child))),
+      Err(e) => Err(match e.kind() {
+        io::ErrorKind::NotFound => InjectionError::ModToolsMissing(format!(
+          "mod-tools.exe not found or is inaccessible at path: {}. Please install CSLOL Manager or copy the correct mod-tools.exe to the application directory.",
+          self.path.display()
+        )),
+        io::ErrorKind::PermissionDenied => InjectionError::ProcessError(
+          "Permission denied when trying to run mod-tools.exe. Try running the application as administrator.".into(),
+        ),
+        _ => InjectionError::ProcessError(format!(
+          "Error running mod-tools.exe: {}. Please ensure it's correctly installed and compatible with your system.",
+          e
+        )),
+      }),
+    }
+  }
+
+  fn kill_stray_processes(&self) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+      // Kill any running mod-tools processes
+      let mut command = std::process::Command::new("taskkill");
+      command.args(["/F", "/IM", "mod-tools.exe"]);
+      command.creation_flags(CREATE_NO_WINDOW);
+
+      match command.output() {
+        Ok(output) => {
+          if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stderr.contains("not found") && !stderr.contains("No tasks are running") {
+              return Err(format!("Failed to kill mod-tools processes: {}", stderr));
+            }
+          }
+        }
+        Err(e) => return Err(format!("Could not run taskkill: {}", e)),
+      }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+      // For non-Windows systems, use pkill
+      let mut command = std::process::Command::new("pkill");
+      command.args(["-f", "mod-tools"]);
+      let _ = command.output();
+    }
+
+    Ok(())
+  }
+
+  fn stray_process_ids(&self) -> Vec<u32> {
+    #[cfg(target_os = "windows")]
+    {
+      let mut check_command = std::process::Command::new("wmic");
+      check_command.args([
+        "process",
+        "where",
+        "name='mod-tools.exe'",
+        "get",
+        "processid",
+      ]);
+      check_command.creation_flags(CREATE_NO_WINDOW);
+
+      if let Ok(output) = check_command.output() {
+        if output.status.success() {
+          return String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect();
+        }
+      }
+    }
+
+    Vec::new()
+  }
+
+  fn kill_process(&self, pid: u32) {
+    #[cfg(target_os = "windows")]
+    {
+      let mut kill_pid = std::process::Command::new("taskkill");
+      kill_pid.args(["/F", "/PID", &pid.to_string()]);
+      kill_pid.creation_flags(CREATE_NO_WINDOW);
+      let _ = kill_pid.output();
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+      let _ = std::process::Command::new("kill")
+        .args(["-9", &pid.to_string()])
+        .output();
+    }
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod fake {
+  use super::*;
+  use std::collections::VecDeque;
+  use std::sync::atomic::{AtomicBool, Ordering};
+  use std::sync::mpsc::{self, Receiver};
+  use std::sync::{Arc, Mutex};
+
+  // What a fake overlay does once its scripted output is written
+  #[derive(Debug, Clone, Copy)]
+  pub(crate) enum FakeEnd {
+    // Keep running until stopped or killed, like a real overlay waiting for the game
+    RunUntilStopped,
+    Exit(i32),
+    // Die without exiting cleanly
+    Crash,
+  }
+
+  #[derive(Debug, Clone)]
+  pub(crate) struct FakeScript {
+    // Lines printed on stdout, each after its delay
+    pub(crate) lines: Vec<(Duration, String)>,
+    pub(crate) end: FakeEnd,
+  }
+
+  impl FakeScript {
+    pub(crate) fn new(end: FakeEnd) -> Self {
+      Self {
+        lines: Vec::new(),
+        end,
+      }
+    }
+
+    pub(crate) fn status(mut self, after: Duration, message: &str) -> Self {
+      self.lines.push((after, format!("Status: {}", message)));
+      self
+    }
+  }
+
+  // Arguments of one run_overlay call
+  #[derive(Debug, Clone)]
+  pub(crate) struct FakeRun {
+    pub(crate) overlay_dir: PathBuf,
+    pub(crate) game_path: PathBuf,
+  }
+
+  // Backend that plays scripts instead of starting mod-tools. Each run_overlay call takes the
+  // next script; once they run out the last one is repeated.
+  #[derive(Default)]
+  pub(crate) struct FakeBackend {
+    scripts: Mutex<VecDeque<FakeScript>>,
+    last_script: Mutex<Option<FakeScript>>,
+    fail_spawn: AtomicBool,
+    pub(crate) runs: Mutex<Vec<FakeRun>>,
+    pub(crate) stray_kills: Mutex<usize>,
+  }
+
+  impl FakeBackend {
+    pub(crate) fn new(scripts: Vec<FakeScript>) -> Arc<Self> {
+      Arc::new(Self {
+        scripts: Mutex::new(scripts.into()),
+        ..Default::default()
+      })
+    }
+
+    pub(crate) fn fail_spawn(&self, fail: bool) {
+      self.fail_spawn.store(fail, Ordering::SeqCst);
+    }
+  }
+
+  impl OverlayBackend for FakeBackend {
+    fn describe(&self) -> String {
+      "fake overlay backend".into()
+    }
+
+    fn check_available(&self) -> Result<(), InjectionError> {
+      Ok(())
+    }
+
+    fn run_overlay(
+      &self,
+      overlay_dir: &Path,
+      _config_path: &Path,
+      game_path: &Path,
+    ) -> Result<Box<dyn OverlayProcess>, InjectionError> {
+      if self.fail_spawn.load(Ordering::SeqCst) {
+        return Err(InjectionError::ProcessError("fake spawn failure".into()));
+      }
+      self.runs.lock().unwrap().push(FakeRun {
+        overlay_dir: overlay_dir.to_path_buf(),
+        game_path: game_path.to_path_buf(),
+      });
+
+      let script = {
+        let mut last = self.last_script.lock().unwrap();
+        if let Some(next) = self.scripts.lock().unwrap().pop_front() {
+          *last = Some(next);
+        }
+        last
+          .clone()
+          .unwrap_or_else(|| FakeScript::new(FakeEnd::RunUntilStopped))
+      };
+      Ok(Box::new(FakeProcess::start(script)))
+    }
+
+    fn kill_stray_processes(&self) -> Result<(), String> {
+      *self.stray_kills.lock().unwrap() += 1;
+      Ok(())
+    }
+
+    fn stray_process_ids(&self) -> Vec<u32> {
+      Vec::new()
+    }
+
+    fn kill_process(&self, _pid: u32) {}
+  }
+
+  struct FakeState {
+    exit: Mutex<Option<OverlayExit>>,
+    stop: AtomicBool,
+  }
+
+  pub(crate) struct FakeProcess {
+    stdout: Option<Box<dyn Read + Send>>,
+    state: Arc<FakeState>,
+  }
+
+  impl FakeProcess {
+    fn start(script: FakeScript) -> Self {
+      let state = Arc::new(FakeState {
+        exit: Mutex::new(None),
+        stop: AtomicBool::new(false),
+      });
+      let (tx, rx) = mpsc::channel::<Vec<u8>>();
+
+      let thread_state = state.clone();
+      thread::spawn(move || {
+        let stopped = |state: &FakeState| state.stop.load(Ordering::SeqCst);
+        for (delay, line) in script.lines {
+          if sleep_unless_stopped(&thread_state, delay) {
+            break;
+          }
+          let _ = tx.send(format!("{}\n", line).into_bytes());
+        }
+        let exit = match script.end {
+          _ if stopped(&thread_state) => OverlayExit { code: Some(0) },
+          FakeEnd::Exit(code) => OverlayExit { code: Some(code) },
+          FakeEnd::Crash => OverlayExit { code: None },
+          FakeEnd::RunUntilStopped => {
+            while !stopped(&thread_state) {
+              thread::sleep(Duration::from_millis(5));
+            }
+            OverlayExit { code: Some(0) }
+          }
+        };
+        // Closing stdout before reporting the exit, like a real process
+        drop(tx);
+        thread_state.exit.lock().unwrap().get_or_insert(exit);
+      });
+
+      Self {
+        stdout: Some(Box::new(ChannelReader {
+          rx,
+          pending: Vec::new(),
+        })),
+        state,
+      }
+    }
+  }
+
+  // Returns true when a stop was requested while sleeping
+  fn sleep_unless_stopped(state: &FakeState, delay: Duration) -> bool {
+    let deadline = std::time::Instant::now() + delay;
+    while std::time::Instant::now() < deadline {
+      if state.stop.load(Ordering::SeqCst) {
+        return true;
+      }
+      thread::sleep(Duration::from_millis(2));
+    }
+    state.stop.load(Ordering::SeqCst)
+  }
+
+  impl OverlayProcess for FakeProcess {
+    fn take_stdout(&mut self) -> Option<Box<dyn Read + Send>> {
+      self.stdout.take()
+    }
+
+    fn take_stderr(&mut self) -> Option<Box<dyn Read + Send>> {
+      None
+    }
+
+    fn try_wait(&mut self) -> io::Result<Option<OverlayExit>> {
+      Ok(*self.state.exit.lock().unwrap())
+    }
+
+    fn request_stop(&mut self) {
+      self.state.stop.store(true, Ordering::SeqCst);
+    }
+
+    fn kill(&mut self) {
+      self
+        .state
+        .exit
+        .lock()
+        .unwrap()
+        .get_or_insert(OverlayExit { code: None });
+      self.state.stop.store(true, Ordering::SeqCst);
+    }
+
+    fn wait(&mut self) {
+      while self.state.exit.lock().unwrap().is_none() {
+        thread::sleep(Duration::from_millis(2));
+      }
+    }
+  }
+
+  struct ChannelReader {
+    rx: Receiver<Vec<u8>>,
+    pending: Vec<u8>,
+  }
+
+  impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+      if self.pending.is_empty() {
+        match self.rx.recv() {
+          Ok(chunk) => self.pending = chunk,
+          // Sender dropped: the process exited
+          Err(_) => return Ok(0),
+        }
+      }
+      let n = buf.len().min(self.pending.len());
+      buf[..n].copy_from_slice(&self.pending[..n]);
+      self.pending.drain(..n);
+      Ok(n)
+    }
+  }
+}
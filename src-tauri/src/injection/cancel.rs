@@ -0,0 +1,127 @@
+use crate::injection::error::InjectionError;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Cancelling in-flight injections
+//
+// Every injection registers a CancelToken before it waits for INJECTION_LOCK. The pipeline
+// checks the token between steps (each resolved item, archive entry, overlay WAD and overlay
+// spawn attempt) and stops with InjectionError::Aborted, which rolls Game/mods back like any
+// other failure.
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn cancel(&self) {
+    self.0.store(true, Ordering::SeqCst);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.0.load(Ordering::SeqCst)
+  }
+
+  // Fails with Aborted once cancelled; `step` names what the injection was doing
+  pub fn check(&self, step: &str) -> Result<(), InjectionError> {
+    if self.is_cancelled() {
+      return Err(InjectionError::Aborted(format!(
+        "injection cancelled during {}",
+        step
+      )));
+    }
+    Ok(())
+  }
+}
+
+#[derive(Default)]
+struct CancelRegistry {
+  tokens: Mutex<Vec<CancelToken>>,
+}
+
+impl CancelRegistry {
+  fn register(&self) -> CancelToken {
+    let token = CancelToken::new();
+    self
+      .tokens
+      .lock()
+      .expect("cancel registry poisoned")
+      .push(token.clone());
+    token
+  }
+
+  fn unregister(&self, token: &CancelToken) {
+    self
+      .tokens
+      .lock()
+      .expect("cancel registry poisoned")
+      .retain(|t| !Arc::ptr_eq(&t.0, &token.0));
+  }
+
+  fn cancel_all(&self) -> usize {
+    let tokens = self.tokens.lock().expect("cancel registry poisoned");
+    for token in tokens.iter() {
+      token.cancel();
+    }
+    tokens.len()
+  }
+}
+
+static IN_FLIGHT: Lazy<CancelRegistry> = Lazy::new(CancelRegistry::default);
+
+// An injection that can be cancelled until this is dropped
+pub(crate) struct InFlight {
+  token: CancelToken,
+}
+
+impl InFlight {
+  pub(crate) fn register() -> Self {
+    Self {
+      token: IN_FLIGHT.register(),
+    }
+  }
+
+  pub(crate) fn token(&self) -> &CancelToken {
+    &self.token
+  }
+}
+
+impl Drop for InFlight {
+  fn drop(&mut self) {
+    IN_FLIGHT.unregister(&self.token);
+  }
+}
+
+// Cancel every injection that is running or waiting for its turn. Returns how many there were.
+pub fn cancel_in_flight() -> usize {
+  IN_FLIGHT.cancel_all()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cancels_registered_tokens_only() {
+    let registry = CancelRegistry::default();
+    let first = registry.register();
+    let second = registry.register();
+    registry.unregister(&second);
+
+    assert!(first.check("resolution").is_ok());
+    assert_eq!(registry.cancel_all(), 1);
+    assert!(first.is_cancelled());
+    assert!(!second.is_cancelled());
+    match first.check("extraction") {
+      Err(InjectionError::Aborted(msg)) => assert!(msg.contains("extraction")),
+      other => panic!("expected Aborted, got {:?}", other),
+    }
+
+    registry.unregister(&first);
+    assert_eq!(registry.cancel_all(), 0);
+  }
+}
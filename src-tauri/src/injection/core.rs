@@ -1,15 +1,25 @@
-use crate::injection::error::{InjectionError, MiscItem, ModState, Skin};
+use crate::injection::backend::{ModToolsBackend, OverlayBackend, OverlayProcess};
+use crate::injection::cancel::{CancelToken, InFlight};
+use crate::injection::deadline::{
+  champ_select_deadline, items_to_drop, InjectionDeadline, TimeoutReason,
+};
+use crate::injection::error::{InjectionError, MiscItem, ModSource, ModState, Skin};
+use crate::injection::history::{
+  InjectionJournal, InjectionOutcome, InjectionRecord, InjectionTrigger, OutcomeSink, ResolvedFile,
+};
+use crate::injection::injection_log::{injection_logs_dir, InjectionLog};
+use crate::injection::mod_cache::{archive_hash, cache_key};
+use crate::injection::plan::MissingItem;
+use crate::injection::skin_file::{prepare_mods, ExtractionLimits, ResolutionMethod};
+use crate::injection::staging::ModsTransaction;
+use crate::injection::timing::{TimingReport, TimingStage};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::env;
-use std::fs::{self, File};
-use std::io::{self, Write};
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
+use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
-use std::thread;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tauri::{AppHandle, Emitter, Manager};
 
 #[cfg(target_os = "windows")]
@@ -23,19 +33,60 @@ pub struct SkinInjector {
   pub(crate) root_path: PathBuf, // Store the root League directory path
   pub(crate) game_path: PathBuf, // Store the Game subdirectory path
   pub(crate) status: String,
-  pub(crate) log_file: Option<File>,
-  pub(crate) mod_tools_path: Option<PathBuf>, // Add mod_tools path
+  // Log of the running injection, see injection_log
+  pub(crate) log_file: Option<InjectionLog>,
+  // Runs and stops the overlay; None for injectors that never start one
+  pub(crate) backend: Option<Arc<dyn OverlayBackend>>,
   #[allow(dead_code)]
   pub(crate) champion_names: HashMap<u32, String>, // Keep for compatibility but not used actively
   pub(crate) app_handle: Option<AppHandle>,
+  // Mod directory name -> what it was built from, for labelling conflicts
+  pub(crate) mod_sources: HashMap<String, ModSource>,
+  // Mod directory name -> mod cache key (archive hash + format), for overlay caching
+  pub(crate) mod_keys: HashMap<String, String>,
+  // What started the injection, for the history journal
+  pub(crate) trigger: InjectionTrigger,
+  // Journal entry the running overlay reports its outcome to
+  pub(crate) outcome_sink: Option<OutcomeSink>,
+  // Stage timings of the current injection
+  pub(crate) timing: TimingReport,
+  // Cancellation of the current injection, see cancel.rs
+  pub(crate) cancel: CancelToken,
+  // When the game starts loading, for injections racing it; see deadline.rs
+  pub(crate) deadline: Option<InjectionDeadline>,
+  // Whether the current injection was cut down or given up because of its deadline
+  pub(crate) timeout: Option<TimeoutReason>,
+  // Leave a running overlay up and reuse it when the loadout did not change
+  pub(crate) persistent_overlay: bool,
+}
+
+// An archive resolved for injection, waiting to be processed
+pub(crate) struct PendingMod {
+  pub(crate) label: String,
+  pub(crate) mod_name: String,
+  pub(crate) archive: PathBuf,
+  pub(crate) source: ModSource,
+  // How a skin archive was found; None for misc items
+  pub(crate) method: Option<ResolutionMethod>,
+}
+
+// Outcome of resolving the selected skins and misc items to archives on disk
+pub(crate) struct Resolution {
+  pub(crate) pending: Vec<PendingMod>,
+  pub(crate) missing: Vec<MissingItem>,
 }
 
 static INJECTION_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
+// The overlay process slot is global, so overlay tests take turns
+#[cfg(test)]
+pub(crate) static OVERLAY_TEST_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
 // Keep the overlay process alive across command boundaries.
 // The injector instance used for an injection is often short-lived; if the Child is dropped,
 // mod-tools may interpret stdin closing as a stop signal and exit immediately.
-static OVERLAY_PROCESS: Lazy<Mutex<Option<std::process::Child>>> = Lazy::new(|| Mutex::new(None));
+static OVERLAY_PROCESS: Lazy<Mutex<Option<Box<dyn OverlayProcess>>>> =
+  Lazy::new(|| Mutex::new(None));
 
 pub(crate) fn is_global_overlay_running() -> bool {
   let mut guard = OVERLAY_PROCESS
@@ -72,31 +123,48 @@ pub(crate) fn stop_global_overlay_process(reason: &str) {
   match child.try_wait() {
     Ok(Some(_)) => return,
     Ok(None) => {
-      if let Some(ref mut stdin) = child.stdin {
-        let _ = stdin.write_all(b"\n");
-        let _ = stdin.flush();
-      }
+      child.request_stop();
 
       std::thread::sleep(std::time::Duration::from_millis(400));
 
       match child.try_wait() {
         Ok(Some(_)) => return,
         Ok(None) => {
-          let _ = child.kill();
-          let _ = child.wait();
+          child.kill();
+          child.wait();
         }
         Err(_) => {
-          let _ = child.kill();
+          child.kill();
         }
       }
     }
     Err(_) => {
-      let _ = child.kill();
+      child.kill();
     }
   }
 }
 
-pub(crate) fn set_global_overlay_process(child: std::process::Child) {
+// Overlay signature of the running overlay, so a persistent overlay is only reused for the
+// loadout it was built from. None when the overlay was built without one.
+static OVERLAY_SIGNATURE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+pub(crate) fn running_overlay_signature() -> Option<String> {
+  if !is_global_overlay_running() {
+    return None;
+  }
+  OVERLAY_SIGNATURE
+    .lock()
+    .expect("OVERLAY_SIGNATURE poisoned")
+    .clone()
+}
+
+pub(crate) fn set_running_overlay_signature(signature: Option<String>) {
+  *OVERLAY_SIGNATURE
+    .lock()
+    .expect("OVERLAY_SIGNATURE poisoned") = signature;
+}
+
+pub(crate) fn set_global_overlay_process(child: Box<dyn OverlayProcess>) {
   let mut guard = OVERLAY_PROCESS
     .lock()
     .expect("OVERLAY_PROCESS poisoned");
@@ -106,16 +174,13 @@ pub(crate) fn set_global_overlay_process(child: std::process::Child) {
     match existing.try_wait() {
       Ok(Some(_)) => {}
       Ok(None) => {
-        if let Some(ref mut stdin) = existing.stdin {
-          let _ = stdin.write_all(b"\n");
-          let _ = stdin.flush();
-        }
+        existing.request_stop();
         std::thread::sleep(std::time::Duration::from_millis(200));
-        let _ = existing.kill();
-        let _ = existing.wait();
+        existing.kill();
+        existing.wait();
       }
       Err(_) => {
-        let _ = existing.kill();
+        existing.kill();
       }
     }
   }
@@ -123,7 +188,10 @@ pub(crate) fn set_global_overlay_process(child: std::process::Child) {
   *guard = Some(child);
 }
 
-pub(crate) fn take_global_overlay_process() -> Option<std::process::Child> {
+// Taking the process out means it is about to be stopped, so the watchdog lets it go
+pub(crate) fn take_global_overlay_process() -> Option<Box<dyn OverlayProcess>> {
+  crate::injection::watchdog::disarm();
+  set_running_overlay_signature(None);
   OVERLAY_PROCESS
     .lock()
     .expect("OVERLAY_PROCESS poisoned")
@@ -152,133 +220,104 @@ impl SkinInjector {
     fs::create_dir_all(app_dir.join("mods"))?;
     fs::create_dir_all(app_dir.join("temp"))?;
 
-    // Create log file
-    let log_path = app_dir.join("log.txt");
-    let log_file = File::create(&log_path)?;
-
     // Initialize empty champion names cache
     let champion_names = HashMap::new();
 
     // Look for mod-tools executable in multiple locations
-    let mut mod_tools_path = None;
-
-    // Prefer bundled resources shipped with the app first (force using packaged tools)
-    if let Ok(resource_dir) = app_handle.path().resource_dir() {
-      let direct = resource_dir.join("mod-tools.exe");
-      if direct.exists() {
-        mod_tools_path = Some(direct);
-      }
-      if mod_tools_path.is_none() {
-        let bundled = resource_dir.join("cslol-tools").join("mod-tools.exe");
-        if bundled.exists() {
-          mod_tools_path = Some(bundled);
-        }
-      }
-    }
-
-    // Prefer tools downloaded/managed by the app in app data directories (fallback)
-    if mod_tools_path.is_none() {
-      if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-        let candidate = app_data_dir.join("cslol-tools").join("mod-tools.exe");
-
-        if candidate.exists() {
-          mod_tools_path = Some(candidate);
-        }
-      }
-    }
-
-    // Tauri's app-local directory is used by some installers; check there as well
-    if mod_tools_path.is_none() {
-      if let Ok(app_local_dir) = app_handle.path().app_local_data_dir() {
-        let managed = app_local_dir.join("cslol-tools").join("mod-tools.exe");
-
-        if managed.exists() {
-          mod_tools_path = Some(managed);
-        }
-      }
-    }
-
-    // Legacy installers might have dropped mod-tools.exe directly in app-local data
-    if mod_tools_path.is_none() {
-      if let Ok(app_local_dir) = app_handle.path().app_local_data_dir() {
-        let legacy_single = app_local_dir.join("mod-tools.exe");
-        if legacy_single.exists() {
-          mod_tools_path = Some(legacy_single);
-        }
-      }
-    }
+    let backend: Arc<dyn OverlayBackend> = Arc::new(ModToolsBackend::locate(app_handle)?);
 
-    // Finally search near the running executable (useful in dev environments)
-    if mod_tools_path.is_none() {
-      if let Ok(exe_path) = env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-          let candidates = [
-            exe_dir.join("cslol-tools").join("mod-tools.exe"),
-            exe_dir
-              .join("resources")
-              .join("cslol-tools")
-              .join("mod-tools.exe"),
-            exe_dir.join("mod-tools.exe"),
-          ];
-          for candidate in &candidates {
-            if candidate.exists() {
-              mod_tools_path = Some(candidate.clone());
-
-              break;
-            }
-          }
-        }
-      }
-    }
+    Ok(Self {
+      state: ModState::Uninitialized,
+      app_dir,
+      root_path,
+      game_path,
+      status: String::new(),
+      log_file: None,
+      backend: Some(backend),
+      champion_names,
+      app_handle: Some(app_handle.clone()),
+      mod_sources: HashMap::new(),
+      mod_keys: HashMap::new(),
+      trigger: InjectionTrigger::Manual,
+      outcome_sink: None,
+      timing: TimingReport::default(),
+      cancel: CancelToken::new(),
+      deadline: None,
+      timeout: None,
+      persistent_overlay: crate::commands::config::is_persistent_overlay_enabled(app_handle),
+    })
+  }
 
-    if !mod_tools_path
-      .as_ref()
-      .map(|path| path.exists())
-      .unwrap_or(false)
-    {
-      const MOD_TOOLS_WAIT_ATTEMPTS: usize = 25;
-      for _ in 0..MOD_TOOLS_WAIT_ATTEMPTS {
-        if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-          let candidate = app_data_dir.join("cslol-tools").join("mod-tools.exe");
-          if candidate.exists() {
-            mod_tools_path = Some(candidate);
-            break;
-          }
-        }
-        if mod_tools_path
-          .as_ref()
-          .map(|path| path.exists())
-          .unwrap_or(false)
-        {
-          break;
-        }
-        thread::sleep(Duration::from_millis(200));
-      }
+  // Injector for read-only work such as plan_injection: no mod-tools lookup and no
+  // directories created. Log lines only go to the console and global log.
+  pub(crate) fn for_planning(
+    app_handle: &AppHandle,
+    root_path: &str,
+  ) -> Result<Self, InjectionError> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| {
+      InjectionError::IoError(io::Error::new(io::ErrorKind::NotFound, format!("{}", e)))
+    })?;
 
-      if !mod_tools_path
-        .as_ref()
-        .map(|path| path.exists())
-        .unwrap_or(false)
-      {
-        return Err(InjectionError::ProcessError(
-          "mod-tools.exe not found. Please install CSLOL Manager before injecting.".into(),
-        ));
-      }
+    let root_path = PathBuf::from(root_path);
+    let game_path = root_path.join("Game");
+    if !game_path.join("League of Legends.exe").exists() {
+      return Err(InjectionError::InvalidGamePath(
+        "Game\\League of Legends.exe not found".into(),
+      ));
     }
 
     Ok(Self {
-      state: ModState::Uninitialized,
+      state: ModState::Idle,
       app_dir,
       root_path,
       game_path,
       status: String::new(),
-      log_file: Some(log_file),
-      mod_tools_path,
-      champion_names,
-      app_handle: Some(app_handle.clone()),
+      log_file: None,
+      backend: None,
+      champion_names: HashMap::new(),
+      app_handle: None,
+      mod_sources: HashMap::new(),
+      mod_keys: HashMap::new(),
+      trigger: InjectionTrigger::Manual,
+      outcome_sink: None,
+      timing: TimingReport::default(),
+      cancel: CancelToken::new(),
+      deadline: None,
+      timeout: None,
+      persistent_overlay: false,
     })
   }
 
+  // Injector running against an arbitrary directory layout and overlay backend, without an
+  // AppHandle, League of Legends.exe or mod-tools
+  #[cfg(test)]
+  pub(crate) fn with_backend(
+    app_dir: &Path,
+    root_path: &Path,
+    backend: Arc<dyn OverlayBackend>,
+  ) -> Self {
+    Self {
+      state: ModState::Idle,
+      app_dir: app_dir.to_path_buf(),
+      root_path: root_path.to_path_buf(),
+      game_path: root_path.join("Game"),
+      status: String::new(),
+      log_file: None,
+      backend: Some(backend),
+      champion_names: HashMap::new(),
+      app_handle: None,
+      mod_sources: HashMap::new(),
+      mod_keys: HashMap::new(),
+      trigger: InjectionTrigger::Manual,
+      outcome_sink: None,
+      timing: TimingReport::default(),
+      cancel: CancelToken::new(),
+      deadline: None,
+      timeout: None,
+      persistent_overlay: false,
+    }
+  }
+
   // Initialize the injector - sets up necessary state
   pub(crate) fn initialize(&mut self) -> Result<(), InjectionError> {
     self.log("Initializing skin injector...");
@@ -352,10 +391,9 @@ impl SkinInjector {
       format!("ℹ️ {}", message)
     };
 
-    // Write to per-injector log file if present (best-effort)
+    // Write to the injection's log file if present (best-effort)
     if let Some(log_file) = &mut self.log_file {
-      let _ = writeln!(log_file, "{}", emoji_message);
-      let _ = log_file.flush();
+      let _ = log_file.write_line(&emoji_message);
     }
 
     // Also append to global logs so Print Logs captures injection details
@@ -376,6 +414,139 @@ impl SkinInjector {
     }
   }
 
+  fn record_mod(&mut self, mod_name: &str, mod_dir: &Path, source: ModSource) {
+    self.mod_sources.insert(mod_name.to_string(), source);
+    // Processed mods live in the mod cache, named by their cache key
+    if let Some(key) = mod_dir.file_name().and_then(|n| n.to_str()) {
+      self.mod_keys.insert(mod_name.to_string(), key.to_string());
+    }
+  }
+
+  // Find the archive for every skin and misc item without touching the game directory.
+  // Used by both injection and plan_injection.
+  pub(crate) fn resolve_archives(
+    &mut self,
+    skins: &[Skin],
+    misc_items: &[MiscItem],
+    skin_file_files_dir: &Path,
+  ) -> Result<Resolution, InjectionError> {
+    let mut pending: Vec<PendingMod> = Vec::new();
+    let mut missing: Vec<MissingItem> = Vec::new();
+
+    for (i, skin) in skins.iter().enumerate() {
+      self.cancel.check("resolution")?;
+      self.log(&format!(
+        "🔄 Processing skin {}/{}: champion_id={}, skin_id={}, chroma_id={:?}",
+        i + 1,
+        skins.len(),
+        skin.champion_id,
+        skin.skin_id,
+        skin.chroma_id
+      ));
+      self.log(&format!(
+        "📁 Skin skin_file path: {:?}",
+        skin.skin_file_path
+      ));
+
+      let source = ModSource::Skin {
+        champion_id: skin.champion_id,
+        skin_id: skin.skin_id,
+        chroma_id: skin.chroma_id,
+      };
+
+      // Find the skin_file file
+      if let Some(resolved) = self.find_skin_file_for_skin(skin, skin_file_files_dir)? {
+        self.log(&format!(
+          "✅ Found skin_file file: {} ({:?})",
+          resolved.path.display(),
+          resolved.method
+        ));
+        pending.push(PendingMod {
+          label: format!("skin {}", skin.skin_id),
+          mod_name: self.mod_name_for_skin_file(&resolved.path),
+          archive: resolved.path,
+          source,
+          method: Some(resolved.method),
+        });
+      } else {
+        missing.push(MissingItem {
+          label: format!("skin {}", skin.skin_id),
+          source,
+          requested_path: skin.skin_file_path.clone(),
+          reason: format!(
+            "No skin_file file found for skin: champion_id={}, skin_id={}, chroma_id={:?}",
+            skin.champion_id, skin.skin_id, skin.chroma_id
+          ),
+        });
+      }
+    }
+
+    // Misc items live in the misc_items directory
+    let misc_items_dir = self.app_dir.join("misc_items");
+
+    for (i, misc_item) in misc_items.iter().enumerate() {
+      self.cancel.check("resolution")?;
+      self.log(&format!(
+        "Processing misc item {}/{}: type={}, name={}",
+        i + 1,
+        misc_items.len(),
+        misc_item.item_type,
+        misc_item.name
+      ));
+
+      let skin_file_path = misc_items_dir.join(&misc_item.skin_file_path);
+
+      self.log(&format!(
+        "[DEBUG] Looking for misc item skin_file at: {}",
+        skin_file_path.display()
+      ));
+
+      let source = ModSource::Misc {
+        id: misc_item.id.clone(),
+        name: misc_item.name.clone(),
+        item_type: misc_item.item_type.clone(),
+      };
+
+      if skin_file_path.exists() {
+        self.log(&format!(
+          "Found misc item skin_file file: {}",
+          skin_file_path.display()
+        ));
+        pending.push(PendingMod {
+          label: format!("misc item {}", misc_item.name),
+          mod_name: self.mod_name_for_skin_file(&skin_file_path),
+          archive: skin_file_path,
+          source,
+          method: None,
+        });
+      } else {
+        let msg = format!(
+          "No skin_file file found for misc item: {} (looked in {})",
+          misc_item.skin_file_path,
+          skin_file_path.display()
+        );
+        self.log(&format!("WARNING: {}", msg));
+        missing.push(MissingItem {
+          label: format!("misc item {}", misc_item.name),
+          source,
+          requested_path: Some(misc_item.skin_file_path.clone()),
+          reason: msg,
+        });
+      }
+    }
+
+    Ok(Resolution { pending, missing })
+  }
+
+  // Archive limits from config.json, the defaults without an app (tests)
+  pub(crate) fn extraction_limits(&self) -> ExtractionLimits {
+    self
+      .app_handle
+      .as_ref()
+      .map(crate::commands::config::get_extraction_limits_from_config)
+      .unwrap_or_default()
+  }
+
   // Simplified champion name lookup - no longer uses cache or fallback
   #[allow(dead_code)]
   pub(crate) fn get_champion_name(&mut self, _champion_id: u32) -> Option<String> {
@@ -384,9 +555,12 @@ impl SkinInjector {
   }
 
   // Functions split across modular files:
-  // - skin_file.rs: extract_skin_file,  extract_skin_file_mmap, find_skin_file_for_skin, create_mod_from_extracted, process_skin_file
+  // - skin_file.rs: find_skin_file_for_skin, prepare_mods (+ convert_skin_file_to_mod)
+  // - plan.rs: plan_injection (dry run of resolve_archives)
   // - mod_tools.rs: copy_mod_to_game, run_overlay
-  // - game_config.rs: enable_mods_in_game_cfg
+  // - backend.rs: OverlayBackend (starting/stopping mod-tools runoverlay)
+  // - game_config.rs: enable_mods_in_game_cfg, restore_game_cfg
+  // - staging.rs: ModsTransaction (staged Game/mods swap with rollback)
 
   #[allow(dead_code)]
   pub fn inject_skins(
@@ -415,12 +589,69 @@ impl SkinInjector {
     self.inject_skins_and_misc_internal(skins, misc_items, skin_file_files_dir, false)
   }
 
+  // Run the injection and append it to the history journal, whatever the result
   fn inject_skins_and_misc_internal(
     &mut self,
     skins: &[Skin],
     misc_items: &[MiscItem],
     skin_file_files_dir: &Path,
     emit_events: bool,
+  ) -> Result<(), InjectionError> {
+    // Registered before waiting for INJECTION_LOCK, so a queued injection can be cancelled too
+    let in_flight = InFlight::register();
+    self.cancel = in_flight.token().clone();
+
+    let journal = InjectionJournal::for_app_dir(&self.app_dir);
+    let mut record = InjectionRecord::start(self.trigger, skins, misc_items);
+    self.outcome_sink = Some(OutcomeSink::new(journal.clone(), record.id.clone()));
+    self.timing = TimingReport::new(record.id.clone());
+    self.timeout = None;
+    // Champ select injections race the game start
+    if matches!(
+      self.trigger,
+      InjectionTrigger::ChampSelect | InjectionTrigger::Party
+    ) {
+      self.deadline = champ_select_deadline();
+    }
+    if let Some(app) = &self.app_handle {
+      crate::commands::skin_injection::record_injection_timeout(app, None);
+    }
+    self.log_file = match InjectionLog::create(&injection_logs_dir(&self.app_dir), &record.id) {
+      Ok(log) => Some(log),
+      Err(e) => {
+        println!("[Injection] Failed to create the injection log: {}", e);
+        None
+      }
+    };
+
+    let result = self.run_injection(
+      skins,
+      misc_items,
+      skin_file_files_dir,
+      emit_events,
+      &mut record,
+    );
+
+    self.log(&format!("Injection timings: {}", self.timing.summary()));
+    if let Some(app) = &self.app_handle {
+      crate::commands::skin_injection::record_injection_timing(app, self.timing.clone());
+    }
+    record.timing = self.timing.clone();
+    record.timeout = self.timeout.clone();
+    record.finish(result.as_ref().err().map(|e| e.to_string()));
+    if let Err(e) = journal.append_injection(&record) {
+      self.log(&format!("WARNING: Failed to record injection history: {}", e));
+    }
+    result
+  }
+
+  fn run_injection(
+    &mut self,
+    skins: &[Skin],
+    misc_items: &[MiscItem],
+    skin_file_files_dir: &Path,
+    emit_events: bool,
+    record: &mut InjectionRecord,
   ) -> Result<(), InjectionError> {
     // Emit start event to frontend (only if requested)
     if emit_events {
@@ -429,11 +660,13 @@ impl SkinInjector {
       }
     }
 
-    let _injection_guard = INJECTION_LOCK
-      .lock()
-      .expect("failed to lock injection mutex");
-    let _ = self.cleanup_mod_tools_processes();
-    std::thread::sleep(std::time::Duration::from_millis(200));
+    let _injection_guard = INJECTION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    self.cancel.check("waiting for another injection")?;
+    // A persistent overlay stays up until run_overlay knows whether it can be reused
+    if !self.persistent_overlay {
+      let _ = self.cleanup_mod_tools_processes();
+      std::thread::sleep(std::time::Duration::from_millis(200));
+    }
 
     // NOTE: Cleanup is now handled by the LCU watcher on phase changes instead of before each injection
     // This improves performance and is better design
@@ -442,123 +675,262 @@ impl SkinInjector {
     self.set_state(ModState::Busy);
     self.log("Starting skin injection process...");
 
-    // First, clean up the game's mods directory
-    let game_mods_dir = self.game_path.join("mods");
-    if game_mods_dir.exists() {
-      self.log("Cleaning up existing mods in game directory");
-      fs::remove_dir_all(&game_mods_dir)?;
+    // Game/mods is left alone until every mod is ready, see ModsTransaction
+    self.mod_sources.clear();
+    self.mod_keys.clear();
+
+    // Resolve every archive first (cheap and sequential), then build the mods in parallel
+    let started = Instant::now();
+    let Resolution { pending, missing } =
+      self.resolve_archives(skins, misc_items, skin_file_files_dir)?;
+    self.timing.add(TimingStage::Resolve, started.elapsed());
+    record.resolved = pending
+      .iter()
+      .map(|item| ResolvedFile {
+        label: item.label.clone(),
+        mod_name: item.mod_name.clone(),
+        archive: item.archive.display().to_string(),
+        source: item.source.clone(),
+        method: item.method,
+      })
+      .collect();
+    record.missing = missing.clone();
+
+    // A missing skin fails the injection; missing misc items are skipped
+    if let Some(item) = missing
+      .iter()
+      .find(|item| matches!(item.source, ModSource::Skin { .. }))
+    {
+      self.log(&format!("ERROR: {}", item.reason));
+      self.set_state(ModState::Idle);
+      return Err(InjectionError::MissingFantomeFile(item.reason.clone()));
     }
-    fs::create_dir_all(&game_mods_dir)?;
 
-    // Process each skin
-    for (i, skin) in skins.iter().enumerate() {
-      self.log(&format!(
-        "🔄 Processing skin {}/{}: champion_id={}, skin_id={}, chroma_id={:?}",
-        i + 1,
-        skins.len(),
-        skin.champion_id,
-        skin.skin_id,
-        skin.chroma_id
-      ));
-      self.log(&format!(
-        "📁 Skin skin_file path: {:?}",
-        skin.skin_file_path
-      ));
+    let pending = match self.fit_to_deadline(pending) {
+      Ok(pending) => pending,
+      Err(e) => {
+        self.set_state(ModState::Idle);
+        return Err(e);
+      }
+    };
 
-      // Find the skin_file file
-      let skin_file_path = self.find_skin_file_for_skin(skin, skin_file_files_dir)?;
-      if let Some(skin_file_path) = skin_file_path {
-        self.log(&format!(
-          "✅ Found skin_file file: {}",
-          skin_file_path.display()
-        ));
+    if self.persistent_overlay && self.reuse_running_overlay(&pending) {
+      // The running overlay reports to the injection that started it, so this one ends here
+      record.outcome = InjectionOutcome::Reused;
+      if emit_events {
+        if let Some(app) = &self.app_handle {
+          let _ = app.emit("injection-status", "completed");
+        }
+      }
+      return Ok(());
+    }
 
-        // Process the skin_file file to create a proper mod structure
-        let mod_dir = self.process_skin_file(&skin_file_path)?;
+    let archives: Vec<PathBuf> = pending.iter().map(|item| item.archive.clone()).collect();
+    let limits = self.extraction_limits();
+    let started = Instant::now();
+    let results = prepare_mods(&self.app_dir, &archives, &limits, &self.cancel);
+    self.timing.add(TimingStage::Extract, started.elapsed());
+    let ready = self.cancel.check("extraction");
+    if let Err(e) = ready.and_then(|_| self.check_deadline(TimingStage::CopyToGame)) {
+      self.log(&format!("Injection stopped: {}", e));
+      self.set_state(ModState::Idle);
+      return Err(e);
+    }
 
-        // Copy the processed mod to the game
-        if self.is_valid_mod_dir(&mod_dir) {
+    let mut prepared = Vec::new();
+    let mut failures = Vec::new();
+    for (item, result) in pending.iter().zip(results) {
+      if let Ok(mod_info) = &result {
+        self
+          .timing
+          .add_mod(&item.mod_name, mod_info.cached, mod_info.elapsed);
+      }
+      match result {
+        Ok(mod_info) if self.is_valid_mod_dir(&mod_info.dir) => {
           self.log(&format!(
-            "✅ Mod structure is valid, copying to game directory for skin {}",
-            skin.skin_id
+            "✅ Mod structure is valid for {} ({}, {} ms)",
+            item.label,
+            if mod_info.cached {
+              "cached".to_string()
+            } else {
+              format!(
+                "converted {} WAD files, {} bytes{}",
+                mod_info.stats.wad_files,
+                mod_info.stats.bytes_written,
+                if mod_info.stats.generated_info { ", generated META/info.json" } else { "" }
+              )
+            },
+            mod_info.elapsed.as_millis()
           ));
-          self.copy_mod_to_game(&mod_dir)?;
-        } else {
-          // If processing failed, return error
-          self.log("ERROR: Processing failed, mod structure is invalid");
-          self.set_state(ModState::Idle);
-          return Err(InjectionError::ProcessError(
-            "Failed to process skin_file file".into(),
+          prepared.push((item, mod_info.dir));
+        }
+        Ok(_) => {
+          self.log(&format!(
+            "ERROR: Processing failed for {}, mod structure is invalid",
+            item.label
           ));
+          failures.push(format!("{}: mod structure is invalid", item.label));
+        }
+        Err(e) => {
+          self.log(&format!("ERROR: Processing failed for {}: {}", item.label, e));
+          failures.push(format!("{}: {}", item.label, e));
         }
-      } else {
-        let msg = format!(
-          "No skin_file file found for skin: champion_id={}, skin_id={}, chroma_id={:?}",
-          skin.champion_id, skin.skin_id, skin.chroma_id
-        );
-        self.log(&format!("ERROR: {}", msg));
-        self.set_state(ModState::Idle);
-        return Err(InjectionError::MissingFantomeFile(msg));
       }
     }
 
-    // Process misc items
-    for (i, misc_item) in misc_items.iter().enumerate() {
-      self.log(&format!(
-        "Processing misc item {}/{}: type={}, name={}",
-        i + 1,
-        misc_items.len(),
-        misc_item.item_type,
-        misc_item.name
-      ));
+    // Successfully converted items stay in the mod cache, so a retry only redoes the failures
+    if !failures.is_empty() {
+      self.set_state(ModState::Idle);
+      return Err(InjectionError::ProcessError(format!(
+        "Failed to process {} of {} items: {}",
+        failures.len(),
+        pending.len(),
+        failures.join("; ")
+      )));
+    }
 
-      // Find the skin_file file for the misc item in the misc_items directory
-      let misc_items_dir = if let Some(app_handle) = &self.app_handle {
-        app_handle
-          .path()
-          .app_data_dir()
-          .unwrap_or_else(|_| PathBuf::from("."))
-          .join("misc_items")
-      } else {
-        PathBuf::from(".").join("misc_items")
+    // Copy the processed mods into a staging directory, then swap it in for Game/mods
+    let started = Instant::now();
+    let mut transaction = ModsTransaction::begin(&self.game_path)?;
+    for (item, mod_dir) in prepared {
+      if let Err(e) = self.cancel.check("copying mods") {
+        return Err(self.roll_back_injection(transaction, e));
+      }
+      self.record_mod(&item.mod_name, &mod_dir, item.source.clone());
+      if let Err(e) = self.copy_mod_to_game(&mod_dir, transaction.staging_dir(), &item.mod_name) {
+        return Err(self.roll_back_injection(transaction, e));
+      }
+    }
+    self.log("Replacing game mods directory with staged mods");
+    if let Err(e) = transaction.swap_in() {
+      return Err(self.roll_back_injection(transaction, e));
+    }
+    self.timing.add(TimingStage::CopyToGame, started.elapsed());
+
+    // mkoverlay and the overlay spawn are timed inside run_overlay
+    if let Err(e) = self.activate_game_mods() {
+      return Err(self.roll_back_injection(transaction, e));
+    }
+    transaction.commit();
+
+    self.log("Skin injection completed successfully");
+    // Note: We don't set state to Idle because we're now in Running state with the overlay active
+    // After all steps complete successfully, emit end event (only if requested)
+    if emit_events {
+      if let Some(app) = &self.app_handle {
+        let _ = app.emit("injection-status", "completed");
+      }
+    }
+    Ok(())
+  }
+
+  // A persistent overlay built from the same loadout is already waiting for the game. The
+  // cache keys come from the archive hashes, so this is decided before anything is extracted,
+  // staged or written to Game.cfg. Game/mods still holds the mods that overlay was built from.
+  fn reuse_running_overlay(&mut self, pending: &[PendingMod]) -> bool {
+    let Some(running) = running_overlay_signature() else {
+      return false;
+    };
+    for item in pending {
+      let Ok(hash) = archive_hash(&item.archive) else {
+        return false;
       };
-      let skin_file_path = misc_items_dir.join(&misc_item.skin_file_path);
+      self
+        .mod_sources
+        .insert(item.mod_name.clone(), item.source.clone());
+      self
+        .mod_keys
+        .insert(item.mod_name.clone(), cache_key(&hash));
+    }
+    let mut mod_names: Vec<String> = pending.iter().map(|item| item.mod_name.clone()).collect();
+    crate::injection::overlay::order_mod_names(
+      &mut mod_names,
+      &self.mod_sources,
+      &self.load_order(),
+    );
+    let winners = self.conflict_winners();
+    if self.loadout_signature(&mod_names, &winners).as_ref() != Some(&running) {
+      return false;
+    }
 
-      self.log(&format!(
-        "[DEBUG] Looking for misc item skin_file at: {}",
-        skin_file_path.display()
-      ));
+    self.log(&format!("Reusing running overlay {}", running));
+    self.set_state(ModState::Running);
+    true
+  }
 
-      if skin_file_path.exists() {
-        self.log(&format!(
-          "Found misc item skin_file file: {}",
-          skin_file_path.display()
-        ));
+  // Leave out what can't be ready before the deadline, see deadline.rs
+  fn fit_to_deadline(
+    &mut self,
+    pending: Vec<PendingMod>,
+  ) -> Result<Vec<PendingMod>, InjectionError> {
+    let Some(deadline) = self.deadline else {
+      return Ok(pending);
+    };
+    self.check_deadline(TimingStage::Extract)?;
 
-        // Process the skin_file file to create a proper mod structure
-        let mod_dir = self.process_skin_file(&skin_file_path)?;
+    let remaining = deadline.remaining(Instant::now());
+    let sources: Vec<&ModSource> = pending.iter().map(|item| &item.source).collect();
+    let to_drop = items_to_drop(&sources, remaining);
+    if to_drop.is_empty() {
+      return Ok(pending);
+    }
 
-        // Copy the processed mod to the game
-        if self.is_valid_mod_dir(&mod_dir) {
-          self.log("Misc item mod structure is valid, copying to game directory");
-          self.copy_mod_to_game(&mod_dir)?;
-        } else {
-          self.log("ERROR: Misc item processing failed, mod structure is invalid");
-          self.set_state(ModState::Idle);
-          return Err(InjectionError::ProcessError(
-            "Failed to process misc item skin_file file".into(),
-          ));
-        }
+    let mut kept = Vec::new();
+    let mut dropped = Vec::new();
+    for (i, item) in pending.into_iter().enumerate() {
+      if to_drop.contains(&i) {
+        dropped.push(item.label);
       } else {
-        let msg = format!(
-          "No skin_file file found for misc item: {} (looked in {})",
-          misc_item.skin_file_path,
-          skin_file_path.display()
-        );
-        self.log(&format!("WARNING: {}", msg));
-        // Continue processing other items even if one is missing
+        kept.push(item);
       }
     }
+    self.log(&format!(
+      "WARNING: Only {} ms left before the game starts, leaving out {}",
+      remaining.as_millis(),
+      dropped.join(", ")
+    ));
+    self.note_timeout(TimeoutReason::Degraded {
+      remaining_ms: remaining.as_millis() as u64,
+      dropped,
+    });
+    Ok(kept)
+  }
+
+  // Give up once the deadline has passed; `stage` is the step about to start
+  pub(crate) fn check_deadline(&mut self, stage: TimingStage) -> Result<(), InjectionError> {
+    let Some(overdue) = self.deadline.and_then(|d| d.overdue(Instant::now())) else {
+      return Ok(());
+    };
+    let overdue_ms = overdue.as_millis() as u64;
+    self.log(&format!(
+      "ERROR: The game is already starting ({} ms past the deadline), giving up before {:?}",
+      overdue_ms, stage
+    ));
+    self.note_timeout(TimeoutReason::Expired { stage, overdue_ms });
+    Err(InjectionError::Timeout(format!(
+      "the game started before the injection finished ({} ms too late for {:?})",
+      overdue_ms, stage
+    )))
+  }
+
+  fn note_timeout(&mut self, reason: TimeoutReason) {
+    if let Some(app) = &self.app_handle {
+      crate::commands::skin_injection::record_injection_timeout(app, Some(reason.clone()));
+    }
+    self.timeout = Some(reason);
+  }
+
+  // Enable mods in Game.cfg and start the overlay for whatever is now in Game/mods
+  fn load_order(&self) -> Vec<String> {
+    self
+      .app_handle
+      .as_ref()
+      .map(crate::commands::config::get_load_order_from_config)
+      .unwrap_or_else(crate::commands::types::default_load_order)
+  }
+
+  fn activate_game_mods(&mut self) -> Result<(), InjectionError> {
+    let game_mods_dir = self.game_path.join("mods");
 
     // Enable mods in Game.cfg
     self.enable_mods_in_game_cfg()?;
@@ -581,29 +953,45 @@ impl SkinInjector {
       }
     }
 
+    // Layer mods by the user's load order so the result never depends on read_dir order
+    let load_order = self.load_order();
+    crate::injection::overlay::order_mod_names(&mut mod_names, &self.mod_sources, &load_order);
+    if !mod_names.is_empty() {
+      self.log(&format!("Mod load order: {}", mod_names.join(" < ")));
+    }
+
     // Start the overlay process - THIS is the key part that makes skins actually show in-game!
-    if let Err(e) = self.run_overlay() {
+    if let Err(e) = self.run_overlay(&mod_names) {
       self.log(&format!("ERROR: Failed to start overlay process: {}", e));
-      self.set_state(ModState::Idle);
       return Err(e);
     }
+    Ok(())
+  }
 
-    self.log("Skin injection completed successfully");
-    // Note: We don't set state to Idle because we're now in Running state with the overlay active
-    // After all steps complete successfully, emit end event (only if requested)
-    if emit_events {
-      if let Some(app) = &self.app_handle {
-        let _ = app.emit("injection-status", "completed");
-      }
+  // Put Game/mods and Game.cfg back the way they were before this injection, returning the
+  // error that caused it
+  fn roll_back_injection(
+    &mut self,
+    transaction: ModsTransaction,
+    error: InjectionError,
+  ) -> InjectionError {
+    self.log(&format!(
+      "ERROR: Injection failed, restoring previous game mods: {}",
+      error
+    ));
+    if let Err(e) = transaction.rollback() {
+      self.log(&format!(
+        "WARNING: Failed to restore previous game mods: {}",
+        e
+      ));
     }
-    Ok(())
+    self.set_state(ModState::Idle);
+    error
   }
 
   // Add a cleanup method to stop the injection
   pub fn cleanup(&mut self) -> Result<(), InjectionError> {
-    let _injection_guard = INJECTION_LOCK
-      .lock()
-      .expect("failed to lock injection mutex");
+    let _injection_guard = INJECTION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     self.log("Stopping skin injection process...");
     
     // If we have an overlay process that we started, try to terminate it gracefully
@@ -617,17 +1005,11 @@ impl SkinInjector {
         Ok(None) => {
           // Still running - send graceful stop signal
           self.log("Sending stop signal to overlay process...");
-          
-          // Try graceful stop first - send newline to stdin like cslol does
-          if let Some(ref mut stdin) = child.stdin {
-            use std::io::Write;
-            let _ = stdin.write_all(b"\n");
-            let _ = stdin.flush();
-          }
-          
+          child.request_stop();
+
           // Wait a bit for graceful exit
           std::thread::sleep(std::time::Duration::from_millis(500));
-          
+
           // Check if it exited
           match child.try_wait() {
             Ok(Some(_)) => {
@@ -636,71 +1018,38 @@ impl SkinInjector {
             Ok(None) => {
               // Still running, force kill
               self.log("Overlay process didn't exit gracefully, forcing termination...");
-              let _ = child.kill();
-              let _ = child.wait();
+              child.kill();
+              child.wait();
             }
             Err(e) => {
               self.log(&format!("Error checking overlay process: {}", e));
-              let _ = child.kill();
+              child.kill();
             }
           }
         }
         Err(e) => {
           self.log(&format!("Error checking overlay process: {}", e));
-          let _ = child.kill();
+          child.kill();
         }
       }
     }
-    
+
     // Clean up any other mod-tools processes that might be lingering
     let _ = self.cleanup_mod_tools_processes();
     std::thread::sleep(std::time::Duration::from_millis(200));
 
-    // Find and kill the mod-tools processes - more aggressive approach
-    #[cfg(target_os = "windows")]
-    {
-      // First try normal taskkill
-      let mut command = std::process::Command::new("taskkill");
-      command.args(["/F", "/IM", "mod-tools.exe"]);
-
-      #[cfg(target_os = "windows")]
-      const CREATE_NO_WINDOW: u32 = 0x08000000;
-      #[cfg(target_os = "windows")]
-      command.creation_flags(CREATE_NO_WINDOW);
-
-      let _ = command.output();
-
-      // Then check if any processes are still running with wmic (more reliable)
-      let mut check_command = std::process::Command::new("wmic");
-      check_command.args([
-        "process",
-        "where",
-        "name='mod-tools.exe'",
-        "get",
-        "processid",
-      ]);
-      #[cfg(target_os = "windows")]
-      check_command.creation_flags(CREATE_NO_WINDOW);
-
-      // If we find any processes still running, use taskkill with /PID for each one
-      if let Ok(output) = check_command.output() {
-        if output.status.success() {
-          let output_str = String::from_utf8_lossy(&output.stdout);
-          for line in output_str.lines() {
-            let line = line.trim();
-            if line != "ProcessId" && !line.is_empty() && line.chars().all(|c| c.is_digit(10)) {
-              // Found a PID, kill it specifically
-              let mut kill_pid = std::process::Command::new("taskkill");
-              kill_pid.args(["/F", "/PID", line]);
-              #[cfg(target_os = "windows")]
-              kill_pid.creation_flags(CREATE_NO_WINDOW);
-              let _ = kill_pid.output();
-            }
-          }
-        }
+    // Kill whatever survived one by one
+    if let Some(backend) = self.backend.clone() {
+      for pid in backend.stray_process_ids() {
+        backend.kill_process(pid);
       }
     }
 
+    // Mods are off again until the next injection
+    if let Err(e) = self.restore_game_cfg() {
+      self.log(&format!("WARNING: Failed to restore Game.cfg: {}", e));
+    }
+
     // Clean up the overlay directory
     let overlay_dir = self.app_dir.join("overlay");
     if overlay_dir.exists() {
@@ -732,33 +1081,21 @@ impl SkinInjector {
   // Check if injection cleanup is needed (non-destructive check)
   pub fn needs_cleanup(&self) -> bool {
     // Check if we have running mod-tools processes
-    #[cfg(target_os = "windows")]
-    {
-      let mut check_command = std::process::Command::new("wmic");
-      check_command.args([
-        "process",
-        "where",
-        "name='mod-tools.exe'",
-        "get",
-        "processid",
-      ]);
-
-      const CREATE_NO_WINDOW: u32 = 0x08000000;
-      check_command.creation_flags(CREATE_NO_WINDOW);
-
-      if let Ok(output) = check_command.output() {
-        if output.status.success() {
-          let output_str = String::from_utf8_lossy(&output.stdout);
-          for line in output_str.lines() {
-            let line = line.trim();
-            if line != "ProcessId" && !line.is_empty() && line.chars().all(|c| c.is_digit(10)) {
-              return true; // Found at least one mod-tools process
-            }
-          }
-        }
+    if let Some(backend) = &self.backend {
+      if !backend.stray_process_ids().is_empty() {
+        return true;
       }
     }
 
+    // Game.cfg still has mods enabled by us
+    if self
+      .game_path
+      .join(crate::injection::game_config::GAME_CFG_BACKUP)
+      .exists()
+    {
+      return true;
+    }
+
     // Also check if overlay directory exists with content
     let overlay_dir = self.app_dir.join("overlay");
     if overlay_dir.exists() {
@@ -777,41 +1114,12 @@ impl SkinInjector {
     // Prefer stopping the exact overlay child we spawned (if any) before falling back to taskkill.
     crate::injection::core::stop_global_overlay_process("cleanup_mod_tools_processes");
 
-    #[cfg(target_os = "windows")]
-    {
-      // Kill any running mod-tools processes
-      let mut command = std::process::Command::new("taskkill");
-      command.args(["/F", "/IM", "mod-tools.exe"]);
-      command.creation_flags(CREATE_NO_WINDOW);
-
-      match command.output() {
-        Ok(output) => {
-          if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !stderr.contains("not found") && !stderr.contains("No tasks are running") {
-              self.log(&format!(
-                "Warning: Failed to kill mod-tools processes: {}",
-                stderr
-              ));
-            }
-          } else {
-            self.log("Successfully terminated mod-tools processes");
-          }
-        }
-        Err(e) => {
-          self.log(&format!("Warning: Could not run taskkill: {}", e));
-        }
+    if let Some(backend) = self.backend.clone() {
+      if let Err(e) = backend.kill_stray_processes() {
+        self.log(&format!("Warning: {}", e));
       }
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-      // For non-Windows systems, use pkill
-      let mut command = std::process::Command::new("pkill");
-      command.args(["-f", "mod-tools"]);
-      let _ = command.output();
-    }
-
     Ok(())
   }
 }
@@ -822,8 +1130,9 @@ pub fn inject_skins(
   game_path: &str,
   skins: &[Skin],
   skin_file_files_dir: &Path,
-) -> Result<(), String> {
-  inject_skins_and_misc(app_handle, game_path, skins, &[], skin_file_files_dir)
+  trigger: InjectionTrigger,
+) -> Result<(), InjectionError> {
+  inject_skins_and_misc(app_handle, game_path, skins, &[], skin_file_files_dir, trigger)
 }
 
 // Enhanced wrapper function that supports both skins and misc items
@@ -833,20 +1142,18 @@ pub fn inject_skins_and_misc(
   skins: &[Skin],
   misc_items: &[MiscItem],
   skin_file_files_dir: &Path,
-) -> Result<(), String> {
-  // Create injector
-  let mut injector = SkinInjector::new(app_handle, game_path)
-    .map_err(|e| format!("Failed to create injector: {}", e))?;
-
-  // Initialize
-  injector
-    .initialize()
-    .map_err(|e| format!("Failed to initialize: {}", e))?;
-
-  // Inject skins and misc items
-  injector
-    .inject_skins_and_misc(skins, misc_items, skin_file_files_dir)
-    .map_err(|e| format!("Failed to inject: {}", e))
+  trigger: InjectionTrigger,
+) -> Result<(), InjectionError> {
+  // Runs on the injection worker, see queue.rs
+  crate::injection::queue::run_queued(crate::injection::queue::InjectionJob {
+    app: app_handle.clone(),
+    league_path: game_path.to_string(),
+    skins: skins.to_vec(),
+    misc_items: misc_items.to_vec(),
+    skin_file_files_dir: skin_file_files_dir.to_path_buf(),
+    trigger,
+    emit_events: true,
+  })
 }
 
 // New function to check if cleanup is needed without performing it
@@ -870,3 +1177,362 @@ pub fn cleanup_injection(app_handle: &AppHandle, game_path: &str) -> Result<(),
     .cleanup()
     .map_err(|e| format!("Failed to stop skin injection: {}", e))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::injection::backend::fake::{FakeBackend, FakeEnd, FakeScript};
+  use crate::injection::cancel::cancel_in_flight;
+  use std::io::Write;
+  use std::time::{Duration, Instant};
+
+  fn temp_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("osskins-{}-{}", name, uuid::Uuid::new_v4()));
+    fs::create_dir_all(root.join("Game").join("mods").join("previous").join("META")).unwrap();
+    fs::write(
+      root
+        .join("Game")
+        .join("mods")
+        .join("previous")
+        .join("META")
+        .join("info.json"),
+      "{}",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("app").join("champions")).unwrap();
+
+    let archive = root.join("app").join("champions").join("ahri_arcade.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(archive).unwrap());
+    writer
+      .start_file("WAD/Ahri.wad.client/skin.bin", Default::default())
+      .unwrap();
+    writer.write_all(b"skin").unwrap();
+    writer.finish().unwrap();
+    root
+  }
+
+  fn skin() -> Skin {
+    Skin {
+      champion_id: 103,
+      skin_id: 1,
+      chroma_id: None,
+      skin_file_path: Some("ahri_arcade.zip".to_string()),
+    }
+  }
+
+  fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+      if condition() {
+        return true;
+      }
+      std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+  }
+
+  #[test]
+  fn injects_and_stops_overlay_with_fake_backend() {
+    let _lock = OVERLAY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = temp_root("inject-fake");
+    let backend = FakeBackend::new(vec![FakeScript::new(FakeEnd::RunUntilStopped).status(
+      Duration::from_millis(5),
+      "Waiting for league match to start",
+    )]);
+    let mut injector = SkinInjector::with_backend(&root.join("app"), &root, backend.clone());
+
+    injector
+      .inject_skins_and_misc(&[skin()], &[], &root.join("app").join("champions"))
+      .unwrap();
+
+    let mods_dir = root.join("Game").join("mods");
+    assert!(mods_dir
+      .join("ahri_arcade")
+      .join("META")
+      .join("info.json")
+      .exists());
+    assert!(!mods_dir.join("previous").exists());
+    assert!(fs::read_to_string(root.join("Game").join("Game.cfg"))
+      .unwrap()
+      .contains("EnableMods=1"));
+    assert_eq!(injector.state, ModState::Running);
+    {
+      let runs = backend.runs.lock().unwrap();
+      assert_eq!(runs.len(), 1);
+      assert_eq!(runs[0].game_path, root.join("Game"));
+      assert!(runs[0].overlay_dir.exists());
+    }
+    assert!(is_global_overlay_running());
+
+    injector.cleanup().unwrap();
+    assert!(!is_global_overlay_running());
+    assert_eq!(injector.state, ModState::Idle);
+    // There was no Game.cfg before the injection
+    assert!(!root.join("Game").join("Game.cfg").exists());
+    assert!(*backend.stray_kills.lock().unwrap() > 0);
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn relative_skin_paths_stay_inside_the_skin_directories() {
+    let root = temp_root("resolve-relative");
+    let champions = root.join("app").join("champions");
+    fs::write(root.join("app").join("secret.zip"), "outside").unwrap();
+    let mut injector =
+      SkinInjector::with_backend(&root.join("app"), &root, FakeBackend::new(Vec::new()));
+
+    let resolved = injector
+      .find_skin_file_for_skin(&skin(), &champions)
+      .unwrap();
+    assert_eq!(
+      resolved.map(|r| r.path),
+      Some(champions.join("ahri_arcade.zip"))
+    );
+
+    let outside = Skin {
+      skin_file_path: Some(format!("..{}secret.zip", std::path::MAIN_SEPARATOR)),
+      ..skin()
+    };
+    let resolved = injector
+      .find_skin_file_for_skin(&outside, &champions)
+      .unwrap();
+    assert!(resolved.is_none());
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn basename_fallbacks_stay_inside_the_skin_directories() {
+    let root = temp_root("resolve-basename");
+    let champions = root.join("app").join("champions");
+    fs::write(root.join("app").join("secret.zip"), "outside").unwrap();
+    std::os::unix::fs::symlink(
+      root.join("app").join("secret.zip"),
+      champions.join("lux.zip"),
+    )
+    .unwrap();
+    let mut injector =
+      SkinInjector::with_backend(&root.join("app"), &root, FakeBackend::new(Vec::new()));
+
+    // Basename, alternate extension and /ezrea/ lookups all land on the link
+    for shared in [
+      "/friend/skins/lux.zip",
+      "/friend/skins/lux.skin_file",
+      "/ezrea/lux.zip",
+    ] {
+      let friend_skin = Skin {
+        skin_file_path: Some(shared.to_string()),
+        ..skin()
+      };
+      let resolved = injector
+        .find_skin_file_for_skin(&friend_skin, &champions)
+        .unwrap();
+      assert!(resolved.is_none(), "{} resolved", shared);
+    }
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn crashed_overlay_is_no_longer_reported_running() {
+    let _lock = OVERLAY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = temp_root("inject-crash");
+    let backend = FakeBackend::new(vec![
+      FakeScript::new(FakeEnd::Crash).status(Duration::from_millis(20), "Found League")
+    ]);
+    let mut injector = SkinInjector::with_backend(&root.join("app"), &root, backend);
+
+    injector
+      .inject_skins_and_misc(&[skin()], &[], &root.join("app").join("champions"))
+      .unwrap();
+    assert!(wait_until(|| !is_global_overlay_running()));
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn failed_overlay_start_restores_previous_mods() {
+    let _lock = OVERLAY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = temp_root("inject-spawn-fail");
+    let backend = FakeBackend::new(Vec::new());
+    backend.fail_spawn(true);
+    let mut injector = SkinInjector::with_backend(&root.join("app"), &root, backend.clone());
+
+    let result =
+      injector.inject_skins_and_misc(&[skin()], &[], &root.join("app").join("champions"));
+    assert!(matches!(result, Err(InjectionError::ProcessError(_))));
+
+    let mods_dir = root.join("Game").join("mods");
+    assert!(mods_dir.join("previous").exists());
+    assert!(!mods_dir.join("ahri_arcade").exists());
+    assert!(!root.join("Game").join("Game.cfg").exists());
+    assert_eq!(injector.state, ModState::Idle);
+    assert!(backend.runs.lock().unwrap().is_empty());
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn watchdog_restarts_crashed_overlay_until_it_is_stopped() {
+    use crate::injection::watchdog::{self, RelaunchOutcome};
+
+    let _lock = OVERLAY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = temp_root("inject-watchdog");
+    let backend = FakeBackend::new(vec![
+      FakeScript::new(FakeEnd::Crash).status(Duration::from_millis(20), "Found League"),
+      FakeScript::new(FakeEnd::RunUntilStopped),
+    ]);
+    let mut injector = SkinInjector::with_backend(&root.join("app"), &root, backend.clone());
+
+    injector
+      .inject_skins_and_misc(&[skin()], &[], &root.join("app").join("champions"))
+      .unwrap();
+    let generation = watchdog::current_generation().unwrap();
+    assert!(wait_until(|| !is_global_overlay_running()));
+
+    match watchdog::relaunch_supervised(generation, || true, |_| {}) {
+      Some(RelaunchOutcome::Restarted { attempt, process }) => {
+        assert_eq!(attempt, 1);
+        set_global_overlay_process(process);
+      }
+      _ => panic!("expected the watchdog to restart the overlay"),
+    }
+    assert!(is_global_overlay_running());
+    {
+      let runs = backend.runs.lock().unwrap();
+      assert_eq!(runs.len(), 2);
+      assert_eq!(runs[0].overlay_dir, runs[1].overlay_dir);
+    }
+
+    // Stopping on purpose disarms the watchdog
+    injector.cleanup().unwrap();
+    assert_eq!(watchdog::current_generation(), None);
+    assert!(watchdog::relaunch_supervised(generation, || true, |_| {}).is_none());
+    assert_eq!(backend.runs.lock().unwrap().len(), 2);
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn cancelled_injection_leaves_game_mods_untouched() {
+    let _lock = OVERLAY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = temp_root("inject-cancel");
+    let backend = FakeBackend::new(vec![FakeScript::new(FakeEnd::RunUntilStopped)]);
+
+    // Keep the injection queued behind another one until it has been cancelled
+    let busy = INJECTION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let handle = {
+      let root = root.clone();
+      let backend = backend.clone();
+      std::thread::spawn(move || {
+        let mut injector = SkinInjector::with_backend(&root.join("app"), &root, backend);
+        injector.inject_skins_and_misc(&[skin()], &[], &root.join("app").join("champions"))
+      })
+    };
+    assert!(wait_until(|| cancel_in_flight() > 0));
+    drop(busy);
+
+    let result = handle.join().unwrap();
+    assert!(matches!(result, Err(InjectionError::Aborted(_))));
+    let mods_dir = root.join("Game").join("mods");
+    assert!(mods_dir.join("previous").exists());
+    assert!(!mods_dir.join("ahri_arcade").exists());
+    assert!(backend.runs.lock().unwrap().is_empty());
+    assert_eq!(cancel_in_flight(), 0);
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn injection_past_its_deadline_times_out() {
+    let _lock = OVERLAY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = temp_root("inject-deadline");
+    let backend = FakeBackend::new(vec![FakeScript::new(FakeEnd::RunUntilStopped)]);
+    let mut injector = SkinInjector::with_backend(&root.join("app"), &root, backend.clone());
+    let passed = Instant::now() - Duration::from_secs(1);
+    injector.deadline = Some(InjectionDeadline::at(passed));
+
+    let result =
+      injector.inject_skins_and_misc(&[skin()], &[], &root.join("app").join("champions"));
+    assert!(matches!(result, Err(InjectionError::Timeout(_))));
+    assert!(matches!(
+      injector.timeout,
+      Some(TimeoutReason::Expired {
+        stage: TimingStage::Extract,
+        ..
+      })
+    ));
+    let mods_dir = root.join("Game").join("mods");
+    assert!(mods_dir.join("previous").exists());
+    assert!(!mods_dir.join("ahri_arcade").exists());
+    assert!(backend.runs.lock().unwrap().is_empty());
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn persistent_overlay_is_reused_until_the_loadout_changes() {
+    let _lock = OVERLAY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let root = temp_root("inject-persistent");
+    let backend = FakeBackend::new(vec![
+      FakeScript::new(FakeEnd::RunUntilStopped),
+      FakeScript::new(FakeEnd::RunUntilStopped),
+    ]);
+    let champions = root.join("app").join("champions");
+    let mut writer = zip::ZipWriter::new(fs::File::create(champions.join("lux_star.zip")).unwrap());
+    writer
+      .start_file("WAD/Lux.wad.client/skin.bin", Default::default())
+      .unwrap();
+    writer.write_all(b"lux").unwrap();
+    writer.finish().unwrap();
+    let lux = Skin {
+      champion_id: 99,
+      skin_id: 7,
+      chroma_id: None,
+      skin_file_path: Some("lux_star.zip".to_string()),
+    };
+
+    let marker = root.join("Game").join("mods").join("marker");
+    for reused in [false, true] {
+      let mut injector = SkinInjector::with_backend(&root.join("app"), &root, backend.clone());
+      injector.persistent_overlay = true;
+      injector
+        .inject_skins_and_misc(&[skin()], &[], &champions)
+        .unwrap();
+      assert_eq!(injector.state, ModState::Running);
+      // Staging replaces Game/mods as a whole
+      assert_eq!(marker.exists(), reused);
+      fs::write(&marker, "").unwrap();
+    }
+    // The next game with the same skins goes to the overlay that is already waiting
+    assert_eq!(backend.runs.lock().unwrap().len(), 1);
+    assert!(running_overlay_signature().is_some());
+    let records = InjectionJournal::for_app_dir(&root.join("app"))
+      .load()
+      .unwrap();
+    let outcomes: Vec<InjectionOutcome> = records.iter().map(|r| r.outcome).collect();
+    assert_eq!(
+      outcomes,
+      vec![InjectionOutcome::Pending, InjectionOutcome::Reused]
+    );
+
+    let mut injector = SkinInjector::with_backend(&root.join("app"), &root, backend.clone());
+    injector.persistent_overlay = true;
+    injector
+      .inject_skins_and_misc(&[skin(), lux], &[], &champions)
+      .unwrap();
+    {
+      let runs = backend.runs.lock().unwrap();
+      assert_eq!(runs.len(), 2);
+      assert_ne!(runs[0].overlay_dir, runs[1].overlay_dir);
+    }
+    assert!(is_global_overlay_running());
+
+    injector.cleanup().unwrap();
+    assert_eq!(running_overlay_signature(), None);
+
+    let _ = fs::remove_dir_all(root);
+  }
+}
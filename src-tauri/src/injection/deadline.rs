@@ -0,0 +1,192 @@
+use crate::injection::error::ModSource;
+use crate::injection::timing::TimingStage;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Injection deadlines
+//
+// runoverlay has to be waiting before League starts loading, otherwise the game runs without
+// the mods. In champ select that moment follows from `timer.adjustedTimeLeftInPhase` of
+// /lol-champ-select/v1/session. Injections started from champ select trim their load when the
+// deadline is close, and give up with InjectionError::Timeout once it has passed.
+
+// League starts loading shortly after the finalization timer runs out
+const GAME_START_GRACE: Duration = Duration::from_secs(5);
+// Below this much time left, misc items (maps, fonts, HUDs) are dropped
+const MISC_ITEMS_BUDGET: Duration = Duration::from_secs(12);
+// Below this much time left, only the first skin of each champion is kept
+const EXTRA_SKINS_BUDGET: Duration = Duration::from_secs(6);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectionDeadline {
+  at: Instant,
+}
+
+impl InjectionDeadline {
+  pub fn at(at: Instant) -> Self {
+    Self { at }
+  }
+
+  // Deadline for a champ select timer read at `now`. None during phases that are always
+  // followed by finalization, which gives the injection plenty of time.
+  pub fn from_champ_select_timer(phase: &str, time_left_ms: u64, now: Instant) -> Option<Self> {
+    match phase {
+      "FINALIZATION" | "GAME_STARTING" => Some(Self::at(
+        now + Duration::from_millis(time_left_ms) + GAME_START_GRACE,
+      )),
+      _ => None,
+    }
+  }
+
+  pub fn from_champ_select_session(session: &serde_json::Value, now: Instant) -> Option<Self> {
+    let timer = session.get("timer")?;
+    let phase = timer.get("phase")?.as_str()?;
+    let time_left_ms = timer
+      .get("adjustedTimeLeftInPhase")
+      .and_then(|v| v.as_f64())
+      .unwrap_or(0.0)
+      .max(0.0) as u64;
+    Self::from_champ_select_timer(phase, time_left_ms, now)
+  }
+
+  pub fn remaining(&self, now: Instant) -> Duration {
+    self.at.saturating_duration_since(now)
+  }
+
+  // How long ago the deadline passed, None while there is still time
+  pub fn overdue(&self, now: Instant) -> Option<Duration> {
+    now.checked_duration_since(self.at).filter(|d| !d.is_zero())
+  }
+}
+
+// Why an injection was cut down or given up because of its deadline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimeoutReason {
+  // Too little time was left for everything, so `dropped` was left out
+  Degraded {
+    remaining_ms: u64,
+    dropped: Vec<String>,
+  },
+  // The deadline passed before `stage`, so the injection gave up
+  Expired {
+    stage: TimingStage,
+    overdue_ms: u64,
+  },
+}
+
+// Indices of the mods to leave out with `remaining` time left: misc items go first, then
+// every skin but the first one of each champion, since those only conflict with it
+pub(crate) fn items_to_drop(sources: &[&ModSource], remaining: Duration) -> Vec<usize> {
+  let mut dropped = Vec::new();
+  if remaining < MISC_ITEMS_BUDGET {
+    dropped.extend(
+      sources
+        .iter()
+        .enumerate()
+        .filter(|(_, source)| matches!(source, ModSource::Misc { .. }))
+        .map(|(i, _)| i),
+    );
+  }
+  if remaining < EXTRA_SKINS_BUDGET {
+    let mut champions = Vec::new();
+    for (i, source) in sources.iter().enumerate() {
+      if let ModSource::Skin { champion_id, .. } = source {
+        if champions.contains(champion_id) {
+          dropped.push(i);
+        } else {
+          champions.push(*champion_id);
+        }
+      }
+    }
+    dropped.sort_unstable();
+  }
+  dropped
+}
+
+// Deadline from the latest champ select session, cleared when champ select ends
+static CHAMP_SELECT_DEADLINE: Lazy<Mutex<Option<InjectionDeadline>>> =
+  Lazy::new(|| Mutex::new(None));
+
+pub fn note_champ_select_session(session: &serde_json::Value) {
+  *CHAMP_SELECT_DEADLINE
+    .lock()
+    .expect("CHAMP_SELECT_DEADLINE poisoned") =
+    InjectionDeadline::from_champ_select_session(session, Instant::now());
+}
+
+pub fn clear_champ_select_deadline() {
+  CHAMP_SELECT_DEADLINE
+    .lock()
+    .expect("CHAMP_SELECT_DEADLINE poisoned")
+    .take();
+}
+
+pub fn champ_select_deadline() -> Option<InjectionDeadline> {
+  *CHAMP_SELECT_DEADLINE
+    .lock()
+    .expect("CHAMP_SELECT_DEADLINE poisoned")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn skin(champion_id: u32, skin_id: u32) -> ModSource {
+    ModSource::Skin {
+      champion_id,
+      skin_id,
+      chroma_id: None,
+    }
+  }
+
+  #[test]
+  fn reads_the_deadline_from_the_session_timer() {
+    let now = Instant::now();
+    let session = serde_json::json!({
+      "timer": { "phase": "FINALIZATION", "adjustedTimeLeftInPhase": 9500.0 }
+    });
+    let deadline = InjectionDeadline::from_champ_select_session(&session, now).unwrap();
+    assert_eq!(deadline.remaining(now), Duration::from_millis(14_500));
+    assert_eq!(deadline.overdue(now), None);
+    assert_eq!(
+      deadline.overdue(now + Duration::from_secs(15)),
+      Some(Duration::from_millis(500))
+    );
+    assert_eq!(
+      deadline.remaining(now + Duration::from_secs(15)),
+      Duration::ZERO
+    );
+
+    let picking = serde_json::json!({
+      "timer": { "phase": "BAN_PICK", "adjustedTimeLeftInPhase": 2000 }
+    });
+    assert_eq!(
+      InjectionDeadline::from_champ_select_session(&picking, now),
+      None
+    );
+    assert_eq!(
+      InjectionDeadline::from_champ_select_session(&serde_json::json!({}), now),
+      None
+    );
+  }
+
+  #[test]
+  fn drops_misc_items_first_then_extra_skins() {
+    let misc = ModSource::Misc {
+      id: "map".into(),
+      name: "Map".into(),
+      item_type: "map".into(),
+    };
+    let ahri = skin(103, 103001);
+    let friend_ahri = skin(103, 103002);
+    let lux = skin(99, 99001);
+    let sources = vec![&ahri, &misc, &friend_ahri, &lux];
+
+    assert!(items_to_drop(&sources, Duration::from_secs(30)).is_empty());
+    assert_eq!(items_to_drop(&sources, Duration::from_secs(8)), vec![1]);
+    assert_eq!(items_to_drop(&sources, Duration::from_secs(2)), vec![1, 2]);
+  }
+}
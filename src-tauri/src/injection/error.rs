@@ -11,14 +11,22 @@ pub enum InjectionError {
   InvalidGamePath(String),
   MissingFantomeFile(String),
   ProcessError(String),
+  // mod-tools.exe could not be found or started
+  ModToolsMissing(String),
+  // The overlay directory could not be replaced, usually because another process holds it
+  OverlayDirLocked(String),
   #[allow(dead_code)]
   ConfigError(String),
-  #[allow(dead_code)]
+  // Champ select ran out before the injection finished, see deadline.rs
   Timeout(String),
-  #[allow(dead_code)]
+  // The injection was cancelled, see cancel.rs
   Aborted(String),
+  // A newer request replaced this one before it ran, see queue.rs
+  Superseded(String),
   WalkdirError(walkdir::Error),
   ZipError(zip::result::ZipError),
+  // An archive broke one of the ExtractionLimits while being converted
+  ArchiveLimitExceeded(String),
 }
 
 impl std::fmt::Display for InjectionError {
@@ -28,11 +36,15 @@ impl std::fmt::Display for InjectionError {
       Self::InvalidGamePath(msg) => write!(f, "Invalid game path: {}", msg),
       Self::MissingFantomeFile(msg) => write!(f, "Missing skin_file file: {}", msg),
       Self::ProcessError(msg) => write!(f, "Process error: {}", msg),
+      Self::ModToolsMissing(msg) => write!(f, "mod-tools unavailable: {}", msg),
+      Self::OverlayDirLocked(msg) => write!(f, "Overlay directory locked: {}", msg),
       Self::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
       Self::Timeout(msg) => write!(f, "Timeout: {}", msg),
       Self::Aborted(msg) => write!(f, "Aborted: {}", msg),
+      Self::Superseded(msg) => write!(f, "Superseded: {}", msg),
       Self::WalkdirError(err) => write!(f, "Walkdir error: {}", err),
       Self::ZipError(err) => write!(f, "Zip error: {}", err),
+      Self::ArchiveLimitExceeded(msg) => write!(f, "Archive limit exceeded: {}", msg),
     }
   }
 }
@@ -76,6 +88,22 @@ pub struct MiscItem {
   pub skin_file_path: String,
 }
 
+// What a processed mod directory was built from, used to label overlay conflicts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ModSource {
+  Skin {
+    champion_id: u32,
+    skin_id: u32,
+    chroma_id: Option<u32>,
+  },
+  Misc {
+    id: String,
+    name: String,
+    item_type: String,
+  },
+}
+
 // Injection request that includes both skins and misc items
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InjectionRequest {
@@ -95,8 +123,8 @@ pub enum ModState {
 }
 
 // This represents a message event for the patcher
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PatcherMessage {
   WaitStart,
   Found,
@@ -110,7 +138,26 @@ pub enum PatcherMessage {
 }
 
 impl PatcherMessage {
-  #[allow(dead_code)]
+  pub const ALL: [PatcherMessage; 9] = [
+    Self::WaitStart,
+    Self::Found,
+    Self::WaitInit,
+    Self::Scan,
+    Self::NeedSave,
+    Self::WaitPatchable,
+    Self::Patch,
+    Self::WaitExit,
+    Self::Done,
+  ];
+
+  // Stage for the text after "Status: " in runoverlay output
+  pub fn from_status(status: &str) -> Option<Self> {
+    let status = status.trim().to_lowercase();
+    Self::ALL
+      .into_iter()
+      .find(|message| status.starts_with(&message.to_string().to_lowercase()))
+  }
+
   pub fn to_string(&self) -> &'static str {
     match self {
       Self::WaitStart => "Waiting for league match to start",
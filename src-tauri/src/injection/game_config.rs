@@ -1,52 +1,411 @@
 use crate::injection::error::InjectionError;
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io;
+use std::path::Path;
 
 // Game.cfg management operations
+//
+// Game.cfg is edited through a small INI model that only rewrites the lines it has to, so
+// comments, ordering and line endings survive. The original file is backed up next to it
+// before the first change and put back by cleanup.
+
+// Backup of the original Game.cfg, kept in the Game directory until cleanup
+pub(crate) const GAME_CFG_BACKUP: &str = "Game.cfg.osskins-backup";
+
+const GENERAL_SECTION: &str = "General";
+const ENABLE_MODS_KEY: &str = "EnableMods";
+
+// Lines of an INI file. Only section headers and `key=value` lines are interpreted; every
+// other line is kept as is.
+#[derive(Debug, Clone)]
+pub(crate) struct IniDocument {
+  lines: Vec<String>,
+  newline: &'static str,
+  trailing_newline: bool,
+  bom: bool,
+}
+
+fn section_name(line: &str) -> Option<&str> {
+  let line = line.trim();
+  line
+    .strip_prefix('[')
+    .and_then(|rest| rest.strip_suffix(']'))
+    .map(str::trim)
+}
+
+fn key_value(line: &str) -> Option<(&str, &str)> {
+  let trimmed = line.trim_start();
+  if trimmed.starts_with(';') || trimmed.starts_with('#') {
+    return None;
+  }
+  let (key, value) = trimmed.split_once('=')?;
+  Some((key.trim(), value.trim()))
+}
+
+impl IniDocument {
+  pub(crate) fn parse(content: &str) -> Self {
+    let (bom, content) = match content.strip_prefix('\u{feff}') {
+      Some(rest) => (true, rest),
+      None => (false, content),
+    };
+    Self {
+      lines: content.lines().map(str::to_string).collect(),
+      newline: if content.contains("\r\n") {
+        "\r\n"
+      } else {
+        "\n"
+      },
+      trailing_newline: content.is_empty() || content.ends_with('\n'),
+      bom,
+    }
+  }
+
+  pub(crate) fn render(&self) -> String {
+    let mut out = String::new();
+    if self.bom {
+      out.push('\u{feff}');
+    }
+    out.push_str(&self.lines.join(self.newline));
+    if self.trailing_newline && !self.lines.is_empty() {
+      out.push_str(self.newline);
+    }
+    out
+  }
+
+  // Indices of the `key=value` lines for `key` inside every `[section]`
+  fn entries(&self, section: &str, key: &str) -> Vec<usize> {
+    let mut current: Option<&str> = None;
+    let mut found = Vec::new();
+    for (i, line) in self.lines.iter().enumerate() {
+      if let Some(name) = section_name(line) {
+        current = Some(name);
+      } else if current.is_some_and(|name| name.eq_ignore_ascii_case(section)) {
+        if let Some((k, _)) = key_value(line) {
+          if k.eq_ignore_ascii_case(key) {
+            found.push(i);
+          }
+        }
+      }
+    }
+    found
+  }
+
+  // Value of `key` in `[section]`; with duplicates the last one wins
+  pub(crate) fn get(&self, section: &str, key: &str) -> Option<&str> {
+    let i = *self.entries(section, key).last()?;
+    key_value(&self.lines[i]).map(|(_, value)| value)
+  }
+
+  // Set `key` in `[section]`, adding the key or the section when missing. Duplicates are all
+  // set so every reader sees the same value. Returns whether anything changed.
+  pub(crate) fn set(&mut self, section: &str, key: &str, value: &str) -> bool {
+    let entries = self.entries(section, key);
+    if !entries.is_empty() {
+      let mut changed = false;
+      for i in entries {
+        let line = &self.lines[i];
+        if key_value(line).map(|(_, v)| v) == Some(value) {
+          continue;
+        }
+        let eq = line.find('=').unwrap_or(line.len());
+        let rest = &line[eq + 1..];
+        let padding = &rest[..rest.len() - rest.trim_start().len()];
+        self.lines[i] = format!("{}={}{}", &line[..eq], padding, value);
+        changed = true;
+      }
+      return changed;
+    }
+
+    let header = self
+      .lines
+      .iter()
+      .position(|line| section_name(line).is_some_and(|name| name.eq_ignore_ascii_case(section)));
+    match header {
+      Some(header) => {
+        // After the last non-blank line of the section
+        let mut end = self.lines[header + 1..]
+          .iter()
+          .position(|line| section_name(line).is_some())
+          .map_or(self.lines.len(), |offset| header + 1 + offset);
+        while end > header + 1 && self.lines[end - 1].trim().is_empty() {
+          end -= 1;
+        }
+        self.lines.insert(end, format!("{}={}", key, value));
+      }
+      None => {
+        if self
+          .lines
+          .last()
+          .is_some_and(|line| !line.trim().is_empty())
+        {
+          self.lines.push(String::new());
+        }
+        self.lines.push(format!("[{}]", section));
+        self.lines.push(format!("{}={}", key, value));
+        self.trailing_newline = true;
+      }
+    }
+    true
+  }
+
+  // Remove every `key` line from `[section]`. Returns whether anything changed.
+  pub(crate) fn remove(&mut self, section: &str, key: &str) -> bool {
+    let entries = self.entries(section, key);
+    for i in entries.iter().rev() {
+      self.lines.remove(*i);
+    }
+    !entries.is_empty()
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GameCfgBackup {
+  // Original Game.cfg; None when there was no Game.cfg
+  original: Option<String>,
+  // What we last wrote, to tell whether something else rewrote the file since
+  written: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum GameCfgChange {
+  Created,
+  Updated,
+  AlreadyEnabled,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum GameCfgRestore {
+  NothingToRestore,
+  // Put back the original file (or removed the one we created)
+  Restored,
+  // The file changed since we wrote it, so only EnableMods was put back
+  Reverted,
+}
+
+fn read_game_cfg(path: &Path) -> Result<Option<String>, InjectionError> {
+  match fs::read_to_string(path) {
+    Ok(content) => Ok(Some(content)),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+fn read_backup(path: &Path) -> Option<GameCfgBackup> {
+  fs::read_to_string(path)
+    .ok()
+    .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+// Set EnableMods=1 under [General], backing up the original Game.cfg before the first change
+pub(crate) fn enable_mods(game_path: &Path) -> Result<GameCfgChange, InjectionError> {
+  let cfg_path = game_path.join("Game.cfg");
+  let original = read_game_cfg(&cfg_path)?;
+
+  let mut doc = IniDocument::parse(original.as_deref().unwrap_or_default());
+  if !doc.set(GENERAL_SECTION, ENABLE_MODS_KEY, "1") && original.is_some() {
+    return Ok(GameCfgChange::AlreadyEnabled);
+  }
+  let written = doc.render();
+
+  // An earlier backup holds the real original; ours would already contain EnableMods=1
+  let backup_path = game_path.join(GAME_CFG_BACKUP);
+  let backup = match read_backup(&backup_path) {
+    Some(backup) => GameCfgBackup {
+      written: written.clone(),
+      ..backup
+    },
+    None => GameCfgBackup {
+      original: original.clone(),
+      written: written.clone(),
+    },
+  };
+  let backup_json = serde_json::to_string_pretty(&backup)
+    .map_err(|e| InjectionError::ProcessError(format!("Failed to back up Game.cfg: {}", e)))?;
+  fs::write(&backup_path, backup_json)?;
+  fs::write(&cfg_path, written)?;
+
+  Ok(if original.is_some() {
+    GameCfgChange::Updated
+  } else {
+    GameCfgChange::Created
+  })
+}
+
+// Undo `enable_mods` using the backup, then remove the backup
+pub(crate) fn restore_game_cfg(game_path: &Path) -> Result<GameCfgRestore, InjectionError> {
+  let backup_path = game_path.join(GAME_CFG_BACKUP);
+  if !backup_path.exists() {
+    return Ok(GameCfgRestore::NothingToRestore);
+  }
+  let Some(backup) = read_backup(&backup_path) else {
+    // Unreadable, there is nothing left to restore from
+    fs::remove_file(&backup_path)?;
+    return Ok(GameCfgRestore::NothingToRestore);
+  };
+
+  let cfg_path = game_path.join("Game.cfg");
+  let result = match read_game_cfg(&cfg_path)? {
+    Some(current) if current == backup.written => {
+      match &backup.original {
+        Some(original) => fs::write(&cfg_path, original)?,
+        None => fs::remove_file(&cfg_path)?,
+      }
+      GameCfgRestore::Restored
+    }
+    // The game saved its settings since; keep them and only undo our key
+    Some(current) => {
+      let mut doc = IniDocument::parse(&current);
+      let original_value =
+        backup
+          .original
+          .as_deref()
+          .map(IniDocument::parse)
+          .and_then(|original| {
+            original
+              .get(GENERAL_SECTION, ENABLE_MODS_KEY)
+              .map(str::to_string)
+          });
+      let changed = match original_value {
+        Some(value) => doc.set(GENERAL_SECTION, ENABLE_MODS_KEY, &value),
+        None => doc.remove(GENERAL_SECTION, ENABLE_MODS_KEY),
+      };
+      if changed {
+        fs::write(&cfg_path, doc.render())?;
+      }
+      GameCfgRestore::Reverted
+    }
+    None => GameCfgRestore::Restored,
+  };
+
+  fs::remove_file(&backup_path)?;
+  Ok(result)
+}
 
 impl crate::injection::core::SkinInjector {
   // Enable mods in Game.cfg
   pub(crate) fn enable_mods_in_game_cfg(&mut self) -> Result<(), InjectionError> {
-    let game_cfg_path = self.game_path.join("Game.cfg");
-
-    // If file doesn't exist, create it with EnableMods=1
-    if !game_cfg_path.exists() {
-      fs::write(game_cfg_path, "[General]\nEnableMods=1\n")?;
-      self.log("Created Game.cfg with EnableMods=1");
-      return Ok(());
-    }
-
-    // Otherwise, read and modify the file
-    let content = fs::read_to_string(&game_cfg_path)?;
-
-    // Check if EnableMods is already set correctly
-    if content.contains("EnableMods=1") {
-      self.log("Game.cfg already has EnableMods=1");
-      return Ok(());
-    }
-
-    // Replace EnableMods=0 with EnableMods=1 if it exists
-    let mut new_content = content.clone();
-    if content.contains("EnableMods=0") {
-      new_content = content.replace("EnableMods=0", "EnableMods=1");
-    } else {
-      // Add EnableMods=1 to the [General] section if it exists
-      if content.contains("[General]") {
-        let parts: Vec<&str> = content.split("[General]").collect();
-        if parts.len() >= 2 {
-          // Fix the temporary value borrowed error
-          let new_part = format!("\nEnableMods=1{}", parts[1]);
-          new_content = format!("{}[General]{}", parts[0], new_part);
-        }
-      } else {
-        // If no [General] section, add it
-        new_content = format!("{}\n[General]\nEnableMods=1\n", content);
+    match enable_mods(&self.game_path)? {
+      GameCfgChange::Created => self.log("Created Game.cfg with EnableMods=1"),
+      GameCfgChange::Updated => self.log("Updated Game.cfg to enable mods"),
+      GameCfgChange::AlreadyEnabled => self.log("Game.cfg already has EnableMods=1"),
+    }
+    Ok(())
+  }
+
+  // Put Game.cfg back the way it was before mods were enabled
+  pub(crate) fn restore_game_cfg(&mut self) -> Result<(), InjectionError> {
+    match restore_game_cfg(&self.game_path)? {
+      GameCfgRestore::NothingToRestore => {}
+      GameCfgRestore::Restored => self.log("Restored the original Game.cfg"),
+      GameCfgRestore::Reverted => {
+        self.log("Game.cfg changed since mods were enabled, reverted EnableMods only")
       }
     }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::path::PathBuf;
+
+  fn temp_game(game_cfg: Option<&str>) -> PathBuf {
+    let game = std::env::temp_dir().join(format!("osskins-gamecfg-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&game).unwrap();
+    if let Some(content) = game_cfg {
+      fs::write(game.join("Game.cfg"), content).unwrap();
+    }
+    game
+  }
+
+  fn read_cfg(game: &Path) -> String {
+    fs::read_to_string(game.join("Game.cfg")).unwrap()
+  }
 
-    // Write the updated content
-    fs::write(game_cfg_path, new_content)?;
-    self.log("Updated Game.cfg to enable mods");
+  #[test]
+  fn sets_key_only_under_general_and_keeps_crlf_and_comments() {
+    let original = "; EnableMods=1 in a comment\r\n[Sound]\r\nEnableMods=1\r\n\r\n[General]\r\n\
+                    WindowMode=2\r\n\r\n[HUD]\r\nScale=1\r\n";
+    let game = temp_game(Some(original));
 
-    Ok(())
+    assert_eq!(enable_mods(&game).unwrap(), GameCfgChange::Updated);
+    assert_eq!(
+      read_cfg(&game),
+      "; EnableMods=1 in a comment\r\n[Sound]\r\nEnableMods=1\r\n\r\n[General]\r\n\
+       WindowMode=2\r\nEnableMods=1\r\n\r\n[HUD]\r\nScale=1\r\n"
+    );
+
+    assert_eq!(restore_game_cfg(&game).unwrap(), GameCfgRestore::Restored);
+    assert_eq!(read_cfg(&game), original);
+    assert!(!game.join(GAME_CFG_BACKUP).exists());
+
+    let _ = fs::remove_dir_all(game);
+  }
+
+  #[test]
+  fn adds_general_section_when_missing() {
+    let game = temp_game(Some("[HUD]\nScale=1"));
+
+    assert_eq!(enable_mods(&game).unwrap(), GameCfgChange::Updated);
+    assert_eq!(
+      read_cfg(&game),
+      "[HUD]\nScale=1\n\n[General]\nEnableMods=1\n"
+    );
+    restore_game_cfg(&game).unwrap();
+    assert_eq!(read_cfg(&game), "[HUD]\nScale=1");
+
+    // No Game.cfg at all: created, then removed again
+    let fresh = temp_game(None);
+    assert_eq!(enable_mods(&fresh).unwrap(), GameCfgChange::Created);
+    assert_eq!(read_cfg(&fresh), "[General]\nEnableMods=1\n");
+    assert_eq!(restore_game_cfg(&fresh).unwrap(), GameCfgRestore::Restored);
+    assert!(!fresh.join("Game.cfg").exists());
+
+    let _ = fs::remove_dir_all(game);
+    let _ = fs::remove_dir_all(fresh);
+  }
+
+  #[test]
+  fn sets_every_duplicate_key_and_backs_up_once() {
+    let original = "[General]\nEnableMods = 0\nenablemods=0\n[general]\nEnableMods=1\n";
+    let game = temp_game(Some(original));
+
+    assert_eq!(enable_mods(&game).unwrap(), GameCfgChange::Updated);
+    assert_eq!(
+      read_cfg(&game),
+      "[General]\nEnableMods = 1\nenablemods=1\n[general]\nEnableMods=1\n"
+    );
+    assert_eq!(enable_mods(&game).unwrap(), GameCfgChange::AlreadyEnabled);
+
+    // The game reset the key; enabling again must not replace the original backup
+    fs::write(game.join("Game.cfg"), "[General]\nEnableMods=0\n").unwrap();
+    assert_eq!(enable_mods(&game).unwrap(), GameCfgChange::Updated);
+    assert_eq!(restore_game_cfg(&game).unwrap(), GameCfgRestore::Restored);
+    assert_eq!(read_cfg(&game), original);
+
+    let _ = fs::remove_dir_all(game);
+  }
+
+  #[test]
+  fn keeps_settings_saved_by_the_game_when_restoring() {
+    let game = temp_game(Some("[General]\nWindowMode=2\n"));
+    enable_mods(&game).unwrap();
+
+    // The game saved other settings while mods were enabled
+    fs::write(
+      game.join("Game.cfg"),
+      "[General]\nWindowMode=1\nEnableMods=1\n",
+    )
+    .unwrap();
+    assert_eq!(restore_game_cfg(&game).unwrap(), GameCfgRestore::Reverted);
+    assert_eq!(read_cfg(&game), "[General]\nWindowMode=1\n");
+    assert_eq!(
+      restore_game_cfg(&game).unwrap(),
+      GameCfgRestore::NothingToRestore
+    );
+
+    let _ = fs::remove_dir_all(game);
   }
 }
@@ -0,0 +1,371 @@
+use crate::injection::deadline::TimeoutReason;
+use crate::injection::error::{InjectionError, MiscItem, ModSource, Skin};
+use crate::injection::patcher::StageTimestamp;
+use crate::injection::plan::MissingItem;
+use crate::injection::skin_file::ResolutionMethod;
+use crate::injection::timing::TimingReport;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Injection history journal
+//
+// app_data/injection_history.jsonl gets one line per injection when it finishes, and one
+// line per patcher outcome once mod-tools reports whether the game was patched. Reading the
+// history merges the two by injection id, so the file itself is only ever appended to.
+
+const JOURNAL_FILE: &str = "injection_history.jsonl";
+// Past this size the journal is rewritten with only the newest MAX_RECORDS injections
+const MAX_JOURNAL_BYTES: u64 = 2 * 1024 * 1024;
+const MAX_RECORDS: usize = 200;
+
+// What started an injection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionTrigger {
+  // Auto injection when the champion is locked in ChampSelect
+  ChampSelect,
+  // Lobby -> Matchmaking for queues that assign champions instantly
+  InstantAssign,
+  // Party mode, with skins shared by friends
+  Party,
+  // Started from the UI or the manual injection mode
+  Manual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionOutcome {
+  // The injection itself failed, mod-tools was never started
+  Failed,
+  // The overlay is running but hasn't reported patching yet
+  Pending,
+  // mod-tools reported the game as patched
+  Patched,
+  // The overlay exited before the game was patched
+  OverlayStopped,
+  // A persistent overlay built from the same mods was already running and was kept
+  Reused,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedFile {
+  pub label: String,
+  pub mod_name: String,
+  pub archive: String,
+  pub source: ModSource,
+  pub method: Option<ResolutionMethod>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InjectionRecord {
+  pub id: String,
+  pub trigger: InjectionTrigger,
+  pub started_at_ms: u64,
+  pub finished_at_ms: u64,
+  pub champion_ids: Vec<u32>,
+  pub skins: Vec<Skin>,
+  pub misc_items: Vec<MiscItem>,
+  #[serde(default)]
+  pub resolved: Vec<ResolvedFile>,
+  #[serde(default)]
+  pub missing: Vec<MissingItem>,
+  #[serde(default)]
+  pub timing: TimingReport,
+  // Set when the injection ran short of time in champ select
+  #[serde(default)]
+  pub timeout: Option<TimeoutReason>,
+  pub error: Option<String>,
+  // Filled in from the latest outcome line when the history is read
+  pub outcome: InjectionOutcome,
+  #[serde(default)]
+  pub outcome_error: Option<String>,
+  #[serde(default)]
+  pub patcher_stages: Vec<StageTimestamp>,
+}
+
+impl InjectionRecord {
+  pub(crate) fn start(trigger: InjectionTrigger, skins: &[Skin], misc_items: &[MiscItem]) -> Self {
+    let mut champion_ids: Vec<u32> = skins.iter().map(|s| s.champion_id).collect();
+    champion_ids.sort_unstable();
+    champion_ids.dedup();
+    let id = uuid::Uuid::new_v4().to_string();
+    Self {
+      timing: TimingReport::new(id.clone()),
+      id,
+      trigger,
+      started_at_ms: now_ms(),
+      finished_at_ms: 0,
+      champion_ids,
+      skins: skins.to_vec(),
+      misc_items: misc_items.to_vec(),
+      resolved: Vec::new(),
+      missing: Vec::new(),
+      timeout: None,
+      error: None,
+      outcome: InjectionOutcome::Pending,
+      outcome_error: None,
+      patcher_stages: Vec::new(),
+    }
+  }
+
+  pub(crate) fn finish(&mut self, error: Option<String>) {
+    self.finished_at_ms = now_ms();
+    if error.is_some() {
+      self.outcome = InjectionOutcome::Failed;
+    }
+    self.error = error;
+  }
+}
+
+// What mod-tools did with an injection, appended after the injection record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutcomeRecord {
+  pub id: String,
+  pub outcome: InjectionOutcome,
+  pub error: Option<String>,
+  pub at_ms: u64,
+  #[serde(default)]
+  pub patcher_stages: Vec<StageTimestamp>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JournalEntry {
+  Injection(Box<InjectionRecord>),
+  Outcome(OutcomeRecord),
+}
+
+fn now_ms() -> u64 {
+  crate::commands::lcu_watcher::types::current_time_ms()
+}
+
+#[derive(Debug, Clone)]
+pub struct InjectionJournal {
+  path: PathBuf,
+}
+
+impl InjectionJournal {
+  pub fn for_app_dir(app_dir: &Path) -> Self {
+    Self {
+      path: app_dir.join(JOURNAL_FILE),
+    }
+  }
+
+  fn append_entry(&self, entry: &JournalEntry) -> Result<(), InjectionError> {
+    if fs::metadata(&self.path).is_ok_and(|m| m.len() > MAX_JOURNAL_BYTES) {
+      self.compact()?;
+    }
+    let mut line = serde_json::to_string(entry)
+      .map_err(|e| InjectionError::ProcessError(format!("Failed to encode history: {}", e)))?;
+    line.push('\n');
+    if let Some(parent) = self.path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&self.path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+  }
+
+  pub fn append_injection(&self, record: &InjectionRecord) -> Result<(), InjectionError> {
+    self.append_entry(&JournalEntry::Injection(Box::new(record.clone())))
+  }
+
+  pub fn append_outcome(&self, outcome: OutcomeRecord) -> Result<(), InjectionError> {
+    self.append_entry(&JournalEntry::Outcome(outcome))
+  }
+
+  fn read_entries(&self) -> Result<Vec<JournalEntry>, InjectionError> {
+    let content = match fs::read_to_string(&self.path) {
+      Ok(content) => content,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(e) => return Err(e.into()),
+    };
+    // A line cut short by a crash is skipped rather than failing the whole history
+    Ok(
+      content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect(),
+    )
+  }
+
+  // Injections oldest first, each with its latest outcome applied
+  pub fn load(&self) -> Result<Vec<InjectionRecord>, InjectionError> {
+    let mut records = Vec::new();
+    let mut outcomes: HashMap<String, OutcomeRecord> = HashMap::new();
+    for entry in self.read_entries()? {
+      match entry {
+        JournalEntry::Injection(record) => records.push(*record),
+        JournalEntry::Outcome(outcome) => {
+          outcomes.insert(outcome.id.clone(), outcome);
+        }
+      }
+    }
+    for record in &mut records {
+      if record.error.is_some() {
+        continue;
+      }
+      if let Some(outcome) = outcomes.remove(&record.id) {
+        record.outcome = outcome.outcome;
+        record.outcome_error = outcome.error;
+        record.patcher_stages = outcome.patcher_stages;
+      }
+    }
+    Ok(records)
+  }
+
+  // Rewrite the journal with only the newest MAX_RECORDS injections
+  fn compact(&self) -> Result<(), InjectionError> {
+    let records = self.load()?;
+    let keep = &records[records.len().saturating_sub(MAX_RECORDS)..];
+    let mut content = String::new();
+    for record in keep {
+      if let Ok(line) = serde_json::to_string(&JournalEntry::Injection(Box::new(record.clone()))) {
+        content.push_str(&line);
+        content.push('\n');
+      }
+    }
+    let tmp = self.path.with_extension("jsonl.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, &self.path)?;
+    Ok(())
+  }
+
+  pub fn clear(&self) -> Result<(), InjectionError> {
+    match fs::remove_file(&self.path) {
+      Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+      _ => Ok(()),
+    }
+  }
+}
+
+// Where patcher outcomes of a running overlay are written
+#[derive(Debug, Clone)]
+pub(crate) struct OutcomeSink {
+  journal: InjectionJournal,
+  id: String,
+}
+
+impl OutcomeSink {
+  pub(crate) fn new(journal: InjectionJournal, id: String) -> Self {
+    Self { journal, id }
+  }
+
+  pub(crate) fn injection_id(&self) -> &str {
+    &self.id
+  }
+
+  pub(crate) fn record(
+    &self,
+    outcome: InjectionOutcome,
+    error: Option<String>,
+    patcher_stages: &[StageTimestamp],
+  ) {
+    let result = self.journal.append_outcome(OutcomeRecord {
+      id: self.id.clone(),
+      outcome,
+      error,
+      at_ms: now_ms(),
+      patcher_stages: patcher_stages.to_vec(),
+    });
+    if let Err(e) = result {
+      println!("[History] Failed to record injection outcome: {}", e);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::injection::timing::TimingStage;
+  use std::time::Duration;
+
+  fn journal() -> (PathBuf, InjectionJournal) {
+    let dir = std::env::temp_dir().join(format!("osskins-history-{}", uuid::Uuid::new_v4()));
+    let journal = InjectionJournal::for_app_dir(&dir);
+    (dir, journal)
+  }
+
+  fn skin(champion_id: u32) -> Skin {
+    Skin {
+      champion_id,
+      skin_id: champion_id * 1000 + 1,
+      chroma_id: None,
+      skin_file_path: None,
+    }
+  }
+
+  #[test]
+  fn merges_outcomes_into_injections() {
+    let (dir, journal) = journal();
+    assert!(journal.load().unwrap().is_empty());
+
+    let mut patched = InjectionRecord::start(InjectionTrigger::Party, &[skin(103), skin(1)], &[]);
+    patched
+      .timing
+      .add(TimingStage::Resolve, Duration::from_millis(3));
+    patched.finish(None);
+    journal.append_injection(&patched).unwrap();
+
+    let mut failed = InjectionRecord::start(InjectionTrigger::Manual, &[skin(103)], &[]);
+    failed.finish(Some("Missing skin_file file".into()));
+    journal.append_injection(&failed).unwrap();
+
+    let sink = OutcomeSink::new(journal.clone(), patched.id.clone());
+    sink.record(
+      InjectionOutcome::OverlayStopped,
+      Some("crashed".into()),
+      &[],
+    );
+    sink.record(InjectionOutcome::Patched, None, &[]);
+    // Cut off while writing, e.g. by a crash
+    fs::write(
+      dir.join(JOURNAL_FILE),
+      fs::read_to_string(dir.join(JOURNAL_FILE)).unwrap() + "{\"type\":\"inj",
+    )
+    .unwrap();
+
+    let history = journal.load().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].id, patched.id);
+    assert_eq!(history[0].trigger, InjectionTrigger::Party);
+    assert_eq!(history[0].champion_ids, vec![1, 103]);
+    assert_eq!(history[0].timing.duration_of(TimingStage::Resolve), Some(3));
+    assert_eq!(history[0].outcome, InjectionOutcome::Patched);
+    assert_eq!(history[0].outcome_error, None);
+    assert_eq!(history[1].outcome, InjectionOutcome::Failed);
+    assert_eq!(history[1].error.as_deref(), Some("Missing skin_file file"));
+
+    journal.clear().unwrap();
+    assert!(journal.load().unwrap().is_empty());
+    journal.clear().unwrap();
+
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn compaction_keeps_newest_records() {
+    let (dir, journal) = journal();
+    let mut ids = Vec::new();
+    for _ in 0..MAX_RECORDS + 5 {
+      let mut record = InjectionRecord::start(InjectionTrigger::ChampSelect, &[skin(1)], &[]);
+      record.finish(None);
+      journal.append_injection(&record).unwrap();
+      ids.push(record.id);
+    }
+
+    journal.compact().unwrap();
+    let history = journal.load().unwrap();
+    assert_eq!(history.len(), MAX_RECORDS);
+    assert_eq!(history[0].id, ids[5]);
+    assert_eq!(history.last().unwrap().id, *ids.last().unwrap());
+
+    let _ = fs::remove_dir_all(dir);
+  }
+}
@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Per-injection log files
+//
+// Every injection writes its log to app_data/logs/injections/<timestamp>-<id>-<part>.log, where
+// <id> is the injection id from the history journal. A file that grows past MAX_LOG_FILE_BYTES
+// continues in the next part, and only the newest MAX_LOG_FILES files are kept. Names sort
+// chronologically, with the parts of one injection in order.
+
+const INJECTION_LOGS_DIR: &str = "injections";
+const MAX_LOG_FILE_BYTES: u64 = 512 * 1024;
+const MAX_LOG_FILES: usize = 40;
+
+pub fn injection_logs_dir(app_dir: &Path) -> PathBuf {
+  app_dir.join("logs").join(INJECTION_LOGS_DIR)
+}
+
+#[derive(Debug)]
+pub struct InjectionLog {
+  dir: PathBuf,
+  // "<timestamp>-<injection id>", shared by every part
+  base_name: String,
+  part: u32,
+  file: File,
+  written: u64,
+  max_bytes: u64,
+  max_files: usize,
+}
+
+impl InjectionLog {
+  pub fn create(dir: &Path, injection_id: &str) -> io::Result<Self> {
+    Self::create_with(
+      dir,
+      injection_id,
+      Utc::now(),
+      MAX_LOG_FILE_BYTES,
+      MAX_LOG_FILES,
+    )
+  }
+
+  fn create_with(
+    dir: &Path,
+    injection_id: &str,
+    started: DateTime<Utc>,
+    max_bytes: u64,
+    max_files: usize,
+  ) -> io::Result<Self> {
+    fs::create_dir_all(dir)?;
+    let base_name = format!("{}-{}", started.format("%Y%m%d-%H%M%S%3f"), injection_id);
+    let file = File::create(dir.join(part_file_name(&base_name, 1)))?;
+    prune_logs(dir, max_files);
+    Ok(Self {
+      dir: dir.to_path_buf(),
+      base_name,
+      part: 1,
+      file,
+      written: 0,
+      max_bytes,
+      max_files,
+    })
+  }
+
+  pub fn path(&self) -> PathBuf {
+    self.dir.join(part_file_name(&self.base_name, self.part))
+  }
+
+  pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+    let len = line.len() as u64 + 1;
+    if self.written > 0 && self.written + len > self.max_bytes {
+      self.part += 1;
+      self.file = File::create(self.path())?;
+      self.written = 0;
+      prune_logs(&self.dir, self.max_files);
+    }
+    writeln!(self.file, "{}", line)?;
+    self.file.flush()?;
+    self.written += len;
+    Ok(())
+  }
+}
+
+fn part_file_name(base_name: &str, part: u32) -> String {
+  format!("{}-{:03}.log", base_name, part)
+}
+
+// Log files in `dir`, oldest first
+fn list_logs(dir: &Path) -> Vec<PathBuf> {
+  let mut logs: Vec<PathBuf> = match fs::read_dir(dir) {
+    Ok(entries) => entries
+      .filter_map(|entry| entry.ok().map(|e| e.path()))
+      .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+      .collect(),
+    Err(_) => Vec::new(),
+  };
+  logs.sort();
+  logs
+}
+
+// Delete the oldest log files until at most `max_files` are left
+fn prune_logs(dir: &Path, max_files: usize) {
+  let logs = list_logs(dir);
+  let excess = logs.len().saturating_sub(max_files);
+  for path in &logs[..excess] {
+    let _ = fs::remove_file(path);
+  }
+}
+
+// Files of the newest `count` injections, oldest first
+pub fn recent_injection_logs(dir: &Path, count: usize) -> Vec<PathBuf> {
+  let logs = list_logs(dir);
+  let mut injections: Vec<(String, Vec<PathBuf>)> = Vec::new();
+  for path in logs {
+    let name = path
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_default();
+    // Strip the "-<part>" suffix to group the parts of one injection
+    let base = name
+      .rsplit_once('-')
+      .map(|(base, _)| base.to_string())
+      .unwrap_or(name);
+    match injections.last_mut() {
+      Some((last, parts)) if *last == base => parts.push(path),
+      _ => injections.push((base, vec![path])),
+    }
+  }
+  let skip = injections.len().saturating_sub(count);
+  injections
+    .into_iter()
+    .skip(skip)
+    .flat_map(|(_, parts)| parts)
+    .collect()
+}
+
+// The newest `count` injection logs as one text, each file under a header with its name
+pub fn bundle_recent_logs(dir: &Path, count: usize) -> String {
+  let mut bundle = String::new();
+  for path in recent_injection_logs(dir, count) {
+    let Ok(content) = fs::read_to_string(&path) else {
+      continue;
+    };
+    let name = path
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+    bundle.push_str(&format!("===== {} =====\n{}\n", name, content));
+  }
+  bundle
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("osskins-logs-{}", uuid::Uuid::new_v4()))
+  }
+
+  fn at(second: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, second).unwrap()
+  }
+
+  fn names(paths: &[PathBuf]) -> Vec<String> {
+    paths
+      .iter()
+      .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+      .collect()
+  }
+
+  #[test]
+  fn rotates_by_size_and_keeps_the_newest_files() {
+    let dir = temp_dir();
+
+    let mut first = InjectionLog::create_with(&dir, "aaa", at(1), 16, 4).unwrap();
+    first.write_line("0123456789").unwrap();
+    // Doesn't fit in the 16 bytes left of part 1
+    first.write_line("0123456789").unwrap();
+    assert!(first.path().ends_with("20240501-120001000-aaa-002.log"));
+    assert_eq!(
+      fs::read_to_string(dir.join("20240501-120001000-aaa-001.log")).unwrap(),
+      "0123456789\n"
+    );
+
+    let mut second = InjectionLog::create_with(&dir, "bbb", at(2), 16, 4).unwrap();
+    second.write_line("short").unwrap();
+    let _third = InjectionLog::create_with(&dir, "ccc", at(3), 16, 4).unwrap();
+    assert_eq!(list_logs(&dir).len(), 4);
+
+    // A fifth file pushes out the oldest part
+    let _fourth = InjectionLog::create_with(&dir, "ddd", at(4), 16, 4).unwrap();
+    assert_eq!(
+      names(&list_logs(&dir)),
+      vec![
+        "20240501-120001000-aaa-002.log",
+        "20240501-120002000-bbb-001.log",
+        "20240501-120003000-ccc-001.log",
+        "20240501-120004000-ddd-001.log",
+      ]
+    );
+
+    assert_eq!(
+      names(&recent_injection_logs(&dir, 2)),
+      vec![
+        "20240501-120003000-ccc-001.log",
+        "20240501-120004000-ddd-001.log"
+      ]
+    );
+    assert_eq!(recent_injection_logs(&dir, 10).len(), 4);
+    assert_eq!(
+      bundle_recent_logs(&dir, 1),
+      "===== 20240501-120004000-ddd-001.log =====\n\n"
+    );
+    assert!(bundle_recent_logs(&dir, 3).contains("bbb-001.log =====\nshort\n\n"));
+    assert!(recent_injection_logs(&dir.join("missing"), 3).is_empty());
+
+    let _ = fs::remove_dir_all(dir);
+  }
+}
@@ -1,13 +1,31 @@
 // Injection module - Re-exports all injection functionalities
+pub mod backend;
+pub mod cancel;
 pub mod core;
+pub mod deadline;
 pub mod error;
 pub mod game_config;
+pub mod history;
+pub mod injection_log;
+pub mod mod_cache;
 pub mod mod_tools;
+pub mod overlay;
+pub mod package;
+pub mod patcher;
+pub mod path_policy;
+pub mod plan;
+pub mod prebuild;
+pub mod queue;
 pub mod skin_file;
+pub mod staging;
+pub mod timing;
+pub mod wad;
+pub mod watchdog;
 
 // Re-export all public types and functions
 pub use core::*;
 pub use error::*;
+pub use history::InjectionTrigger;
 
 // Additional helper function for multi-champion injections without event emission
 pub fn inject_skins_and_misc_no_events(
@@ -16,7 +34,15 @@ pub fn inject_skins_and_misc_no_events(
   skins: &[Skin],
   misc_items: &[MiscItem],
   skin_file_files_dir: &std::path::Path,
+  trigger: InjectionTrigger,
 ) -> Result<(), InjectionError> {
-  let mut injector = core::SkinInjector::new(app, league_path)?;
-  injector.inject_skins_and_misc_no_events(skins, misc_items, skin_file_files_dir)
+  queue::run_queued(queue::InjectionJob {
+    app: app.clone(),
+    league_path: league_path.to_string(),
+    skins: skins.to_vec(),
+    misc_items: misc_items.to_vec(),
+    skin_file_files_dir: skin_file_files_dir.to_path_buf(),
+    trigger,
+    emit_events: false,
+  })
 }
@@ -0,0 +1,320 @@
+use crate::injection::cancel::CancelToken;
+use crate::injection::error::InjectionError;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Content-addressed caches of processed mods and built overlays
+//
+// Each archive is converted once into `app_dir/mod_cache/<md5>-v<format>/` (META + WAD) and
+// reused until the archive content or MOD_FORMAT_VERSION changes. Overlays live in
+// `app_dir/overlay_cache/<signature>/` and are reused for the exact same loadout.
+
+// Bump whenever the processed mod layout changes so stale entries are rebuilt
+pub const MOD_FORMAT_VERSION: u32 = 1;
+
+// Least recently used entries beyond these counts are removed after each insert.
+// Overlays hold full copies of the game WADs they touch, so keep only a few.
+const MAX_CACHED_MODS: usize = 200;
+const MAX_CACHED_OVERLAYS: usize = 8;
+
+// File whose presence marks a complete overlay cache entry
+pub const OVERLAY_MARKER: &str = "conflicts.json";
+
+const INDEX_FILE: &str = "index.json";
+
+// (size, mtime, md5) of an archive when it was last hashed
+type HashStamp = (u64, SystemTime, String);
+
+// Archive hashes keyed by path, reused while size and mtime are unchanged
+static HASH_MEMO: Lazy<Mutex<HashMap<PathBuf, HashStamp>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Serializes index.json updates
+static INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// Entries being built right now, so the same archive is never converted twice at once (e.g. by
+// a prebuild and the injection at lock-in)
+static BUILDING: Lazy<(Mutex<HashSet<PathBuf>>, Condvar)> =
+  Lazy::new(|| (Mutex::new(HashSet::new()), Condvar::new()));
+
+// How often a thread waiting for someone else's build checks for cancellation
+const BUILD_WAIT_POLL: Duration = Duration::from_millis(50);
+
+// MD5 of an archive's content as lowercase hex
+pub fn archive_hash(path: &Path) -> Result<String, InjectionError> {
+  let metadata = fs::metadata(path)?;
+  let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+
+  if let Some((size, mtime, hash)) = HASH_MEMO.lock().unwrap().get(path) {
+    if *size == metadata.len() && *mtime == modified {
+      return Ok(hash.clone());
+    }
+  }
+
+  let mut file = fs::File::open(path)?;
+  let mut context = md5::Context::new();
+  let mut buf = vec![0u8; 1 << 16];
+  loop {
+    let read = file.read(&mut buf)?;
+    if read == 0 {
+      break;
+    }
+    context.consume(&buf[..read]);
+  }
+  let hash = format!("{:x}", context.compute());
+
+  HASH_MEMO
+    .lock()
+    .unwrap()
+    .insert(path.to_path_buf(), (metadata.len(), modified, hash.clone()));
+  Ok(hash)
+}
+
+pub fn cache_key(archive_hash: &str) -> String {
+  format!("{}-v{}", archive_hash, MOD_FORMAT_VERSION)
+}
+
+// Held while an entry is built, see ModCache::claim
+pub struct BuildClaim {
+  dir: PathBuf,
+}
+
+impl Drop for BuildClaim {
+  fn drop(&mut self) {
+    let (building, finished) = &*BUILDING;
+    building.lock().unwrap().remove(&self.dir);
+    finished.notify_all();
+  }
+}
+
+pub struct ModCache {
+  root: PathBuf,
+  // Relative path that must exist for an entry to count as complete
+  marker: PathBuf,
+  max_entries: usize,
+}
+
+impl ModCache {
+  pub fn new(app_dir: &Path) -> Self {
+    Self {
+      root: app_dir.join("mod_cache"),
+      marker: Path::new("META").join("info.json"),
+      max_entries: MAX_CACHED_MODS,
+    }
+  }
+
+  // Cache of built overlays; each entry holds `overlay/` plus the conflict report
+  pub fn overlays(app_dir: &Path) -> Self {
+    Self {
+      root: app_dir.join("overlay_cache"),
+      marker: PathBuf::from(OVERLAY_MARKER),
+      max_entries: MAX_CACHED_OVERLAYS,
+    }
+  }
+
+  // Cached directory for `key`, if a complete one exists
+  pub fn lookup(&self, key: &str) -> Option<PathBuf> {
+    let dir = self.root.join(key);
+    if dir.join(&self.marker).exists() {
+      self.touch(key);
+      Some(dir)
+    } else {
+      None
+    }
+  }
+
+  // Like lookup, but leaves the LRU order alone (for dry runs)
+  pub fn peek(&self, key: &str) -> Option<PathBuf> {
+    let dir = self.root.join(key);
+    dir.join(&self.marker).exists().then_some(dir)
+  }
+
+  // Claim `key` for building, first waiting for a build of it that is already underway.
+  // Look the key up after claiming: that build has usually committed it by then.
+  pub fn claim(&self, key: &str, cancel: &CancelToken) -> Result<BuildClaim, InjectionError> {
+    let dir = self.root.join(key);
+    let (building, finished) = &*BUILDING;
+    let mut guard = building.lock().unwrap();
+    while guard.contains(&dir) {
+      cancel.check("extraction")?;
+      guard = finished.wait_timeout(guard, BUILD_WAIT_POLL).unwrap().0;
+    }
+    guard.insert(dir.clone());
+    Ok(BuildClaim { dir })
+  }
+
+  // Fresh directory to build a mod into before `commit`
+  pub fn staging_dir(&self, key: &str) -> Result<PathBuf, InjectionError> {
+    let dir = self
+      .root
+      .join(format!("{}.tmp-{}", key, uuid::Uuid::new_v4().simple()));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+  }
+
+  // Move a finished staging directory into place. If another injection committed the same
+  // key first, its copy wins and ours is discarded.
+  pub fn commit(&self, key: &str, staging: &Path) -> Result<PathBuf, InjectionError> {
+    let dir = self.root.join(key);
+    if dir.exists() && self.lookup(key).is_none() {
+      // Leftover from an interrupted build
+      let _ = fs::remove_dir_all(&dir);
+    }
+    if let Err(e) = fs::rename(staging, &dir) {
+      let _ = fs::remove_dir_all(staging);
+      if self.lookup(key).is_none() {
+        return Err(InjectionError::IoError(e));
+      }
+    }
+    self.touch(key);
+    self.prune(self.max_entries);
+    Ok(dir)
+  }
+
+  pub fn discard(&self, staging: &Path) {
+    let _ = fs::remove_dir_all(staging);
+  }
+
+  fn read_index(&self) -> HashMap<String, u64> {
+    fs::read_to_string(self.root.join(INDEX_FILE))
+      .ok()
+      .and_then(|content| serde_json::from_str(&content).ok())
+      .unwrap_or_default()
+  }
+
+  fn write_index(&self, index: &HashMap<String, u64>) {
+    if let Ok(data) = serde_json::to_string(index) {
+      let _ = fs::create_dir_all(&self.root);
+      let _ = fs::write(self.root.join(INDEX_FILE), data);
+    }
+  }
+
+  fn touch(&self, key: &str) {
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let now = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_millis() as u64)
+      .unwrap_or(0);
+    let mut index = self.read_index();
+    index.insert(key.to_string(), now);
+    self.write_index(&index);
+  }
+
+  // Remove least recently used entries so at most `max_entries` remain
+  fn prune(&self, max_entries: usize) {
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let mut index = self.read_index();
+    // Entries that exist on disk but were never indexed count as oldest
+    if let Ok(read_dir) = fs::read_dir(&self.root) {
+      for entry in read_dir.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_dir() && !name.contains(".tmp-") {
+          index.entry(name).or_insert(0);
+        }
+      }
+    }
+    if index.len() <= max_entries {
+      return;
+    }
+
+    let mut by_age: Vec<(String, u64)> = index.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    by_age.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    let excess = by_age.len() - max_entries;
+    for (key, _) in by_age.into_iter().take(excess) {
+      let _ = fs::remove_dir_all(self.root.join(&key));
+      index.remove(&key);
+    }
+    self.write_index(&index);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("osskins-{}-{}", name, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn build(cache: &ModCache, key: &str) -> PathBuf {
+    let staging = cache.staging_dir(key).unwrap();
+    fs::create_dir_all(staging.join("META")).unwrap();
+    fs::write(staging.join("META").join("info.json"), "{}").unwrap();
+    cache.commit(key, &staging).unwrap()
+  }
+
+  #[test]
+  fn archive_hash_follows_content() {
+    let dir = temp_dir("mod-cache-hash");
+    let archive = dir.join("skin.zip");
+    fs::write(&archive, b"abc").unwrap();
+    assert_eq!(archive_hash(&archive).unwrap(), "900150983cd24fb0d6963f7d28e17f72");
+
+    fs::write(&archive, b"abcd").unwrap();
+    assert_eq!(archive_hash(&archive).unwrap(), "e2fc714c4727ee9395f324cd2e7f331f");
+    assert!(cache_key("abc").ends_with(&format!("-v{}", MOD_FORMAT_VERSION)));
+
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn commit_then_lookup_and_prune_oldest() {
+    let dir = temp_dir("mod-cache");
+    let cache = ModCache::new(&dir);
+    assert!(cache.lookup("a").is_none());
+
+    let a = build(&cache, "a");
+    assert_eq!(cache.lookup("a"), Some(a));
+
+    // Incomplete staging never shows up as a hit
+    let staging = cache.staging_dir("b").unwrap();
+    assert!(cache.lookup("b").is_none());
+    cache.discard(&staging);
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    build(&cache, "b");
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    build(&cache, "c");
+    cache.prune(2);
+    assert!(cache.lookup("a").is_none());
+    assert!(cache.lookup("b").is_some());
+    assert!(cache.lookup("c").is_some());
+
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn a_second_build_of_the_same_key_waits_for_the_first() {
+    let dir = temp_dir("mod-cache-claim");
+    let cache = ModCache::new(&dir);
+    let claim = cache.claim("a", &CancelToken::new()).unwrap();
+
+    let waiter = {
+      let dir = dir.clone();
+      std::thread::spawn(move || {
+        let cache = ModCache::new(&dir);
+        let _claim = cache.claim("a", &CancelToken::new()).unwrap();
+        cache.lookup("a")
+      })
+    };
+    // Other keys are not held up
+    drop(cache.claim("b", &CancelToken::new()).unwrap());
+    let cancelled = CancelToken::new();
+    cancelled.cancel();
+    assert!(cache.claim("a", &cancelled).is_err());
+
+    std::thread::sleep(Duration::from_millis(100));
+    let a = build(&cache, "a");
+    drop(claim);
+    assert_eq!(waiter.join().unwrap(), Some(a));
+
+    let _ = fs::remove_dir_all(dir);
+  }
+}
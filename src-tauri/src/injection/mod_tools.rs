@@ -1,30 +1,44 @@
-use crate::injection::error::{InjectionError, ModState};
+use crate::commands::error::{CommandError, ErrorCode};
+use crate::commands::lcu_watcher::types::current_time_ms;
+use crate::commands::skin_injection::{
+  get_injection_status, record_injection_state_with_message, InjectionStatusValue,
+};
+use crate::injection::backend::OverlayProcess;
+use crate::injection::error::{InjectionError, ModState, PatcherMessage};
+use crate::injection::history::{InjectionOutcome, OutcomeSink};
+use crate::injection::mod_cache::{ModCache, OVERLAY_MARKER};
+use crate::injection::overlay::{
+  analyze_conflicts, build_overlay, game_version_stamp, overlay_signature, EntryConflict,
+};
+use crate::injection::patcher::{PatcherEvent, PatcherTracker, StatusTransition};
 use crate::injection::skin_file::copy_default_overlay;
+use crate::injection::timing::TimingStage;
+use crate::injection::watchdog::{self, OverlayLaunch, RelaunchOutcome};
+use std::collections::HashMap;
 use std::fs;
-use std::io::{self, BufRead, BufReader};
-use std::process::Stdio;
-use tauri::Emitter;
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
+use std::io::{BufRead, BufReader};
+use tauri::{AppHandle, Emitter};
 use std::path::Path;
+use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
-
 // Mod-tools operations and overlay management
 
 impl crate::injection::core::SkinInjector {
-  // Copy a processed mod directory to the game's mods directory
-  pub(crate) fn copy_mod_to_game(&mut self, mod_dir: &Path) -> Result<(), InjectionError> {
+  // Copy a processed mod directory to the game's mods directory as Game/mods/<mod_name>
+  pub(crate) fn copy_mod_to_game(
+    &mut self,
+    mod_dir: &Path,
+    mods_dir: &Path,
+    mod_name: &str,
+  ) -> Result<(), InjectionError> {
     self.log(&format!(
       "Copying mod to game directory: {}",
       mod_dir.display()
     ));
 
-    // Use the mod directory name as the subfolder
-    let mod_name = mod_dir.file_name().unwrap();
-    let game_mod_dir = self.game_path.join("mods").join(mod_name);
+    // `mods_dir` is the staging directory that later replaces Game/mods
+    let game_mod_dir = mods_dir.join(mod_name);
 
     // Remove any existing mod with the same name
     if game_mod_dir.exists() {
@@ -47,54 +61,25 @@ impl crate::injection::core::SkinInjector {
         if let Some(parent) = target_path.parent() {
           fs::create_dir_all(parent)?;
         }
-        fs::copy(path, &target_path)?;
+        // Cached mods are never modified in place, so a hard link is as good as a copy.
+        // Fall back to copying when the cache and the game are on different volumes.
+        if fs::hard_link(path, &target_path).is_err() {
+          fs::copy(path, &target_path)?;
+        }
       }
     }
     Ok(())
   }
 
-  // Run the overlay process using mod-tools.exe
-  pub(crate) fn run_overlay(&mut self) -> Result<(), InjectionError> {
-    // If a previous overlay is still alive, stop it first so we never end up with
-    // two runoverlay instances stacking windows.
-    crate::injection::core::stop_global_overlay_process("run_overlay_start");
-
-    // Check if mod-tools.exe exists
-    let mod_tools_path = match &self.mod_tools_path {
-            Some(path) => {
-                if !path.exists() {
-                    return Err(InjectionError::ProcessError(format!(
-                        "mod-tools.exe was found during initialization but is no longer at path: {}. Please reinstall the application or obtain mod-tools.exe from CSLOL Manager.",
-                        path.display()
-                    )));
-                }
-                path.clone()
-            },
-            None => return Err(InjectionError::ProcessError(
-                "mod-tools.exe not found. Please install CSLOL Manager or copy mod-tools.exe to the application directory.".into()
-            )),
-        };
-
-    self.log(&format!(
-      "Using mod-tools.exe from: {}",
-      mod_tools_path.display()
-    ));
-
-    // First, ensure no mod-tools processes are running before we start
-    let _ = self.cleanup_mod_tools_processes();
-
-    // First create the overlay
-    let game_mods_dir = self.game_path.join("mods");
-    let overlay_dir = self.app_dir.join("overlay");
-
-    // Make sure overlay directory exists or is recreated
+  // Remove and recreate an overlay directory, retrying to ride out Windows file locks
+  fn recreate_overlay_dir(&mut self, overlay_dir: &Path) -> Result<(), InjectionError> {
     if overlay_dir.exists() {
       // Try to remove the overlay dir multiple times with delays
       // This helps with Windows file locks that might be causing access violations
       let mut attempts = 0;
       let max_attempts = 3;
       while attempts < max_attempts {
-        match fs::remove_dir_all(&overlay_dir) {
+        match fs::remove_dir_all(overlay_dir) {
           Ok(_) => break,
           Err(e) => {
             self.log(&format!(
@@ -111,26 +96,109 @@ impl crate::injection::core::SkinInjector {
 
       // If still exists, return error
       if overlay_dir.exists() && attempts >= max_attempts {
-        return Err(InjectionError::ProcessError(
-          "Cannot remove existing overlay directory. It may be locked by another process.".into(),
-        ));
+        return Err(InjectionError::OverlayDirLocked(format!(
+          "Cannot remove existing overlay directory {}. It may be locked by another process.",
+          overlay_dir.display()
+        )));
       }
     }
-    fs::create_dir_all(&overlay_dir)?;
+    fs::create_dir_all(overlay_dir)?;
+    Ok(())
+  }
 
-    // Get list of mod names (just the directory names, no paths)
-    let mut mod_names = Vec::new();
-    for entry in fs::read_dir(&game_mods_dir)? {
-      let entry = entry?;
-      let path = entry.path();
-      if path.is_dir() && path.join("META").join("info.json").exists() {
-        if let Some(name) = path.file_name() {
-          if let Some(name_str) = name.to_str() {
-            mod_names.push(name_str.to_string());
-          }
-        }
-      }
+  // Build the overlay for `mod_names` (directories under Game/mods) into `overlay_dir`
+  fn create_overlay(
+    &mut self,
+    mod_names: &[String],
+    winners: &HashMap<u64, String>,
+    overlay_dir: &Path,
+  ) -> Result<(), InjectionError> {
+    self.log("Creating mod overlay...");
+
+    let summary = build_overlay(
+      &self.game_path,
+      &self.game_path.join("mods"),
+      mod_names,
+      winners,
+      overlay_dir,
+      &self.cancel,
+    )
+    .map_err(|e| {
+      self.log(&format!("Overlay creation failed: {}", e));
+      e
+    })?;
+
+    for wad in &summary.unmatched_wads {
+      self.log(&format!(
+        "No game WAD named like {}, matched entries by hash instead",
+        wad
+      ));
+    }
+    if summary.skipped_entries > 0 {
+      self.log(&format!(
+        "Skipped {} mod entries that could not be placed in the overlay",
+        summary.skipped_entries
+      ));
+    }
+    self.log(&format!(
+      "Overlay creation succeeded: {} WADs, {} mod entries",
+      summary.wads_written, summary.mod_entries
+    ));
+    Ok(())
+  }
+
+  // If a previous overlay is still alive, stop it so we never end up with two runoverlay
+  // instances stacking windows, and make sure no other mod-tools processes linger
+  fn stop_previous_overlay(&mut self) {
+    crate::injection::core::stop_global_overlay_process("run_overlay_start");
+    let _ = self.cleanup_mod_tools_processes();
+  }
+
+  pub(crate) fn conflict_winners(&self) -> HashMap<u64, String> {
+    self
+      .app_handle
+      .as_ref()
+      .map(crate::commands::config::get_conflict_winners_from_config)
+      .unwrap_or_default()
+  }
+
+  // Same loadout on the same game build -> same overlay. None if a mod has no cache key.
+  pub(crate) fn loadout_signature(
+    &self,
+    mod_names: &[String],
+    winners: &HashMap<u64, String>,
+  ) -> Option<String> {
+    let mod_keys: Option<Vec<String>> = mod_names
+      .iter()
+      .map(|name| self.mod_keys.get(name).cloned())
+      .collect();
+    match mod_keys {
+      Some(keys) if !mod_names.is_empty() => Some(overlay_signature(
+        &keys,
+        &game_version_stamp(&self.game_path),
+        winners,
+      )),
+      _ => None,
     }
+  }
+
+  // Run the overlay process using mod-tools.exe. `mod_names` are directories under
+  // Game/mods, already in layering order (first is at the bottom).
+  pub(crate) fn run_overlay(&mut self, mod_names: &[String]) -> Result<(), InjectionError> {
+    // Check if mod-tools.exe exists
+    let backend = match &self.backend {
+      Some(backend) => backend.clone(),
+      None => {
+        return Err(InjectionError::ModToolsMissing(
+          "mod-tools.exe not found. Please install CSLOL Manager or copy mod-tools.exe to the application directory.".into(),
+        ))
+      }
+    };
+    backend.check_available()?;
+
+    self.log(&format!("Using {}", backend.describe()));
+
+    let game_mods_dir = self.game_path.join("mods");
 
     // Check if we have any valid mods
     if mod_names.is_empty() {
@@ -142,164 +210,117 @@ impl crate::injection::core::SkinInjector {
       ));
     }
 
-    // First try to use a pre-built default overlay from resources
-    // This is especially helpful for the first injection which is often slow
-    let used_prebuilt_empty = if let Some(app_handle) = &self.app_handle {
-      if mod_names.is_empty() {
-        // For empty mod list, try the most optimized path - use the pre-built empty overlay
-        match copy_default_overlay(app_handle, &overlay_dir) {
-          Ok(true) => {
-            self.log("Using pre-built empty overlay template for faster injection");
-            true
-          }
-          _ => false,
-        }
-      } else {
-        false
-      }
-    } else {
-      false
-    };
+    let winners = self.conflict_winners();
+    let signature = self.loadout_signature(mod_names, &winners);
 
-    if !used_prebuilt_empty {
-      // Join mod names with / as CSLOL expects
-      let mods_arg = mod_names.join("/");
-
-      self.log("Creating mod overlay...");
-
-      // Try the mkoverlay command with retries for access violation errors (0xc0000005)
-      let max_retries = 5;
-      let mut retry_count = 0;
-      let mut last_error = None;
-
-      while retry_count < max_retries {
-        // Small delay between retries to let resources free up
-        if retry_count > 0 {
-          self.log(&format!(
-            "Retrying overlay creation (attempt {}/{})",
-            retry_count + 1,
-            max_retries
-          ));
-          std::thread::sleep(std::time::Duration::from_millis(1000));
-
-          // Make sure any lingering processes are killed
-          let _ = self.cleanup_mod_tools_processes();
-
-          // For additional retries, recreate the overlay directory to ensure it's clean
-          if overlay_dir.exists() {
-            match fs::remove_dir_all(&overlay_dir) {
-              Ok(_) => fs::create_dir_all(&overlay_dir)?,
-              Err(e) => {
-                self.log(&format!("Could not clean overlay directory: {}", e));
-                // Try to continue anyway
-              }
-            }
-          } else {
-            fs::create_dir_all(&overlay_dir)?;
-          }
-        }
+    // Same-loadout reuse happens before staging, see reuse_running_overlay. A persistent
+    // overlay keeps running while the new one is built into the overlay cache, which it does
+    // not read from. Without a signature the new one is built
+    // into app_dir/overlay, so the old one has to go first.
+    let hot_rebuild = self.persistent_overlay && signature.is_some();
+    if !hot_rebuild {
+      self.stop_previous_overlay();
+    }
 
-        // Add more explicit memory cleanup hint
-        #[cfg(target_os = "windows")]
-        {
-          use std::process::Command;
-          // Run a quick garbage collection via PowerShell to help free memory
-          if retry_count > 0 {
-            let mut gc_cmd = Command::new("powershell");
-            gc_cmd.args(["-Command", "[System.GC]::Collect()"]);
-            gc_cmd.creation_flags(CREATE_NO_WINDOW);
-            let _ = gc_cmd.output(); // Ignore output
-          }
-        }
+    self.cancel.check("mkoverlay")?;
+    self.check_deadline(TimingStage::Mkoverlay)?;
+    let mkoverlay_started = Instant::now();
+    let overlay_cache = ModCache::overlays(&self.app_dir);
+    let cached = signature
+      .as_ref()
+      .and_then(|sig| overlay_cache.lookup(sig));
 
-        let mut command = std::process::Command::new(&mod_tools_path);
-        command.args([
-          "mkoverlay",
-          game_mods_dir.to_str().unwrap(),
-          overlay_dir.to_str().unwrap(),
-          &format!("--game:{}", self.game_path.to_str().unwrap()),
-          &format!("--mods:{}", mods_arg),
-          "--noTFT",
-          "--ignoreConflict",
-        ]);
-
-        #[cfg(target_os = "windows")]
-        command.creation_flags(CREATE_NO_WINDOW);
-
-        // Hide command output for cleaner logs, just show we're working
-        if retry_count == 0 {
-          self.log("Running mkoverlay command...");
+    let (overlay_dir, conflicts) = if let Some(entry) = cached {
+      self.log(&format!(
+        "Reusing cached overlay {}",
+        signature.as_deref().unwrap_or_default()
+      ));
+      let conflicts: Vec<EntryConflict> = fs::read_to_string(entry.join(OVERLAY_MARKER))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+      (entry.join("overlay"), conflicts)
+    } else {
+      // Report entries that several mods overwrite before layering them
+      let conflicts = if mod_names.is_empty() {
+        Vec::new()
+      } else {
+        match analyze_conflicts(
+          &self.game_path,
+          &game_mods_dir,
+          mod_names,
+          &self.mod_sources,
+          &winners,
+        ) {
+          Ok(conflicts) => conflicts,
+          Err(e) => {
+            self.log(&format!("WARNING: Conflict analysis failed: {}", e));
+            Vec::new()
+          }
         }
-
-        match command.output() {
-          Ok(output) => {
-            if output.status.success() {
-              // Success - break out of retry loop
-              self.log("Overlay creation succeeded!");
-              break;
-            } else {
-              // Command ran but had error status
-              let stderr_output = String::from_utf8_lossy(&output.stderr).into_owned();
-              let stdout_output = String::from_utf8_lossy(&output.stdout).into_owned();
-              let error_message = if stderr_output.is_empty() {
-                stdout_output
-              } else {
-                stderr_output
-              };
-
-              // Check if it's an access violation error
-              if output.status.to_string().contains("0xc0000005") {
-                self.log(&format!(
-                  "Access violation error in attempt {}/{}. Retrying...",
-                  retry_count + 1,
-                  max_retries
-                ));
-                last_error = Some(InjectionError::ProcessError(format!(
-                  "mkoverlay command failed: {}. Exit code: {}",
-                  error_message, output.status
-                )));
-                retry_count += 1;
-                continue;
-              } else {
-                // Other error, only show error log if this is the final attempt
-                if retry_count + 1 >= max_retries {
-                  self.log(&format!("Overlay creation failed: {}", error_message));
-                }
-                retry_count += 1;
-
-                last_error = Some(InjectionError::ProcessError(format!(
-                  "mkoverlay command failed: {}. Exit code: {}",
-                  error_message, output.status
-                )));
-
-                // Try again if we haven't exhausted retries
-                if retry_count < max_retries {
-                  continue;
-                }
-
-                return Err(last_error.unwrap());
-              }
+      };
+
+      let overlay_dir = match &signature {
+        Some(sig) => {
+          let staging = overlay_cache.staging_dir(sig)?;
+          match self.create_overlay(mod_names, &winners, &staging.join("overlay")) {
+            Ok(()) => {
+              fs::write(
+                staging.join(OVERLAY_MARKER),
+                serde_json::to_string(&conflicts).unwrap_or_else(|_| "[]".to_string()),
+              )?;
+              overlay_cache.commit(sig, &staging)?.join("overlay")
+            }
+            Err(e) => {
+              overlay_cache.discard(&staging);
+              return Err(e);
             }
           }
-          Err(e) => {
-            // Command couldn't be started
-            return Err(InjectionError::ProcessError(format!(
-              "Failed to create overlay: {}. The mod-tools.exe might be missing or incompatible.",
-              e
-            )));
+        }
+        None => {
+          let overlay_dir = self.app_dir.join("overlay");
+          self.recreate_overlay_dir(&overlay_dir)?;
+
+          // For an empty mod list, try the pre-built empty overlay from resources first.
+          // This is especially helpful for the first injection which is often slow
+          let used_prebuilt_empty = match &self.app_handle {
+            Some(app_handle) if mod_names.is_empty() => {
+              matches!(copy_default_overlay(app_handle, &overlay_dir), Ok(true))
+            }
+            _ => false,
+          };
+          if used_prebuilt_empty {
+            self.log("Using pre-built empty overlay template for faster injection");
+          } else {
+            self.create_overlay(mod_names, &winners, &overlay_dir)?;
           }
+          overlay_dir
         }
-      }
+      };
+      (overlay_dir, conflicts)
+    };
+    self
+      .timing
+      .add(TimingStage::Mkoverlay, mkoverlay_started.elapsed());
 
-      // Check if we exhausted our retries
-      if retry_count >= max_retries {
-        if let Some(err) = last_error {
-          return Err(err);
-        }
-        return Err(InjectionError::ProcessError(
-          "Failed to create overlay after multiple attempts".into(),
-        ));
-      }
+    for conflict in &conflicts {
+      let owners: Vec<&str> = conflict.owners.iter().map(|o| o.mod_name.as_str()).collect();
+      self.log(&format!(
+        "Conflict in {} entry {}: {} (using {}{})",
+        conflict.wad,
+        conflict.path_hash,
+        owners.join(", "),
+        conflict.winner,
+        if conflict.chosen_by_user { ", user choice" } else { "" }
+      ));
+    }
+    if let Some(app) = &self.app_handle {
+      crate::commands::skin_injection::record_mod_conflicts(app, conflicts);
+    }
+
+    if hot_rebuild {
+      self.log("Replacing the running overlay with the rebuilt one");
+      self.stop_previous_overlay();
     }
 
     // Create config.json
@@ -313,6 +334,7 @@ impl crate::injection::core::SkinInjector {
     self.set_state(ModState::Running);
 
     // Try running the overlay, with retries
+    let spawn_started = Instant::now();
     let max_run_retries = 2;
     let mut run_retry_count = 0;
     let mut last_run_error = None;
@@ -329,140 +351,51 @@ impl crate::injection::core::SkinInjector {
         // Make sure any lingering processes are killed
         let _ = self.cleanup_mod_tools_processes();
       }
+      let ready = self.cancel.check("overlay spawn");
+      if let Err(e) = ready.and_then(|_| self.check_deadline(TimingStage::OverlaySpawn)) {
+        self.set_state(ModState::Idle);
+        return Err(e);
+      }
 
-      // Run the overlay process - EXACT format from CSLOL
-      // Capture stdout to read status messages like "Waiting for league match to start"
-      // Capture stdin so we can send newline to stop it gracefully (like cslol does)
-      let mut command = std::process::Command::new(&mod_tools_path);
-      command.args([
-        "runoverlay",
-        overlay_dir.to_str().unwrap(),
-        config_path.to_str().unwrap(),
-        &format!("--game:{}", self.game_path.to_str().unwrap()),
-        "--opts:none",
-      ]);
-      
-      // Capture stdin for graceful stop, stdout/stderr for status updates
-      command.stdin(Stdio::piped());
-      command.stdout(Stdio::piped());
-      command.stderr(Stdio::piped());
-
-      #[cfg(target_os = "windows")]
-      command.creation_flags(CREATE_NO_WINDOW);
-
-      match command.spawn() {
+      match backend.run_overlay(&overlay_dir, &config_path, &self.game_path) {
         Ok(mut child) => {
-          self.log("Overlay process started successfully");
-          
-          // Spawn a thread to read stdout and emit status updates
-          if let Some(stdout) = child.stdout.take() {
-            if let Some(app_handle) = self.app_handle.clone() {
-              std::thread::spawn(move || {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                  if let Ok(line) = line {
-                    let trimmed = line.trim();
-                    if !trimmed.is_empty() {
-                      // Log the status from runoverlay
-                      crate::commands::lcu_watcher::append_global_log(&format!("[Patcher] {}", trimmed));
-                      let _ = app_handle.emit("terminal-log", format!("[Patcher] {}", trimmed));
-                      
-                      // Parse status messages from mod-tools runoverlay
-                      // Format: "Status: <message>" or "[INF] <message>" or "[DLL] <message>"
-                      if let Some(status_msg) = trimmed.strip_prefix("Status: ") {
-                        // Emit detailed patcher status
-                        let _ = app_handle.emit("patcher-status", status_msg);
-                        
-                        // Update injection state based on patcher status
-                        use crate::commands::skin_injection::{record_injection_state_with_message, InjectionStatusValue};
-                        if status_msg.contains("Waiting for league") {
-                          record_injection_state_with_message(&app_handle, InjectionStatusValue::Running, Some(status_msg.to_string()), None);
-                        } else if status_msg.contains("Patching") {
-                          record_injection_state_with_message(&app_handle, InjectionStatusValue::Patching, Some(status_msg.to_string()), None);
-                        } else if status_msg.contains("Found League") {
-                          record_injection_state_with_message(&app_handle, InjectionStatusValue::Running, Some("Found League - preparing to patch".to_string()), None);
-                        } else if status_msg.contains("exited") {
-                          record_injection_state_with_message(&app_handle, InjectionStatusValue::Success, Some("Game exited - mods were applied".to_string()), None);
-                        }
-                      }
-                    }
-                  }
-                }
-                // Process ended - this means game exited or overlay stopped
-                println!("[Patcher] Overlay process stdout closed");
-
-                // Update UI state when process exits.
-                // If we already reported Success, returning to Idle is expected.
-                // Otherwise, surface an error because the overlay stopped early.
-                use crate::commands::skin_injection::{
-                  get_injection_status, record_injection_state, record_injection_state_with_message,
-                  InjectionStatusValue,
-                };
-
-                match get_injection_status() {
-                  InjectionStatusValue::Success | InjectionStatusValue::Idle => {
-                    record_injection_state(&app_handle, InjectionStatusValue::Idle, None);
-                  }
-                  _ => {
-                    record_injection_state_with_message(
-                      &app_handle,
-                      InjectionStatusValue::Error,
-                      Some("Overlay process stopped unexpectedly".to_string()),
-                      Some(
-                        "Overlay exited before patching the game (mod-tools stopped/crashed).".to_string(),
-                      ),
-                    );
-                  }
-                }
-              });
-            }
+          // Cancelled while spawning: don't leave an overlay running for an aborted injection
+          if let Err(e) = self.cancel.check("overlay spawn") {
+            child.kill();
+            child.wait();
+            self.set_state(ModState::Idle);
+            return Err(e);
           }
-          
-          // Also spawn thread for stderr
-          if let Some(stderr) = child.stderr.take() {
-            if let Some(app_handle) = self.app_handle.clone() {
-              std::thread::spawn(move || {
-                let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                  if let Ok(line) = line {
-                    let trimmed = line.trim();
-                    if !trimmed.is_empty() {
-                      crate::commands::lcu_watcher::append_global_log(&format!("[Patcher ERR] {}", trimmed));
-                      let _ = app_handle.emit("terminal-log", format!("[Patcher ERR] {}", trimmed));
-                    }
-                  }
-                }
-              });
-            }
+          self.log("Overlay process started successfully");
+          self
+            .timing
+            .add(TimingStage::OverlaySpawn, spawn_started.elapsed());
+
+          let generation = watchdog::arm(OverlayLaunch {
+            backend: backend.clone(),
+            overlay_dir: overlay_dir.clone(),
+            config_path: config_path.clone(),
+            game_path: self.game_path.clone(),
+          });
+          if let Some(app_handle) = &self.app_handle {
+            let outcome = self.outcome_sink.clone();
+            attach_patcher_output(app_handle, child.as_mut(), generation, outcome);
           }
-          
+
           // Store the child globally so it stays alive even after this injector instance is dropped.
           crate::injection::core::set_global_overlay_process(child);
+          crate::injection::core::set_running_overlay_signature(signature.clone());
           return Ok(());
         }
         Err(e) => {
           run_retry_count += 1;
 
+          self.log(&format!("Failed to start overlay process: {}", e));
           // Store error for potential later use
-          last_run_error = Some(match e.kind() {
-                        io::ErrorKind::NotFound => InjectionError::ProcessError(format!(
-                            "mod-tools.exe not found or is inaccessible at path: {}. Please install CSLOL Manager or copy the correct mod-tools.exe to the application directory.",
-                            mod_tools_path.display()
-                        )),
-                        io::ErrorKind::PermissionDenied => InjectionError::ProcessError(format!(
-                            "Permission denied when trying to run mod-tools.exe. Try running the application as administrator."
-                        )),
-                        _ => InjectionError::ProcessError(format!(
-                            "Error running mod-tools.exe: {}. Please ensure it's correctly installed and compatible with your system.",
-                            e
-                        ))
-                    });
+          last_run_error = Some(e);
 
           if run_retry_count < max_run_retries {
-            self.log(&format!(
-              "Failed to start overlay process: {}. Retrying...",
-              e
-            ));
+            self.log("Retrying...");
             continue;
           }
         }
@@ -485,3 +418,198 @@ impl crate::injection::core::SkinInjector {
     }
   }
 }
+
+// Read the overlay's stdout and stderr on background threads, parse them into patcher events
+// and drive the injection status from them. When stdout closes before the game was patched,
+// the watchdog gets a chance to restart the overlay. Whether the game got patched goes to
+// the injection's history entry.
+fn attach_patcher_output(
+  app_handle: &AppHandle,
+  child: &mut dyn OverlayProcess,
+  generation: u64,
+  outcome: Option<OutcomeSink>,
+) {
+  if let Some(stdout) = child.take_stdout() {
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+      let mut reported_patched = false;
+      let mut tracker = PatcherTracker::new(current_time_ms());
+      let reader = BufReader::new(stdout);
+      for line in reader.lines().map_while(Result::ok) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+          continue;
+        }
+        let log_line = format!("[Patcher] {}", trimmed);
+        crate::commands::lcu_watcher::append_global_log(&log_line);
+        let _ = app_handle.emit("terminal-log", log_line);
+
+        let event = PatcherEvent::parse(trimmed);
+        if let PatcherEvent::Status { message, .. } = &event {
+          // Kept for listeners of the raw status text
+          let _ = app_handle.emit("patcher-status", message.as_str());
+        }
+        let waiting_for_league = matches!(
+          event,
+          PatcherEvent::Status {
+            stage: Some(PatcherMessage::WaitStart),
+            ..
+          }
+        );
+        let transition = apply_patcher_event(&app_handle, &mut tracker, event);
+        if let (true, Some(sink)) = (waiting_for_league, &outcome) {
+          let stage = tracker
+            .stages()
+            .iter()
+            .find(|s| s.stage == PatcherMessage::WaitStart);
+          if let Some(stage) = stage {
+            crate::commands::skin_injection::record_late_injection_timing(
+              &app_handle,
+              sink.injection_id(),
+              TimingStage::WaitForLeague,
+              Duration::from_millis(stage.elapsed_ms),
+            );
+          }
+        }
+        if transition.is_some_and(|t| t.status == InjectionStatusValue::Success) {
+          watchdog::note_patched(generation);
+          if let Some(sink) = outcome.as_ref().filter(|_| !reported_patched) {
+            sink.record(InjectionOutcome::Patched, None, tracker.stages());
+          }
+          reported_patched = true;
+        }
+      }
+      // Process ended - this means game exited or overlay stopped
+      println!("[Patcher] Overlay process stdout closed");
+
+      // Only this run's own output says whether it got the game patched, see
+      // PatcherTracker::patched. Stopping the overlay on purpose disarms the watchdog.
+      if tracker.patched() || !restart_after_unexpected_exit(&app_handle, generation, &outcome) {
+        let transition = apply_patcher_event(&app_handle, &mut tracker, PatcherEvent::Closed);
+        if let (Some(sink), Some(transition)) = (&outcome, transition) {
+          if transition.status == InjectionStatusValue::Error {
+            sink.record(
+              InjectionOutcome::OverlayStopped,
+              transition.error,
+              tracker.stages(),
+            );
+          }
+        }
+      }
+    });
+  }
+
+  // Also spawn thread for stderr
+  if let Some(stderr) = child.take_stderr() {
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+      let mut tracker = PatcherTracker::new(current_time_ms());
+      let reader = BufReader::new(stderr);
+      for line in reader.lines().map_while(Result::ok) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+          continue;
+        }
+        let log_line = format!("[Patcher ERR] {}", trimmed);
+        crate::commands::lcu_watcher::append_global_log(&log_line);
+        let _ = app_handle.emit("terminal-log", log_line);
+        let event = PatcherEvent::parse_stderr(trimmed);
+        apply_patcher_event(&app_handle, &mut tracker, event);
+      }
+    });
+  }
+}
+
+// Let the watchdog relaunch an overlay that stopped early. Returns false when it isn't
+// supervised anymore or the phase no longer needs it, leaving the exit to be reported as is.
+fn restart_after_unexpected_exit(
+  app_handle: &AppHandle,
+  generation: u64,
+  outcome: &Option<OutcomeSink>,
+) -> bool {
+  let log = |message: &str| {
+    println!("{}", message);
+    crate::commands::lcu_watcher::append_global_log(message);
+    let _ = app_handle.emit("terminal-log", message);
+  };
+
+  let outcome = watchdog::relaunch_supervised(
+    generation,
+    crate::commands::lcu_watcher::phase_needs_overlay,
+    |delay| {
+      log(&format!(
+        "[Watchdog] Overlay stopped unexpectedly, restarting in {} ms",
+        delay.as_millis()
+      ));
+      std::thread::sleep(delay);
+    },
+  );
+
+  match outcome {
+    Some(RelaunchOutcome::Restarted {
+      attempt,
+      mut process,
+    }) => {
+      log(&format!(
+        "[Watchdog] Overlay restarted (attempt {})",
+        attempt
+      ));
+      record_injection_state_with_message(
+        app_handle,
+        InjectionStatusValue::Running,
+        Some(format!(
+          "Overlay restarted after an unexpected exit (attempt {})",
+          attempt
+        )),
+        None,
+      );
+      attach_patcher_output(app_handle, process.as_mut(), generation, outcome.clone());
+      crate::injection::core::set_global_overlay_process(process);
+      true
+    }
+    Some(RelaunchOutcome::GaveUp { attempts, error }) => {
+      log(&format!(
+        "[Watchdog] Giving up on the overlay after {} restart attempts: {}",
+        attempts, error
+      ));
+      let error = format!(
+        "The overlay stopped unexpectedly and could not be restarted after {} attempts: {}",
+        attempts, error
+      );
+      if let Some(sink) = outcome {
+        sink.record(InjectionOutcome::OverlayStopped, Some(error.clone()), &[]);
+      }
+      record_injection_state_with_message(
+        app_handle,
+        InjectionStatusValue::Error,
+        Some("Overlay keeps stopping".to_string()),
+        Some(CommandError::new(ErrorCode::ProcessFailed, error)),
+      );
+      true
+    }
+    Some(RelaunchOutcome::NotNeeded) | None => false,
+  }
+}
+
+// Stamp a patcher event, apply its status transition and emit it as `patcher-event`.
+// Returns the transition if the event caused one.
+fn apply_patcher_event(
+  app_handle: &AppHandle,
+  tracker: &mut PatcherTracker,
+  event: PatcherEvent,
+) -> Option<StatusTransition> {
+  let record = tracker.record(event, get_injection_status(), current_time_ms());
+  if let Some(transition) = &record.transition {
+    record_injection_state_with_message(
+      app_handle,
+      transition.status,
+      transition.message.clone(),
+      transition
+        .error
+        .clone()
+        .map(|e| CommandError::new(ErrorCode::ProcessFailed, e)),
+    );
+  }
+  let _ = app_handle.emit("patcher-event", &record);
+  record.transition
+}
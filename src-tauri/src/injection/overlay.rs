@@ -0,0 +1,854 @@
+use crate::injection::cancel::CancelToken;
+use crate::injection::error::{InjectionError, ModSource};
+use crate::injection::wad::{
+  hash_wad_path, read_wad_toc, write_wad, EntrySource, WadEntry, WadVersion, WadWriteEntry,
+  WAD_KIND_RAW, WAD_KIND_ZSTD_MULTI,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// Native overlay builder - replaces `mod-tools mkoverlay`
+//
+// For every game WAD touched by at least one mod, the overlay gets a full copy of that WAD
+// at the same relative path (e.g. `DATA/FINAL/Champions/Ahri.wad.client`) in which the mod
+// entries replace the base entries. Mods are layered in the order given: later mods win.
+
+#[derive(Debug, Default, Clone)]
+pub struct OverlaySummary {
+  pub wads_written: usize,
+  pub mod_entries: usize,
+  // Entries we could not place because no game WAD contains their hash
+  pub skipped_entries: usize,
+  // Mod WAD names that matched no game WAD by name
+  pub unmatched_wads: Vec<String>,
+}
+
+// Bump whenever build_overlay output changes so cached overlays are rebuilt
+pub const OVERLAY_FORMAT_VERSION: u32 = 1;
+
+// Builder options that change the output; part of the overlay cache signature
+const OVERLAY_FLAGS: &str = "noTFT";
+
+// Identifies the installed game build: the patch version from content-metadata.json plus the
+// executable's size and mtime, so any patch invalidates cached overlays
+pub fn game_version_stamp(game_path: &Path) -> String {
+  let version = fs::read_to_string(game_path.join("content-metadata.json"))
+    .ok()
+    .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    .and_then(|value| value.get("version").and_then(|v| v.as_str()).map(String::from))
+    .unwrap_or_default();
+  let exe = fs::metadata(game_path.join("League of Legends.exe")).ok();
+  let size = exe.as_ref().map(|m| m.len()).unwrap_or(0);
+  let modified = exe
+    .and_then(|m| m.modified().ok())
+    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+    .map(|d| d.as_secs())
+    .unwrap_or(0);
+  format!("{}|{}|{}", version, size, modified)
+}
+
+// Cache signature of an overlay: ordered mod content keys, game build, builder flags and
+// the user's conflict choices
+pub fn overlay_signature(
+  mod_keys: &[String],
+  game_stamp: &str,
+  winners: &HashMap<u64, String>,
+) -> String {
+  let mut context = md5::Context::new();
+  context.consume(format!("format:{}\n", OVERLAY_FORMAT_VERSION));
+  context.consume(format!("game:{}\n", game_stamp));
+  context.consume(format!("flags:{}\n", OVERLAY_FLAGS));
+  for key in mod_keys {
+    context.consume(format!("mod:{}\n", key));
+  }
+  let mut choices: Vec<(&u64, &String)> = winners.iter().collect();
+  choices.sort();
+  for (hash, mod_name) in choices {
+    context.consume(format!("winner:{}={}\n", format_path_hash(*hash), mod_name));
+  }
+  format!("{:x}", context.compute())
+}
+
+// Default layering by mod category, lowest first: maps at the bottom, the HUD on top
+pub const DEFAULT_LOAD_ORDER: [&str; 6] = ["map", "skin", "custom_skin", "misc", "font", "hud"];
+
+// Load order category of a mod; misc items use their item type ("map", "hud", ...)
+pub fn load_order_category(source: &ModSource) -> String {
+  match source {
+    // Custom skins are injected with skin_id 0
+    ModSource::Skin { skin_id: 0, .. } => "custom_skin".to_string(),
+    ModSource::Skin { .. } => "skin".to_string(),
+    ModSource::Misc { item_type, .. } => item_type.to_lowercase(),
+  }
+}
+
+// Sort mod directory names into layering order (first is layered at the bottom). Mods whose
+// category is missing from `order` go below everything listed; ties are broken by name so
+// the result never depends on filesystem iteration order.
+pub fn order_mod_names(
+  mod_names: &mut [String],
+  sources: &HashMap<String, ModSource>,
+  order: &[String],
+) {
+  let rank = |name: &String| -> usize {
+    sources
+      .get(name)
+      .map(load_order_category)
+      .and_then(|category| order.iter().position(|c| c.eq_ignore_ascii_case(&category)))
+      .map(|pos| pos + 1)
+      .unwrap_or(0)
+  };
+  mod_names.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+}
+
+// Entry contributed by a mod, already assigned to a game WAD
+#[derive(Debug, Clone)]
+pub(crate) struct ModEntry {
+  pub mod_name: String,
+  pub write: WadWriteEntry,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictOwner {
+  pub mod_name: String,
+  pub source: Option<ModSource>,
+}
+
+// A WAD entry written by more than one mod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryConflict {
+  // WAD path relative to the game directory, e.g. "DATA/FINAL/Champions/Ahri.wad.client"
+  pub wad: String,
+  // 16 hex digits; this is the key used when the user picks a winner
+  pub path_hash: String,
+  // Contributing mods in layering order
+  pub owners: Vec<ConflictOwner>,
+  pub winner: String,
+  // True when the winner comes from a saved user choice rather than layering order
+  pub chosen_by_user: bool,
+}
+
+pub fn format_path_hash(path_hash: u64) -> String {
+  format!("{:016x}", path_hash)
+}
+
+pub fn parse_path_hash(value: &str) -> Option<u64> {
+  let trimmed = value.trim().trim_start_matches("0x");
+  if trimmed.is_empty() || trimmed.len() > 16 {
+    return None;
+  }
+  u64::from_str_radix(trimmed, 16).ok()
+}
+
+// Pick the entry that ends up in the overlay: the user's chosen mod if it contributed,
+// otherwise the last one layered
+fn choose_winner<'a>(
+  contributors: &[&'a ModEntry],
+  chosen: Option<&String>,
+) -> Option<(&'a ModEntry, bool)> {
+  if let Some(chosen) = chosen {
+    if let Some(entry) = contributors.iter().rev().find(|e| &e.mod_name == chosen) {
+      return Some((entry, true));
+    }
+  }
+  contributors.last().map(|entry| (*entry, false))
+}
+
+// Index of the game's WAD archives under DATA/FINAL
+pub(crate) struct GameWadIndex {
+  game_path: PathBuf,
+  // lowercase file name -> path relative to the game directory
+  by_name: HashMap<String, PathBuf>,
+  // path hash -> relative WAD path, built lazily for mods whose WAD names don't match
+  by_hash: Option<HashMap<u64, PathBuf>>,
+}
+
+impl GameWadIndex {
+  pub(crate) fn scan(game_path: &Path) -> Result<Self, InjectionError> {
+    let data_dir = game_path.join("DATA").join("FINAL");
+    let mut by_name = HashMap::new();
+
+    if data_dir.exists() {
+      for entry in WalkDir::new(&data_dir) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+          continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        if !is_wad_name(&name) || is_tft_wad(&name) {
+          continue;
+        }
+        let rel = entry
+          .path()
+          .strip_prefix(game_path)
+          .map_err(|e| InjectionError::ProcessError(format!("Path error: {}", e)))?
+          .to_path_buf();
+        by_name.entry(name).or_insert(rel);
+      }
+    }
+
+    Ok(Self {
+      game_path: game_path.to_path_buf(),
+      by_name,
+      by_hash: None,
+    })
+  }
+
+  pub(crate) fn find_by_name(&self, wad_name: &str) -> Option<&PathBuf> {
+    self.by_name.get(&normalize_wad_name(wad_name))
+  }
+
+  pub(crate) fn find_by_hash(&mut self, path_hash: u64) -> Option<&PathBuf> {
+    if self.by_hash.is_none() {
+      let mut by_hash = HashMap::new();
+      // Sort for a deterministic owner when the same hash lives in several WADs
+      let mut wads: Vec<&PathBuf> = self.by_name.values().collect();
+      wads.sort();
+      for rel in wads {
+        if let Ok(toc) = read_wad_toc(&self.game_path.join(rel)) {
+          for entry in toc.entries {
+            by_hash.entry(entry.path_hash).or_insert_with(|| rel.clone());
+          }
+        }
+      }
+      self.by_hash = Some(by_hash);
+    }
+    self.by_hash.as_ref().unwrap().get(&path_hash)
+  }
+}
+
+fn is_wad_name(name: &str) -> bool {
+  name.ends_with(".wad.client") || name.ends_with(".wad")
+}
+
+// Equivalent of mkoverlay --noTFT: never touch the TFT map archives
+fn is_tft_wad(name: &str) -> bool {
+  name.starts_with("map22")
+}
+
+// Legacy mods ship "Foo.wad" for what the game now calls "Foo.wad.client"
+fn normalize_wad_name(name: &str) -> String {
+  let lowered = name.to_lowercase();
+  if lowered.ends_with(".wad") {
+    format!("{}.client", lowered)
+  } else {
+    lowered
+  }
+}
+
+// Collect the entries of a single WAD inside a mod's WAD/ folder. Supports both packed
+// archives and unpacked directories whose files are named by game path or by hex hash.
+pub(crate) fn read_mod_wad(path: &Path) -> Result<Vec<WadWriteEntry>, InjectionError> {
+  if path.is_file() {
+    let toc = read_wad_toc(path)?;
+    return Ok(
+      toc
+        .entries
+        .into_iter()
+        .map(|entry| WadWriteEntry {
+          source: EntrySource::Archive {
+            path: path.to_path_buf(),
+            offset: entry.offset,
+          },
+          entry,
+        })
+        .collect(),
+    );
+  }
+
+  let mut entries = Vec::new();
+  for item in WalkDir::new(path).sort_by_file_name() {
+    let item = item?;
+    if !item.file_type().is_file() {
+      continue;
+    }
+    let rel = item
+      .path()
+      .strip_prefix(path)
+      .map_err(|e| InjectionError::ProcessError(format!("Path error: {}", e)))?;
+    let rel_str = rel.to_string_lossy().replace('\\', "/");
+    let path_hash = parse_hex_file_name(&rel_str).unwrap_or_else(|| hash_wad_path(&rel_str));
+    let len = item.metadata().map(|m| m.len()).unwrap_or(0);
+    if len > u32::MAX as u64 {
+      return Err(InjectionError::ProcessError(format!(
+        "File too large for WAD entry: {}",
+        item.path().display()
+      )));
+    }
+    entries.push(WadWriteEntry {
+      entry: WadEntry {
+        path_hash,
+        offset: 0,
+        compressed_size: len as u32,
+        size: len as u32,
+        kind: WAD_KIND_RAW,
+        subchunk_count: 0,
+        subchunk_index: 0,
+        checksum: 0,
+      },
+      source: EntrySource::File {
+        path: item.path().to_path_buf(),
+      },
+    });
+  }
+  Ok(entries)
+}
+
+// Files extracted with unknown names are stored as "<16 hex digits>[.ext]"
+fn parse_hex_file_name(rel: &str) -> Option<u64> {
+  if rel.contains('/') {
+    return None;
+  }
+  let stem = rel.split('.').next().unwrap_or(rel);
+  if stem.len() != 16 {
+    return None;
+  }
+  u64::from_str_radix(stem, 16).ok()
+}
+
+// Pair mod directory names under `mods_dir` with their paths
+fn mods_in_dir(mods_dir: &Path, mod_names: &[String]) -> Vec<(String, PathBuf)> {
+  mod_names
+    .iter()
+    .map(|name| (name.clone(), mods_dir.join(name)))
+    .collect()
+}
+
+// Gather every entry contributed by `mods` (name, directory; in layering order), grouped by the game
+// WAD they belong to. Returned vectors keep every contribution in order so callers can
+// inspect overlaps.
+pub(crate) fn collect_mod_entries(
+  index: &mut GameWadIndex,
+  mods: &[(String, PathBuf)],
+  summary: &mut OverlaySummary,
+) -> Result<BTreeMap<PathBuf, Vec<ModEntry>>, InjectionError> {
+  let mut by_wad: BTreeMap<PathBuf, Vec<ModEntry>> = BTreeMap::new();
+
+  for (mod_name, mod_dir) in mods {
+    let wad_dir = mod_dir.join("WAD");
+    if !wad_dir.exists() {
+      continue;
+    }
+
+    let mut wad_items: Vec<PathBuf> = fs::read_dir(&wad_dir)?
+      .filter_map(|e| e.ok().map(|e| e.path()))
+      .filter(|p| {
+        p.file_name()
+          .map(|n| is_wad_name(&n.to_string_lossy().to_lowercase()))
+          .unwrap_or(false)
+      })
+      .collect();
+    wad_items.sort();
+
+    for wad_path in wad_items {
+      let wad_name = wad_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+      let target = index.find_by_name(&wad_name).cloned();
+      if target.is_none() {
+        summary.unmatched_wads.push(format!("{}/{}", mod_name, wad_name));
+      }
+
+      for write in read_mod_wad(&wad_path)? {
+        // Subchunked payloads point into the mod's own .subchunktoc which we don't carry over,
+        // so the entry would be unreadable in the overlay. Refuse the mod instead of dropping it.
+        if write.entry.kind == WAD_KIND_ZSTD_MULTI {
+          return Err(InjectionError::ProcessError(format!(
+            "{}/{} has subchunked entry {:016x}, which the overlay builder can't place",
+            mod_name, wad_name, write.entry.path_hash
+          )));
+        }
+        let owner = match &target {
+          Some(rel) => Some(rel.clone()),
+          None => index.find_by_hash(write.entry.path_hash).cloned(),
+        };
+        match owner {
+          Some(rel) => {
+            summary.mod_entries += 1;
+            by_wad.entry(rel).or_default().push(ModEntry {
+              mod_name: mod_name.clone(),
+              write,
+            });
+          }
+          None => summary.skipped_entries += 1,
+        }
+      }
+    }
+  }
+
+  Ok(by_wad)
+}
+
+fn group_by_hash(mod_entries: &[ModEntry]) -> BTreeMap<u64, Vec<&ModEntry>> {
+  let mut by_hash: BTreeMap<u64, Vec<&ModEntry>> = BTreeMap::new();
+  for entry in mod_entries {
+    by_hash.entry(entry.write.entry.path_hash).or_default().push(entry);
+  }
+  by_hash
+}
+
+// Report every WAD entry that more than one mod writes. `winners` maps path hashes to the
+// mod the user wants on top; `sources` labels mod directories with what they were built from.
+pub fn analyze_conflicts(
+  game_path: &Path,
+  mods_dir: &Path,
+  mod_names: &[String],
+  sources: &HashMap<String, ModSource>,
+  winners: &HashMap<u64, String>,
+) -> Result<Vec<EntryConflict>, InjectionError> {
+  analyze_mod_conflicts(game_path, &mods_in_dir(mods_dir, mod_names), sources, winners)
+}
+
+// Same as analyze_conflicts for mods that don't live in a single directory (e.g. the mod cache)
+pub fn analyze_mod_conflicts(
+  game_path: &Path,
+  mods: &[(String, PathBuf)],
+  sources: &HashMap<String, ModSource>,
+  winners: &HashMap<u64, String>,
+) -> Result<Vec<EntryConflict>, InjectionError> {
+  let mut summary = OverlaySummary::default();
+  let mut index = GameWadIndex::scan(game_path)?;
+  let by_wad = collect_mod_entries(&mut index, mods, &mut summary)?;
+
+  let mut conflicts = Vec::new();
+  for (rel, mod_entries) in &by_wad {
+    for (path_hash, contributors) in group_by_hash(mod_entries) {
+      let mut owners: Vec<&String> = Vec::new();
+      for entry in &contributors {
+        if !owners.contains(&&entry.mod_name) {
+          owners.push(&entry.mod_name);
+        }
+      }
+      if owners.len() < 2 {
+        continue;
+      }
+      // Byte-identical payloads (same non-zero checksum) are not worth reporting
+      let first = &contributors[0].write.entry;
+      if first.checksum != 0
+        && contributors
+          .iter()
+          .all(|e| e.write.entry.checksum == first.checksum && e.write.entry.size == first.size)
+      {
+        continue;
+      }
+
+      let Some((winner, chosen_by_user)) =
+        choose_winner(&contributors, winners.get(&path_hash))
+      else {
+        continue;
+      };
+      conflicts.push(EntryConflict {
+        wad: rel.to_string_lossy().replace('\\', "/"),
+        path_hash: format_path_hash(path_hash),
+        owners: owners
+          .into_iter()
+          .map(|name| ConflictOwner {
+            mod_name: name.clone(),
+            source: sources.get(name).cloned(),
+          })
+          .collect(),
+        winner: winner.mod_name.clone(),
+        chosen_by_user,
+      });
+    }
+  }
+
+  Ok(conflicts)
+}
+
+// Build the overlay directory for `mod_names` (directory names under `mods_dir`).
+// Entries listed in `winners` come from the chosen mod when it provides them.
+pub fn build_overlay(
+  game_path: &Path,
+  mods_dir: &Path,
+  mod_names: &[String],
+  winners: &HashMap<u64, String>,
+  overlay_dir: &Path,
+  cancel: &CancelToken,
+) -> Result<OverlaySummary, InjectionError> {
+  let mut summary = OverlaySummary::default();
+  let mut index = GameWadIndex::scan(game_path)?;
+  let by_wad = collect_mod_entries(&mut index, &mods_in_dir(mods_dir, mod_names), &mut summary)?;
+
+  fs::create_dir_all(overlay_dir)?;
+
+  for (rel, mod_entries) in by_wad {
+    cancel.check("mkoverlay")?;
+    let base_path = game_path.join(&rel);
+    let base = read_wad_toc(&base_path)?;
+
+    let mut merged: BTreeMap<u64, WadWriteEntry> = base
+      .entries
+      .into_iter()
+      .map(|entry| {
+        (
+          entry.path_hash,
+          WadWriteEntry {
+            source: EntrySource::Archive {
+              path: base_path.clone(),
+              offset: entry.offset,
+            },
+            entry,
+          },
+        )
+      })
+      .collect();
+    for (path_hash, contributors) in group_by_hash(&mod_entries) {
+      if let Some((winner, _)) = choose_winner(&contributors, winners.get(&path_hash)) {
+        merged.insert(path_hash, winner.write.clone());
+      }
+    }
+
+    let mut entries: Vec<WadWriteEntry> = merged.into_values().collect();
+    let version = if base.version.major == 3 {
+      base.version
+    } else {
+      WadVersion::V3_3
+    };
+    write_wad(&overlay_dir.join(&rel), version, &mut entries)?;
+    summary.wads_written += 1;
+  }
+
+  Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("osskins-{}-{}", name, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn write_fixture_wad(path: &Path, files: &[(&str, &[u8])]) {
+    let data_dir = path.with_extension("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    let mut entries: Vec<WadWriteEntry> = files
+      .iter()
+      .map(|(name, bytes)| {
+        let file = data_dir.join(hash_wad_path(name).to_string());
+        fs::write(&file, bytes).unwrap();
+        WadWriteEntry {
+          entry: WadEntry {
+            path_hash: hash_wad_path(name),
+            offset: 0,
+            compressed_size: bytes.len() as u32,
+            size: bytes.len() as u32,
+            kind: WAD_KIND_RAW,
+            subchunk_count: 0,
+            subchunk_index: 0,
+            checksum: 0,
+          },
+          source: EntrySource::File { path: file },
+        }
+      })
+      .collect();
+    write_wad(path, WadVersion::V3_3, &mut entries).unwrap();
+  }
+
+  fn read_entry(path: &Path, name: &str) -> Option<Vec<u8>> {
+    use std::io::{Read, Seek, SeekFrom};
+    let toc = read_wad_toc(path).unwrap();
+    let entry = toc
+      .entries
+      .iter()
+      .find(|e| e.path_hash == hash_wad_path(name))?;
+    let mut file = fs::File::open(path).unwrap();
+    file.seek(SeekFrom::Start(entry.offset as u64)).unwrap();
+    let mut buf = vec![0u8; entry.compressed_size as usize];
+    file.read_exact(&mut buf).unwrap();
+    Some(buf)
+  }
+
+  #[test]
+  fn overlay_layers_mods_in_order() {
+    let root = temp_dir("overlay");
+    let game = root.join("Game");
+    let champions = game.join("DATA").join("FINAL").join("Champions");
+    fs::create_dir_all(&champions).unwrap();
+    write_fixture_wad(
+      &champions.join("Ahri.wad.client"),
+      &[("skin.bin", b"base-skin"), ("untouched.bin", b"base")],
+    );
+    write_fixture_wad(&champions.join("Annie.wad.client"), &[("annie.bin", b"annie")]);
+
+    let mods = root.join("mods");
+    let first_wad = mods.join("first").join("WAD");
+    fs::create_dir_all(&first_wad).unwrap();
+    write_fixture_wad(&first_wad.join("Ahri.wad.client"), &[("skin.bin", b"first")]);
+
+    // Second mod ships an unpacked WAD folder with a legacy ".wad" name
+    let second_wad = mods.join("second").join("WAD").join("Ahri.wad");
+    fs::create_dir_all(&second_wad).unwrap();
+    fs::write(second_wad.join("skin.bin"), b"second").unwrap();
+    fs::write(second_wad.join("new.bin"), b"added").unwrap();
+
+    let overlay = root.join("overlay");
+    let names = vec!["first".to_string(), "second".to_string()];
+    let summary = build_overlay(
+      &game,
+      &mods,
+      &names,
+      &HashMap::new(),
+      &overlay,
+      &CancelToken::new(),
+    )
+    .unwrap();
+
+    assert_eq!(summary.wads_written, 1);
+    assert_eq!(summary.mod_entries, 3);
+    assert_eq!(summary.skipped_entries, 0);
+
+    let out = overlay
+      .join("DATA")
+      .join("FINAL")
+      .join("Champions")
+      .join("Ahri.wad.client");
+    assert_eq!(read_entry(&out, "skin.bin").unwrap(), b"second");
+    assert_eq!(read_entry(&out, "untouched.bin").unwrap(), b"base");
+    assert_eq!(read_entry(&out, "new.bin").unwrap(), b"added");
+    assert!(!overlay
+      .join("DATA")
+      .join("FINAL")
+      .join("Champions")
+      .join("Annie.wad.client")
+      .exists());
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn unmatched_wad_falls_back_to_hash_lookup() {
+    let root = temp_dir("overlay-hash");
+    let game = root.join("Game");
+    let champions = game.join("DATA").join("FINAL").join("Champions");
+    fs::create_dir_all(&champions).unwrap();
+    write_fixture_wad(&champions.join("Ahri.wad.client"), &[("skin.bin", b"base")]);
+
+    let mods = root.join("mods");
+    let wad_dir = mods.join("renamed").join("WAD");
+    fs::create_dir_all(&wad_dir).unwrap();
+    write_fixture_wad(
+      &wad_dir.join("Custom.wad.client"),
+      &[("skin.bin", b"modded"), ("nowhere.bin", b"lost")],
+    );
+
+    let overlay = root.join("overlay");
+    let summary = build_overlay(
+      &game,
+      &mods,
+      &["renamed".to_string()],
+      &HashMap::new(),
+      &overlay,
+      &CancelToken::new(),
+    )
+    .unwrap();
+
+    assert_eq!(summary.unmatched_wads, vec!["renamed/Custom.wad.client".to_string()]);
+    assert_eq!(summary.mod_entries, 1);
+    assert_eq!(summary.skipped_entries, 1);
+    let out = overlay
+      .join("DATA")
+      .join("FINAL")
+      .join("Champions")
+      .join("Ahri.wad.client");
+    assert_eq!(read_entry(&out, "skin.bin").unwrap(), b"modded");
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn subchunked_mod_entries_fail_the_build() {
+    let root = temp_dir("overlay-subchunked");
+    let game = root.join("Game");
+    let champions = game.join("DATA").join("FINAL").join("Champions");
+    fs::create_dir_all(&champions).unwrap();
+    write_fixture_wad(&champions.join("Ahri.wad.client"), &[("skin.bin", b"base")]);
+
+    let wad_dir = root.join("mods").join("chunked").join("WAD");
+    fs::create_dir_all(&wad_dir).unwrap();
+    let payload = wad_dir.join("payload");
+    fs::write(&payload, b"chunks").unwrap();
+    let mut entries = vec![WadWriteEntry {
+      entry: WadEntry {
+        path_hash: hash_wad_path("skin.bin"),
+        offset: 0,
+        compressed_size: 6,
+        size: 12,
+        kind: WAD_KIND_ZSTD_MULTI,
+        subchunk_count: 2,
+        subchunk_index: 0,
+        checksum: 0,
+      },
+      source: EntrySource::File { path: payload },
+    }];
+    let wad = wad_dir.join("Ahri.wad.client");
+    write_wad(&wad, WadVersion::V3_3, &mut entries).unwrap();
+
+    let overlay = root.join("overlay");
+    let err = build_overlay(
+      &game,
+      &root.join("mods"),
+      &["chunked".to_string()],
+      &HashMap::new(),
+      &overlay,
+      &CancelToken::new(),
+    )
+    .unwrap_err();
+
+    assert!(err.to_string().contains("chunked/Ahri.wad.client"));
+    assert!(!overlay.join("DATA").exists());
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn conflicts_report_owners_and_honor_chosen_winner() {
+    let root = temp_dir("overlay-conflicts");
+    let game = root.join("Game");
+    let champions = game.join("DATA").join("FINAL").join("Champions");
+    fs::create_dir_all(&champions).unwrap();
+    write_fixture_wad(
+      &champions.join("Ahri.wad.client"),
+      &[("skin.bin", b"base"), ("hud.bin", b"base-hud")],
+    );
+
+    let mods = root.join("mods");
+    for (name, payload) in [("skin", b"from-skin"), ("hud", b"from-hud!")] {
+      let wad_dir = mods.join(name).join("WAD");
+      fs::create_dir_all(&wad_dir).unwrap();
+      write_fixture_wad(&wad_dir.join("Ahri.wad.client"), &[("skin.bin", payload)]);
+    }
+    let hud_only = mods.join("hud").join("WAD").join("Ahri.wad");
+    fs::create_dir_all(&hud_only).unwrap();
+    fs::write(hud_only.join("hud.bin"), b"hud").unwrap();
+
+    let names = vec!["skin".to_string(), "hud".to_string()];
+    let mut sources = HashMap::new();
+    sources.insert(
+      "skin".to_string(),
+      ModSource::Skin {
+        champion_id: 103,
+        skin_id: 103001,
+        chroma_id: None,
+      },
+    );
+
+    let conflicts =
+      analyze_conflicts(&game, &mods, &names, &sources, &HashMap::new()).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    let conflict = &conflicts[0];
+    assert_eq!(conflict.wad, "DATA/FINAL/Champions/Ahri.wad.client");
+    assert_eq!(conflict.path_hash, format_path_hash(hash_wad_path("skin.bin")));
+    assert_eq!(conflict.owners.len(), 2);
+    assert_eq!(conflict.owners[0].mod_name, "skin");
+    assert!(conflict.owners[0].source.is_some());
+    assert!(conflict.owners[1].source.is_none());
+    assert_eq!(conflict.winner, "hud");
+    assert!(!conflict.chosen_by_user);
+
+    let mut winners = HashMap::new();
+    winners.insert(hash_wad_path("skin.bin"), "skin".to_string());
+    let conflicts = analyze_conflicts(&game, &mods, &names, &sources, &winners).unwrap();
+    assert_eq!(conflicts[0].winner, "skin");
+    assert!(conflicts[0].chosen_by_user);
+
+    let overlay = root.join("overlay");
+    let cancel = CancelToken::new();
+    build_overlay(&game, &mods, &names, &winners, &overlay, &cancel).unwrap();
+    let out = overlay
+      .join("DATA")
+      .join("FINAL")
+      .join("Champions")
+      .join("Ahri.wad.client");
+    assert_eq!(read_entry(&out, "skin.bin").unwrap(), b"from-skin");
+    assert_eq!(read_entry(&out, "hud.bin").unwrap(), b"hud");
+
+    cancel.cancel();
+    let cancelled = root.join("cancelled");
+    let result = build_overlay(&game, &mods, &names, &winners, &cancelled, &cancel);
+    assert!(matches!(result, Err(InjectionError::Aborted(_))));
+    assert!(!cancelled.join("DATA").exists());
+
+    let _ = fs::remove_dir_all(root);
+  }
+
+  #[test]
+  fn parse_path_hash_accepts_formatted_hashes() {
+    let hash = hash_wad_path("assets/characters/ahri/skins/skin0.bin");
+    assert_eq!(parse_path_hash(&format_path_hash(hash)), Some(hash));
+    assert_eq!(parse_path_hash("0x00000000000000ff"), Some(0xff));
+    assert_eq!(parse_path_hash("not-a-hash"), None);
+  }
+
+  #[test]
+  fn mods_are_ordered_by_category_then_name() {
+    let mut sources = HashMap::new();
+    let misc = |item_type: &str| ModSource::Misc {
+      id: item_type.to_string(),
+      name: item_type.to_string(),
+      item_type: item_type.to_string(),
+    };
+    sources.insert("zz_hud".to_string(), misc("hud"));
+    sources.insert("map".to_string(), misc("Map"));
+    sources.insert(
+      "ahri".to_string(),
+      ModSource::Skin {
+        champion_id: 103,
+        skin_id: 103001,
+        chroma_id: None,
+      },
+    );
+    sources.insert(
+      "annie".to_string(),
+      ModSource::Skin {
+        champion_id: 1,
+        skin_id: 1002,
+        chroma_id: None,
+      },
+    );
+    sources.insert(
+      "custom".to_string(),
+      ModSource::Skin {
+        champion_id: 1,
+        skin_id: 0,
+        chroma_id: None,
+      },
+    );
+
+    let order: Vec<String> = DEFAULT_LOAD_ORDER.iter().map(|s| s.to_string()).collect();
+    let mut names: Vec<String> = ["zz_hud", "custom", "unknown", "annie", "map", "ahri"]
+      .iter()
+      .map(|s| s.to_string())
+      .collect();
+    order_mod_names(&mut names, &sources, &order);
+    assert_eq!(names, ["unknown", "map", "ahri", "annie", "custom", "zz_hud"]);
+
+    // A user order that puts the HUD under the map
+    let order = vec!["hud".to_string(), "map".to_string()];
+    order_mod_names(&mut names, &sources, &order);
+    assert_eq!(names, ["ahri", "annie", "custom", "unknown", "zz_hud", "map"]);
+  }
+
+  #[test]
+  fn overlay_signature_tracks_order_game_and_choices() {
+    let keys = vec!["a-v1".to_string(), "b-v1".to_string()];
+    let reversed = vec!["b-v1".to_string(), "a-v1".to_string()];
+    let none = HashMap::new();
+    let base = overlay_signature(&keys, "14.1|1|2", &none);
+
+    assert_eq!(base, overlay_signature(&keys, "14.1|1|2", &none));
+    assert_ne!(base, overlay_signature(&reversed, "14.1|1|2", &none));
+    assert_ne!(base, overlay_signature(&keys, "14.2|1|2", &none));
+
+    let mut winners = HashMap::new();
+    winners.insert(1u64, "a".to_string());
+    assert_ne!(base, overlay_signature(&keys, "14.1|1|2", &winners));
+  }
+}
@@ -0,0 +1,469 @@
+use crate::injection::error::InjectionError;
+use crate::injection::skin_file::{first_component_is, is_wad_file_name, ExtractionLimits};
+use crate::injection::wad::read_wad_toc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Component, Path};
+use zip::ZipArchive;
+
+// Skin package inspection
+//
+// Reads an archive's central directory (and META/info.json) without extracting anything, and
+// reports what convert_archive_to_mod would do with it. Uploads and received party skins are
+// checked with this before they are accepted.
+
+// Fields cslol-style META/info.json files are expected to have
+const REQUIRED_INFO_FIELDS: [&str; 4] = ["Name", "Author", "Version", "Description"];
+
+// Entries that expand more than ExtractionLimits::max_compression_ratio times their compressed
+// size are flagged, as long as they are also larger than MIN_RATIO_CHECK_SIZE (tiny files
+// compress arbitrarily well)
+const MIN_RATIO_CHECK_SIZE: u64 = 1_048_576;
+
+// info.json files larger than this are not parsed
+const MAX_INFO_SIZE: u64 = 1_048_576;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageFormat {
+  // .zip/.fantome/.skin_file archive
+  Zip,
+  // A bare WAD file, which can't be injected on its own
+  Wad,
+  Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SuspiciousReason {
+  AbsolutePath,
+  ParentTraversal,
+  CompressionRatio { ratio: u64 },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuspiciousEntry {
+  pub name: String,
+  pub reason: SuspiciousReason,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageWad {
+  // Name under WAD/, e.g. "Ahri.wad.client"
+  pub name: String,
+  // 1 for a packed WAD, the file count for an unpacked WAD folder
+  pub files: usize,
+  pub uncompressed_size: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackageInfo {
+  pub name: Option<String>,
+  pub author: Option<String>,
+  pub version: Option<String>,
+  pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageReport {
+  pub format: PackageFormat,
+  pub archive_size: u64,
+  pub entry_count: usize,
+  pub total_uncompressed_size: u64,
+  // True when the archive has META/info.json at all
+  pub has_info: bool,
+  pub info: Option<PackageInfo>,
+  pub info_missing_fields: Vec<String>,
+  // No META/info.json, so injection generates a default one
+  pub synthesized_info: bool,
+  // True when WADs come from a WAD/ folder rather than loose .wad/.wad.client files
+  pub wad_folder: bool,
+  pub wads: Vec<PackageWad>,
+  pub suspicious: Vec<SuspiciousEntry>,
+  // Problems that make the package unusable
+  pub errors: Vec<String>,
+  pub warnings: Vec<String>,
+}
+
+impl PackageReport {
+  fn new(format: PackageFormat, archive_size: u64) -> Self {
+    Self {
+      format,
+      archive_size,
+      entry_count: 0,
+      total_uncompressed_size: 0,
+      has_info: false,
+      info: None,
+      info_missing_fields: Vec::new(),
+      synthesized_info: false,
+      wad_folder: false,
+      wads: Vec::new(),
+      suspicious: Vec::new(),
+      errors: Vec::new(),
+      warnings: Vec::new(),
+    }
+  }
+
+  pub fn is_acceptable(&self) -> bool {
+    self.errors.is_empty()
+  }
+
+  // One line summary of the errors, for logs and command errors
+  pub fn error_summary(&self) -> String {
+    self.errors.join("; ")
+  }
+}
+
+// Inspect the package at `path` against the same `limits` extraction enforces. Only failing to
+// read the file is an error; anything wrong with its content is reported in the returned report.
+pub fn validate_skin_package(
+  path: &Path,
+  limits: &ExtractionLimits,
+) -> Result<PackageReport, InjectionError> {
+  let archive_size = fs::metadata(path)?.len();
+
+  match ZipArchive::new(fs::File::open(path)?) {
+    Ok(mut archive) => Ok(inspect_archive(&mut archive, archive_size, limits)),
+    Err(zip_error) => {
+      // Not a zip; maybe a bare WAD picked by mistake
+      if read_wad_toc(path).is_ok() {
+        let mut report = PackageReport::new(PackageFormat::Wad, archive_size);
+        report.errors.push(
+          "bare WAD files can't be injected; use a .zip or .fantome package with a WAD/ folder"
+            .to_string(),
+        );
+        Ok(report)
+      } else {
+        let mut report = PackageReport::new(PackageFormat::Unknown, archive_size);
+        report
+          .errors
+          .push(format!("not a readable zip archive: {}", zip_error));
+        Ok(report)
+      }
+    }
+  }
+}
+
+pub(crate) fn inspect_archive<R: io::Read + io::Seek>(
+  archive: &mut ZipArchive<R>,
+  archive_size: u64,
+  limits: &ExtractionLimits,
+) -> PackageReport {
+  let mut report = PackageReport::new(PackageFormat::Zip, archive_size);
+  report.entry_count = archive.len();
+  report.wad_folder = archive
+    .file_names()
+    .any(|name| first_component_is(Path::new(name), "WAD") && name.contains('/'));
+
+  let mut wads: BTreeMap<String, PackageWad> = BTreeMap::new();
+  let mut info_index = None;
+
+  for i in 0..archive.len() {
+    let entry = match archive.by_index_raw(i) {
+      Ok(entry) => entry,
+      Err(e) => {
+        report
+          .errors
+          .push(format!("unreadable entry #{}: {}", i, e));
+        continue;
+      }
+    };
+    let name = entry.name().to_string();
+    let size = entry.size();
+    report.total_uncompressed_size += size;
+
+    if let Some(reason) = suspicious_name(&name) {
+      report.suspicious.push(SuspiciousEntry { name, reason });
+      continue;
+    }
+    if size > MIN_RATIO_CHECK_SIZE {
+      let ratio = size / entry.compressed_size().max(1);
+      if ratio > limits.max_compression_ratio {
+        report.suspicious.push(SuspiciousEntry {
+          name: name.clone(),
+          reason: SuspiciousReason::CompressionRatio { ratio },
+        });
+      }
+    }
+    if entry.is_dir() {
+      continue;
+    }
+
+    let rel = Path::new(&name);
+    if first_component_is(rel, "META") {
+      let is_info = rel.components().count() == 2
+        && rel
+          .file_name()
+          .map(|n| n.to_string_lossy().eq_ignore_ascii_case("info.json"))
+          .unwrap_or(false);
+      if is_info {
+        info_index = Some(i);
+      }
+      continue;
+    }
+
+    // Same selection as convert_archive_to_mod
+    let wad_name = if report.wad_folder {
+      if !first_component_is(rel, "WAD") {
+        continue;
+      }
+      match rel.components().nth(1) {
+        Some(component) => component.as_os_str().to_string_lossy().to_string(),
+        None => continue,
+      }
+    } else {
+      match rel.file_name() {
+        Some(file_name) if is_wad_file_name(&file_name.to_string_lossy()) => {
+          file_name.to_string_lossy().to_string()
+        }
+        _ => continue,
+      }
+    };
+    let wad = wads.entry(wad_name.clone()).or_insert(PackageWad {
+      name: wad_name,
+      files: 0,
+      uncompressed_size: 0,
+    });
+    wad.files += 1;
+    wad.uncompressed_size += size;
+  }
+
+  report.wads = wads.into_values().collect();
+  if report.wads.is_empty() {
+    report
+      .errors
+      .push("package contains no WAD files".to_string());
+  }
+  for wad in &report.wads {
+    if !is_wad_file_name(&wad.name) {
+      report.warnings.push(format!(
+        "WAD/{} doesn't end in .wad or .wad.client",
+        wad.name
+      ));
+    }
+  }
+
+  match info_index {
+    Some(index) => {
+      report.has_info = true;
+      read_info(archive, index, &mut report);
+    }
+    None => {
+      report.synthesized_info = true;
+      report
+        .warnings
+        .push("no META/info.json; a default one will be generated".to_string());
+    }
+  }
+
+  if !report.suspicious.is_empty() {
+    report.errors.push(format!(
+      "{} suspicious entr{} (absolute paths, '..' or extreme compression)",
+      report.suspicious.len(),
+      if report.suspicious.len() == 1 {
+        "y"
+      } else {
+        "ies"
+      }
+    ));
+  }
+
+  report
+}
+
+fn suspicious_name(name: &str) -> Option<SuspiciousReason> {
+  let normalized = name.replace('\\', "/");
+  let bytes = normalized.as_bytes();
+  let has_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+  if normalized.starts_with('/') || has_drive {
+    return Some(SuspiciousReason::AbsolutePath);
+  }
+  if Path::new(&normalized)
+    .components()
+    .any(|c| matches!(c, Component::ParentDir))
+  {
+    return Some(SuspiciousReason::ParentTraversal);
+  }
+  None
+}
+
+fn read_info<R: io::Read + io::Seek>(
+  archive: &mut ZipArchive<R>,
+  index: usize,
+  report: &mut PackageReport,
+) {
+  let mut content = String::new();
+  let read = archive
+    .by_index(index)
+    .map_err(|e| e.to_string())
+    .and_then(|entry| {
+      if entry.size() > MAX_INFO_SIZE {
+        return Err(format!("{} bytes", entry.size()));
+      }
+      entry
+        .take(MAX_INFO_SIZE)
+        .read_to_string(&mut content)
+        .map_err(|e| e.to_string())
+    });
+  if let Err(e) = read {
+    report
+      .errors
+      .push(format!("META/info.json can't be read: {}", e));
+    return;
+  }
+
+  let value =
+    match serde_json::from_str::<serde_json::Value>(content.trim_start_matches('\u{feff}')) {
+      Ok(value) => value,
+      Err(e) => {
+        report
+          .errors
+          .push(format!("META/info.json is not valid JSON: {}", e));
+        return;
+      }
+    };
+
+  let field = |key: &str| -> Option<String> {
+    value.get(key).map(|v| match v.as_str() {
+      Some(s) => s.to_string(),
+      None => v.to_string(),
+    })
+  };
+  report.info = Some(PackageInfo {
+    name: field("Name"),
+    author: field("Author"),
+    version: field("Version"),
+    description: field("Description"),
+  });
+  report.info_missing_fields = REQUIRED_INFO_FIELDS
+    .iter()
+    .filter(|key| value.get(**key).is_none())
+    .map(|key| key.to_string())
+    .collect();
+  if !report.info_missing_fields.is_empty() {
+    report.warnings.push(format!(
+      "META/info.json is missing {}",
+      report.info_missing_fields.join(", ")
+    ));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+  use zip::write::FileOptions;
+
+  fn archive(files: &[(&str, &[u8])]) -> ZipArchive<io::Cursor<Vec<u8>>> {
+    let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    for (name, data) in files {
+      writer.start_file(*name, FileOptions::default()).unwrap();
+      writer.write_all(data).unwrap();
+    }
+    ZipArchive::new(writer.finish().unwrap()).unwrap()
+  }
+
+  #[test]
+  fn reports_wads_info_and_sizes() {
+    let mut zip = archive(&[
+      (
+        "META/info.json",
+        br#"{"Name":"Arcade Ahri","Author":"me","Version":"1.0"}"#,
+      ),
+      ("WAD/Ahri.wad.client", b"packed"),
+      ("WAD/Annie.wad.client/data/a.bin", b"one"),
+      ("WAD/Annie.wad.client/data/b.bin", b"two"),
+      ("README.txt", b"ignored"),
+    ]);
+    let report = inspect_archive(&mut zip, 0, &ExtractionLimits::default());
+
+    assert!(report.is_acceptable(), "{:?}", report.errors);
+    assert!(report.wad_folder && report.has_info && !report.synthesized_info);
+    let wads: Vec<(&str, usize)> = report
+      .wads
+      .iter()
+      .map(|w| (w.name.as_str(), w.files))
+      .collect();
+    assert_eq!(wads, vec![("Ahri.wad.client", 1), ("Annie.wad.client", 2)]);
+    assert_eq!(
+      report.info.as_ref().unwrap().name.as_deref(),
+      Some("Arcade Ahri")
+    );
+    assert_eq!(report.info_missing_fields, vec!["Description".to_string()]);
+    assert_eq!(report.total_uncompressed_size, 71);
+    assert_eq!(report.entry_count, 5);
+  }
+
+  #[test]
+  fn loose_wads_without_meta_use_synthesized_info() {
+    let mut zip = archive(&[("skins/Ahri.wad.client", b"packed")]);
+    let report = inspect_archive(&mut zip, 0, &ExtractionLimits::default());
+
+    assert!(report.is_acceptable());
+    assert!(!report.wad_folder && report.synthesized_info);
+    assert_eq!(report.wads[0].name, "Ahri.wad.client");
+  }
+
+  #[test]
+  fn rejects_traversal_bad_info_and_empty_packages() {
+    let mut zip = archive(&[
+      ("META/info.json", b"{not json"),
+      ("WAD/../../evil.dll", b"x"),
+      ("WAD/Ahri.wad.client", b"packed"),
+    ]);
+    let report = inspect_archive(&mut zip, 0, &ExtractionLimits::default());
+    assert!(!report.is_acceptable());
+    assert_eq!(report.suspicious.len(), 1);
+    assert_eq!(
+      report.suspicious[0].reason,
+      SuspiciousReason::ParentTraversal
+    );
+    assert!(report.errors.iter().any(|e| e.contains("not valid JSON")));
+
+    let mut empty = archive(&[("README.txt", b"hi")]);
+    let report = inspect_archive(&mut empty, 0, &ExtractionLimits::default());
+    assert!(!report.is_acceptable());
+    assert!(report.wads.is_empty());
+
+    assert_eq!(
+      suspicious_name("/etc/passwd"),
+      Some(SuspiciousReason::AbsolutePath)
+    );
+    assert_eq!(
+      suspicious_name("C:\\x.wad"),
+      Some(SuspiciousReason::AbsolutePath)
+    );
+    assert_eq!(suspicious_name("WAD/ok.wad.client"), None);
+  }
+
+  #[test]
+  fn flags_extreme_compression_ratio() {
+    let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    writer
+      .start_file(
+        "WAD/Ahri.wad.client",
+        FileOptions::default().compression_method(zip::CompressionMethod::Deflated),
+      )
+      .unwrap();
+    writer.write_all(&vec![0u8; 4 * 1_048_576]).unwrap();
+    let mut zip = ZipArchive::new(writer.finish().unwrap()).unwrap();
+
+    let limits = ExtractionLimits::default();
+    let report = inspect_archive(&mut zip, 0, &limits);
+    let ratio = match report.suspicious[0].reason {
+      SuspiciousReason::CompressionRatio { ratio } => ratio,
+      ref other => panic!("expected CompressionRatio, got {:?}", other),
+    };
+    assert!(ratio > limits.max_compression_ratio);
+    assert!(!report.is_acceptable());
+
+    // Raising the configured limit accepts it, as extraction would
+    let raised = ExtractionLimits {
+      max_compression_ratio: ratio,
+      ..limits
+    };
+    assert!(inspect_archive(&mut zip, 0, &raised).is_acceptable());
+  }
+}
@@ -0,0 +1,426 @@
+use crate::commands::skin_injection::InjectionStatusValue;
+use crate::injection::error::PatcherMessage;
+use serde::{Deserialize, Serialize};
+
+// Typed view of runoverlay output
+//
+// Every line mod-tools prints is parsed into a PatcherEvent. Status lines carry a stage,
+// and the transition table in `next_status` decides how each event moves the injection
+// status shown in the UI.
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PatcherEvent {
+  // "Status: <message>"; stage is None for a status this version doesn't know
+  Status {
+    stage: Option<PatcherMessage>,
+    message: String,
+  },
+  // "[INF] <message>"
+  Info {
+    message: String,
+  },
+  // "[DLL] <message>", forwarded from the injected patcher DLL
+  Dll {
+    message: String,
+  },
+  // "[WRN] <message>"
+  Warning {
+    message: String,
+  },
+  // "[ERR] <message>", "Error: <message>" and anything printed on stderr
+  Error {
+    message: String,
+  },
+  // Any other line
+  Output {
+    line: String,
+  },
+  // stdout closed, the overlay process is gone
+  Closed,
+}
+
+impl PatcherEvent {
+  pub fn parse(line: &str) -> Self {
+    let line = line.trim();
+    if let Some(message) = line.strip_prefix("Status:") {
+      let message = message.trim().to_string();
+      return Self::Status {
+        stage: PatcherMessage::from_status(&message),
+        message,
+      };
+    }
+    let tagged = [
+      ("[INF]", Self::info as fn(String) -> Self),
+      ("[DLL]", Self::dll),
+      ("[WRN]", Self::warning),
+      ("[ERR]", Self::error),
+      ("Error:", Self::error),
+    ];
+    for (prefix, make) in tagged {
+      if let Some(message) = line.strip_prefix(prefix) {
+        return make(message.trim().to_string());
+      }
+    }
+    Self::Output {
+      line: line.to_string(),
+    }
+  }
+
+  // stderr only carries problems, so untagged lines there are errors too
+  pub fn parse_stderr(line: &str) -> Self {
+    match Self::parse(line) {
+      Self::Output { line } => Self::Error { message: line },
+      event => event,
+    }
+  }
+
+  fn info(message: String) -> Self {
+    Self::Info { message }
+  }
+
+  fn dll(message: String) -> Self {
+    Self::Dll { message }
+  }
+
+  fn warning(message: String) -> Self {
+    Self::Warning { message }
+  }
+
+  fn error(message: String) -> Self {
+    Self::Error { message }
+  }
+}
+
+// Status change caused by an event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusTransition {
+  pub status: InjectionStatusValue,
+  pub message: Option<String>,
+  pub error: Option<String>,
+}
+
+impl StatusTransition {
+  fn to(status: InjectionStatusValue, message: &str) -> Self {
+    Self {
+      status,
+      message: Some(message.to_string()),
+      error: None,
+    }
+  }
+}
+
+// Transition table from patcher events to the injection status. None leaves it unchanged.
+pub fn next_status(
+  current: InjectionStatusValue,
+  event: &PatcherEvent,
+) -> Option<StatusTransition> {
+  use InjectionStatusValue::*;
+  use PatcherMessage::*;
+
+  match event {
+    PatcherEvent::Status {
+      stage: Some(stage),
+      message,
+    } => match stage {
+      WaitStart | WaitInit | Scan | NeedSave | WaitPatchable => {
+        Some(StatusTransition::to(Running, message))
+      }
+      Found => Some(StatusTransition::to(
+        Running,
+        "Found League - preparing to patch",
+      )),
+      Patch => Some(StatusTransition::to(Patching, message)),
+      WaitExit => Some(StatusTransition::to(
+        Success,
+        "Mods applied - waiting for the game to exit",
+      )),
+      Done => Some(StatusTransition::to(
+        Success,
+        "Game exited - mods were applied",
+      )),
+    },
+    // Once mods were applied, the overlay going away is the normal end of a game;
+    // anything earlier means mod-tools stopped or crashed
+    PatcherEvent::Closed => match current {
+      Success | Idle => Some(StatusTransition {
+        status: Idle,
+        message: None,
+        error: None,
+      }),
+      _ => Some(StatusTransition {
+        status: Error,
+        message: Some("Overlay process stopped unexpectedly".to_string()),
+        error: Some(
+          "Overlay exited before patching the game (mod-tools stopped/crashed).".to_string(),
+        ),
+      }),
+    },
+    _ => None,
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageTimestamp {
+  pub stage: PatcherMessage,
+  pub at_ms: u64,
+  // Time since the overlay was started
+  pub elapsed_ms: u64,
+}
+
+// One entry of the `patcher-event` stream
+#[derive(Debug, Clone, Serialize)]
+pub struct PatcherEventRecord {
+  pub event: PatcherEvent,
+  pub at_ms: u64,
+  pub elapsed_ms: u64,
+  pub transition: Option<StatusTransition>,
+}
+
+// Follows one overlay run: stamps events and remembers when each stage was first reached
+#[derive(Debug, Clone)]
+pub struct PatcherTracker {
+  started_at_ms: u64,
+  stages: Vec<StageTimestamp>,
+  last_stage: Option<PatcherMessage>,
+}
+
+impl PatcherTracker {
+  pub fn new(started_at_ms: u64) -> Self {
+    Self {
+      started_at_ms,
+      stages: Vec::new(),
+      last_stage: None,
+    }
+  }
+
+  pub fn stages(&self) -> &[StageTimestamp] {
+    &self.stages
+  }
+
+  // Whether this run got the game patched. A persistent overlay that went back to waiting
+  // for the next game counts as not patched again.
+  pub fn patched(&self) -> bool {
+    matches!(
+      self.last_stage,
+      Some(PatcherMessage::WaitExit | PatcherMessage::Done)
+    )
+  }
+
+  pub fn record(
+    &mut self,
+    event: PatcherEvent,
+    current: InjectionStatusValue,
+    at_ms: u64,
+  ) -> PatcherEventRecord {
+    let elapsed_ms = at_ms.saturating_sub(self.started_at_ms);
+    // The injection status already reads Success once the injection returns, long before
+    // the game is patched, so an exit is judged by what this run reached
+    let current = match (&event, current) {
+      (PatcherEvent::Closed, InjectionStatusValue::Success) if !self.patched() => {
+        InjectionStatusValue::Running
+      }
+      _ => current,
+    };
+    if let PatcherEvent::Status {
+      stage: Some(stage), ..
+    } = &event
+    {
+      self.last_stage = Some(*stage);
+      if !self.stages.iter().any(|s| s.stage == *stage) {
+        self.stages.push(StageTimestamp {
+          stage: *stage,
+          at_ms,
+          elapsed_ms,
+        });
+      }
+    }
+    PatcherEventRecord {
+      transition: next_status(current, &event),
+      event,
+      at_ms,
+      elapsed_ms,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // runoverlay output captured from a normal game, one line per stdout write
+  const RECORDED_GAME: &str = "\
+[INF] Overlay: mods/overlay
+Status: Waiting for league match to start
+Status: Found League
+Status: Wait initialized
+Status: Scanning
+[DLL] Patcher v15 loaded
+Status: Wait patchable
+Status: Patching
+[DLL] Patched 3 WADs
+Status: Waiting for exit
+Status: League exited
+";
+
+  // The game was closed while mod-tools was still waiting for it to become patchable
+  const RECORDED_CRASH: &str = "\
+Status: Waiting for league match to start
+Status: Found League
+[WRN] Failed to open process, retrying
+Error: Failed to patch: access denied
+";
+
+  fn replay(
+    output: &str,
+    mut status: InjectionStatusValue,
+  ) -> (
+    Vec<PatcherEventRecord>,
+    InjectionStatusValue,
+    PatcherTracker,
+  ) {
+    let mut tracker = PatcherTracker::new(1_000);
+    let mut records = Vec::new();
+    let events = output
+      .lines()
+      .map(PatcherEvent::parse)
+      .chain(std::iter::once(PatcherEvent::Closed));
+    for (i, event) in events.enumerate() {
+      let record = tracker.record(event, status, 1_000 + 100 * i as u64);
+      if let Some(transition) = &record.transition {
+        status = transition.status;
+      }
+      records.push(record);
+    }
+    (records, status, tracker)
+  }
+
+  #[test]
+  fn parses_every_line_kind() {
+    assert_eq!(
+      PatcherEvent::parse("Status: Patching"),
+      PatcherEvent::Status {
+        stage: Some(PatcherMessage::Patch),
+        message: "Patching".into()
+      }
+    );
+    assert_eq!(
+      PatcherEvent::parse("Status: Something new"),
+      PatcherEvent::Status {
+        stage: None,
+        message: "Something new".into()
+      }
+    );
+    assert_eq!(
+      PatcherEvent::parse("[INF] hello "),
+      PatcherEvent::Info {
+        message: "hello".into()
+      }
+    );
+    assert_eq!(
+      PatcherEvent::parse("[DLL] hooked"),
+      PatcherEvent::Dll {
+        message: "hooked".into()
+      }
+    );
+    assert_eq!(
+      PatcherEvent::parse("[WRN] slow"),
+      PatcherEvent::Warning {
+        message: "slow".into()
+      }
+    );
+    assert_eq!(
+      PatcherEvent::parse("Error: boom"),
+      PatcherEvent::Error {
+        message: "boom".into()
+      }
+    );
+    assert_eq!(
+      PatcherEvent::parse("plain"),
+      PatcherEvent::Output {
+        line: "plain".into()
+      }
+    );
+    assert_eq!(
+      PatcherEvent::parse_stderr("plain"),
+      PatcherEvent::Error {
+        message: "plain".into()
+      }
+    );
+    for message in PatcherMessage::ALL {
+      assert_eq!(
+        PatcherMessage::from_status(message.to_string()),
+        Some(message)
+      );
+    }
+  }
+
+  #[test]
+  fn recorded_game_ends_idle_with_every_stage_stamped() {
+    let (records, status, tracker) = replay(RECORDED_GAME, InjectionStatusValue::Busy);
+
+    let statuses: Vec<InjectionStatusValue> = records
+      .iter()
+      .filter_map(|r| r.transition.as_ref().map(|t| t.status))
+      .collect();
+    use InjectionStatusValue::*;
+    assert_eq!(
+      statuses,
+      vec![Running, Running, Running, Running, Running, Patching, Success, Success, Idle]
+    );
+    assert_eq!(status, Idle);
+
+    let stages: Vec<PatcherMessage> = tracker.stages().iter().map(|s| s.stage).collect();
+    use PatcherMessage::*;
+    assert_eq!(
+      stages,
+      vec![
+        WaitStart,
+        Found,
+        WaitInit,
+        Scan,
+        WaitPatchable,
+        Patch,
+        WaitExit,
+        Done
+      ]
+    );
+    assert_eq!(tracker.stages()[0].elapsed_ms, 100);
+    assert!(tracker.stages().windows(2).all(|w| w[0].at_ms < w[1].at_ms));
+  }
+
+  #[test]
+  fn recorded_crash_ends_in_error() {
+    let (records, status, tracker) = replay(RECORDED_CRASH, InjectionStatusValue::Busy);
+
+    assert_eq!(status, InjectionStatusValue::Error);
+    let last = records.last().unwrap();
+    assert_eq!(last.event, PatcherEvent::Closed);
+    assert!(last.transition.as_ref().unwrap().error.is_some());
+    assert!(records
+      .iter()
+      .any(|r| matches!(r.event, PatcherEvent::Error { .. }) && r.transition.is_none()));
+    assert_eq!(tracker.stages().len(), 2);
+  }
+
+  #[test]
+  fn exit_before_patching_is_an_error_even_after_the_injection_reported_success() {
+    let mut tracker = PatcherTracker::new(1_000);
+    let waiting = PatcherEvent::parse("Status: Waiting for league match to start");
+    tracker.record(waiting, InjectionStatusValue::Busy, 1_100);
+    assert!(!tracker.patched());
+
+    // The watcher marks the injection successful as soon as it returns; then the patcher is
+    // killed before it printed its patched line
+    let record = tracker.record(PatcherEvent::Closed, InjectionStatusValue::Success, 1_200);
+    assert_eq!(
+      record.transition.map(|t| t.status),
+      Some(InjectionStatusValue::Error)
+    );
+    assert!(!tracker.patched());
+
+    let (_, status, tracker) = replay(RECORDED_GAME, InjectionStatusValue::Success);
+    assert_eq!(status, InjectionStatusValue::Idle);
+    assert!(tracker.patched());
+  }
+}
@@ -0,0 +1,169 @@
+use serde::Serialize;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+// Where shared skin archives may resolve to
+//
+// Friends send us a path in their SkinShare. Whatever that path maps to locally has to end
+// up inside one of our own skin directories; an archive anywhere else on disk is never
+// injected, even if the file exists.
+
+// Portable prefix friends use for paths under their champions directory
+pub const PORTABLE_PREFIX: &str = "/ezrea/";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PathRejection {
+  // The requested path climbs out with `..`
+  ParentTraversal,
+  // The path (or one of its symlinks) points outside every allowed root
+  OutsideAllowedRoots,
+  // The path doesn't exist or can't be resolved
+  Unresolvable { error: String },
+}
+
+impl fmt::Display for PathRejection {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PathRejection::ParentTraversal => write!(f, "path contains '..'"),
+      PathRejection::OutsideAllowedRoots => write!(f, "path is outside the skin directories"),
+      PathRejection::Unresolvable { error } => write!(f, "path can't be resolved: {}", error),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct SkinPathPolicy {
+  roots: Vec<PathBuf>,
+}
+
+impl SkinPathPolicy {
+  // champions/ and custom_skins/ under the app data directory
+  pub fn for_app_dir(app_dir: &Path) -> Self {
+    Self {
+      roots: vec![app_dir.join("champions"), app_dir.join("custom_skins")],
+    }
+  }
+
+  pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+    self.roots.push(root.into());
+    self
+  }
+
+  // Local path a portable `/ezrea/...` path maps to, if `requested` uses the prefix
+  pub fn map_portable(&self, requested: &str) -> Option<PathBuf> {
+    let normalized = requested.replace('\\', "/");
+    let tail = normalized
+      .strip_prefix(PORTABLE_PREFIX)
+      .or_else(|| normalized.strip_prefix(&PORTABLE_PREFIX[1..]))?;
+    Some(self.roots[0].join(tail))
+  }
+
+  // Resolved path of `path` if it lies inside an allowed root. Symlinks are followed before
+  // comparing, so a link inside champions/ can't point somewhere else.
+  pub fn check(&self, path: &Path) -> Result<PathBuf, PathRejection> {
+    if path.components().any(|c| matches!(c, Component::ParentDir)) {
+      return Err(PathRejection::ParentTraversal);
+    }
+    let resolved = path
+      .canonicalize()
+      .map_err(|e| PathRejection::Unresolvable {
+        error: e.to_string(),
+      })?;
+    let inside = self
+      .roots
+      .iter()
+      .filter_map(|root| root.canonicalize().ok())
+      .any(|root| resolved.starts_with(root));
+    if inside {
+      Ok(resolved)
+    } else {
+      Err(PathRejection::OutsideAllowedRoots)
+    }
+  }
+
+  pub fn allows(&self, path: &Path) -> bool {
+    self.check(path).is_ok()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+
+  fn temp_app_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("osskins-policy-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(dir.join("champions").join("Ahri")).unwrap();
+    fs::create_dir_all(dir.join("custom_skins")).unwrap();
+    fs::create_dir_all(dir.join("elsewhere")).unwrap();
+    fs::write(
+      dir.join("champions").join("Ahri").join("arcade.zip"),
+      b"zip",
+    )
+    .unwrap();
+    fs::write(dir.join("custom_skins").join("mine.zip"), b"zip").unwrap();
+    fs::write(dir.join("elsewhere").join("secret.zip"), b"zip").unwrap();
+    dir
+  }
+
+  #[test]
+  fn allows_only_paths_inside_skin_directories() {
+    let app_dir = temp_app_dir();
+    let policy = SkinPathPolicy::for_app_dir(&app_dir);
+
+    assert!(policy.allows(&app_dir.join("champions").join("Ahri").join("arcade.zip")));
+    assert!(policy.allows(&app_dir.join("custom_skins").join("mine.zip")));
+    assert_eq!(
+      policy.check(&app_dir.join("elsewhere").join("secret.zip")),
+      Err(PathRejection::OutsideAllowedRoots)
+    );
+    assert_eq!(
+      policy.check(
+        &app_dir
+          .join("champions")
+          .join("..")
+          .join("elsewhere")
+          .join("secret.zip")
+      ),
+      Err(PathRejection::ParentTraversal)
+    );
+    assert!(matches!(
+      policy.check(&app_dir.join("champions").join("missing.zip")),
+      Err(PathRejection::Unresolvable { .. })
+    ));
+    assert!(SkinPathPolicy::for_app_dir(&app_dir)
+      .with_root(app_dir.join("elsewhere"))
+      .allows(&app_dir.join("elsewhere").join("secret.zip")));
+
+    let _ = fs::remove_dir_all(app_dir);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn rejects_symlinks_leaving_skin_directories() {
+    let app_dir = temp_app_dir();
+    let link = app_dir.join("champions").join("link.zip");
+    std::os::unix::fs::symlink(app_dir.join("elsewhere").join("secret.zip"), &link).unwrap();
+
+    let policy = SkinPathPolicy::for_app_dir(&app_dir);
+    assert_eq!(policy.check(&link), Err(PathRejection::OutsideAllowedRoots));
+
+    let _ = fs::remove_dir_all(app_dir);
+  }
+
+  #[test]
+  fn maps_portable_prefix_into_champions() {
+    let app_dir = PathBuf::from("app");
+    let policy = SkinPathPolicy::for_app_dir(&app_dir);
+    assert_eq!(
+      policy.map_portable("/ezrea/Ahri/arcade.zip"),
+      Some(app_dir.join("champions").join("Ahri/arcade.zip"))
+    );
+    assert_eq!(
+      policy.map_portable("ezrea\\Ahri\\arcade.zip"),
+      Some(app_dir.join("champions").join("Ahri/arcade.zip"))
+    );
+    assert_eq!(policy.map_portable("/home/friend/arcade.zip"), None);
+  }
+}
@@ -0,0 +1,277 @@
+use crate::commands::error::CommandError;
+use crate::injection::core::{Resolution, SkinInjector};
+use crate::injection::error::{InjectionError, MiscItem, ModSource, Skin};
+use crate::injection::mod_cache::{archive_hash, cache_key, ModCache};
+use crate::injection::overlay::{analyze_mod_conflicts, order_mod_names, EntryConflict};
+use crate::injection::package::validate_skin_package;
+use crate::injection::skin_file::ResolutionMethod;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// Dry run of an injection
+//
+// Runs the same resolution as inject_skins_and_misc and checks each archive, but never
+// touches Game/mods, Game.cfg or the mod cache contents and never starts mod-tools.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedItem {
+  pub label: String,
+  // Directory name the mod would get under Game/mods
+  pub mod_name: String,
+  pub source: ModSource,
+  pub archive: PathBuf,
+  // Which lookup found a skin archive; None for misc items
+  pub method: Option<ResolutionMethod>,
+  pub bytes: u64,
+  // Already converted in the mod cache, so injecting it is just a copy
+  pub cached: bool,
+  pub valid: bool,
+  pub problem: Option<String>,
+}
+
+// A selected item with no archive on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingItem {
+  pub label: String,
+  pub source: ModSource,
+  pub requested_path: Option<String>,
+  pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InjectionPlan {
+  pub items: Vec<PlannedItem>,
+  pub missing: Vec<MissingItem>,
+  pub total_bytes: u64,
+  // Valid mod names in the order the overlay would layer them
+  pub load_order: Vec<String>,
+  // Conflicts between the mods that are already in the mod cache
+  pub predicted_conflicts: Vec<EntryConflict>,
+  // False when some mods aren't cached yet, so their conflicts couldn't be predicted
+  pub conflicts_complete: bool,
+}
+
+impl SkinInjector {
+  pub(crate) fn plan_injection(
+    &mut self,
+    skins: &[Skin],
+    misc_items: &[MiscItem],
+    skin_file_files_dir: &Path,
+    load_order: &[String],
+    winners: &HashMap<u64, String>,
+  ) -> Result<InjectionPlan, InjectionError> {
+    let Resolution { pending, missing } =
+      self.resolve_archives(skins, misc_items, skin_file_files_dir)?;
+
+    let mut plan = InjectionPlan {
+      missing,
+      ..Default::default()
+    };
+    let cache = ModCache::new(&self.app_dir);
+    let mut sources = HashMap::new();
+    let mut cached_dirs = HashMap::new();
+    let limits = self.extraction_limits();
+
+    for item in pending {
+      let bytes = fs::metadata(&item.archive).map(|m| m.len()).unwrap_or(0);
+      plan.total_bytes += bytes;
+
+      let cached_dir = archive_hash(&item.archive)
+        .ok()
+        .and_then(|hash| cache.peek(&cache_key(&hash)));
+      // Cached mods were converted successfully before; anything else goes through the
+      // package validator
+      let problem = if cached_dir.is_some() {
+        None
+      } else {
+        match validate_skin_package(&item.archive, &limits) {
+          Ok(report) if report.is_acceptable() => None,
+          Ok(report) => Some(report.error_summary()),
+          Err(e) => Some(format!("unreadable archive: {}", e)),
+        }
+      };
+
+      sources.insert(item.mod_name.clone(), item.source.clone());
+      if let Some(dir) = &cached_dir {
+        cached_dirs.insert(item.mod_name.clone(), dir.clone());
+      }
+      plan.items.push(PlannedItem {
+        label: item.label,
+        mod_name: item.mod_name,
+        source: item.source,
+        archive: item.archive,
+        method: item.method,
+        bytes,
+        cached: cached_dir.is_some(),
+        valid: problem.is_none(),
+        problem,
+      });
+    }
+
+    let mut mod_names: Vec<String> = plan
+      .items
+      .iter()
+      .filter(|item| item.valid)
+      .map(|item| item.mod_name.clone())
+      .collect();
+    order_mod_names(&mut mod_names, &sources, load_order);
+    // Archives with the same file name land in the same Game/mods directory
+    mod_names.dedup();
+
+    let mods: Vec<(String, PathBuf)> = mod_names
+      .iter()
+      .filter_map(|name| cached_dirs.get(name).map(|dir| (name.clone(), dir.clone())))
+      .collect();
+    plan.conflicts_complete = mods.len() == mod_names.len();
+    if mods.len() > 1 {
+      match analyze_mod_conflicts(&self.game_path, &mods, &sources, winners) {
+        Ok(conflicts) => plan.predicted_conflicts = conflicts,
+        Err(e) => {
+          self.log(&format!("WARNING: Could not predict conflicts: {}", e));
+          plan.conflicts_complete = false;
+        }
+      }
+    }
+    plan.load_order = mod_names;
+
+    Ok(plan)
+  }
+}
+
+// Wrapper called from the plan_injection command
+pub fn plan_injection(
+  app_handle: &AppHandle,
+  game_path: &str,
+  skins: &[Skin],
+  misc_items: &[MiscItem],
+  skin_file_files_dir: &Path,
+) -> Result<InjectionPlan, CommandError> {
+  let mut injector = SkinInjector::for_planning(app_handle, game_path)
+    .map_err(|e| CommandError::from(e).prefixed("Failed to create injector"))?;
+
+  let load_order = crate::commands::config::get_load_order_from_config(app_handle);
+  let winners = crate::commands::config::get_conflict_winners_from_config(app_handle);
+
+  injector
+    .plan_injection(
+      skins,
+      misc_items,
+      skin_file_files_dir,
+      &load_order,
+      &winners,
+    )
+    .map_err(|e| CommandError::from(e).prefixed("Failed to plan injection"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::injection::cancel::CancelToken;
+  use crate::injection::error::ModState;
+  use crate::injection::history::InjectionTrigger;
+  use crate::injection::timing::TimingReport;
+  use std::io::Write;
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("osskins-{}-{}", name, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+    let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+    for (name, data) in files {
+      writer
+        .start_file(*name, zip::write::FileOptions::default())
+        .unwrap();
+      writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap();
+  }
+
+  fn injector(root: &Path) -> SkinInjector {
+    SkinInjector {
+      state: ModState::Idle,
+      app_dir: root.join("app"),
+      root_path: root.to_path_buf(),
+      game_path: root.join("Game"),
+      status: String::new(),
+      log_file: None,
+      backend: None,
+      champion_names: HashMap::new(),
+      app_handle: None,
+      mod_sources: HashMap::new(),
+      mod_keys: HashMap::new(),
+      trigger: InjectionTrigger::Manual,
+      outcome_sink: None,
+      timing: TimingReport::default(),
+      cancel: CancelToken::new(),
+      deadline: None,
+      timeout: None,
+      persistent_overlay: false,
+    }
+  }
+
+  fn skin(skin_id: u32, path: &str) -> Skin {
+    Skin {
+      champion_id: 103,
+      skin_id,
+      chroma_id: None,
+      skin_file_path: Some(path.to_string()),
+    }
+  }
+
+  #[test]
+  fn plans_resolved_missing_and_invalid_items_without_touching_game() {
+    let root = temp_dir("plan");
+    let skins_dir = root.join("champions");
+    fs::create_dir_all(&skins_dir).unwrap();
+    fs::create_dir_all(root.join("Game")).unwrap();
+    write_zip(
+      &skins_dir.join("ahri_arcade.zip"),
+      &[("WAD/Ahri.wad.client", b"wad")],
+    );
+    write_zip(&skins_dir.join("empty.zip"), &[("README.txt", b"hi")]);
+
+    let misc = MiscItem {
+      id: "m1".into(),
+      name: "Map".into(),
+      item_type: "map".into(),
+      skin_file_path: "map.zip".into(),
+    };
+    let plan = injector(&root)
+      .plan_injection(
+        &[
+          skin(1, "ahri_arcade.zip"),
+          skin(2, "empty.zip"),
+          skin(3, "missing.zip"),
+        ],
+        &[misc],
+        &skins_dir,
+        &crate::commands::types::default_load_order(),
+        &HashMap::new(),
+      )
+      .unwrap();
+
+    assert_eq!(plan.items.len(), 2);
+    assert_eq!(plan.items[0].method, Some(ResolutionMethod::RelativePath));
+    assert!(plan.items[0].valid && !plan.items[0].cached);
+    assert!(!plan.items[1].valid);
+    assert_eq!(
+      plan.total_bytes,
+      plan.items.iter().map(|item| item.bytes).sum::<u64>()
+    );
+    assert_eq!(plan.load_order, vec!["ahri_arcade".to_string()]);
+    assert!(!plan.conflicts_complete);
+
+    let missing: Vec<&str> = plan.missing.iter().map(|m| m.label.as_str()).collect();
+    assert_eq!(missing, vec!["skin 3", "misc item Map"]);
+    assert!(!root.join("Game").join("mods").exists());
+    assert!(!root.join("app").join("mod_cache").exists());
+
+    let _ = fs::remove_dir_all(root);
+  }
+}
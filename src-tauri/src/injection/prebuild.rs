@@ -0,0 +1,144 @@
+use crate::commands::types::SavedConfig;
+use crate::injection::cancel::CancelToken;
+use crate::injection::core::{Resolution, SkinInjector};
+use crate::injection::error::{InjectionError, Skin};
+use crate::injection::skin_file::prepare_mods;
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+// Speculative mod preparation
+//
+// While the local player hovers a champion in champ select, the archives of their saved skins
+// for it (and for the ARAM bench) are converted into the mod cache on a background thread.
+// The injection at lock-in then finds them cached, or waits for the conversion that is still
+// underway, and only has to stage them and build the overlay. Hovering other champions cancels
+// the running prebuild between archive entries.
+
+#[derive(Default)]
+struct PrebuildSlot {
+  // Champions of the latest prebuild and its token
+  current: Option<(Vec<u32>, CancelToken)>,
+}
+
+impl PrebuildSlot {
+  // Token for a prebuild of `champion_ids`, cancelling any prebuild for other champions.
+  // None when the same champions are already prepared or being prepared.
+  fn replace(&mut self, champion_ids: &[u32]) -> Option<CancelToken> {
+    if let Some((current, cancel)) = &self.current {
+      if current.as_slice() == champion_ids && !cancel.is_cancelled() {
+        return None;
+      }
+      cancel.cancel();
+    }
+    let cancel = CancelToken::new();
+    self.current = Some((champion_ids.to_vec(), cancel.clone()));
+    Some(cancel)
+  }
+
+  fn cancel(&mut self) {
+    if let Some((_, cancel)) = self.current.take() {
+      cancel.cancel();
+    }
+  }
+}
+
+static PREBUILD: Lazy<Mutex<PrebuildSlot>> = Lazy::new(|| Mutex::new(PrebuildSlot::default()));
+
+// Prepare the mods for `champion_ids` in the background unless that is already underway
+pub fn start_prebuild(app: &AppHandle, league_path: &str, champion_ids: Vec<u32>) {
+  let cancel = match PREBUILD
+    .lock()
+    .expect("PREBUILD poisoned")
+    .replace(&champion_ids)
+  {
+    Some(cancel) => cancel,
+    None => return,
+  };
+
+  let app = app.clone();
+  let league_path = league_path.to_string();
+  std::thread::spawn(move || {
+    let result = prebuild(&app, &league_path, &champion_ids, &cancel);
+    match result {
+      Ok(prepared) => println!(
+        "[Prebuild] Prepared {} mods for champions {:?}",
+        prepared, champion_ids
+      ),
+      Err(e) => println!(
+        "[Prebuild] Stopped preparing champions {:?}: {}",
+        champion_ids, e
+      ),
+    }
+  });
+}
+
+// Stop the running prebuild, e.g. when champ select ends
+pub fn cancel_prebuild() {
+  PREBUILD.lock().expect("PREBUILD poisoned").cancel();
+}
+
+fn prebuild(
+  app: &AppHandle,
+  league_path: &str,
+  champion_ids: &[u32],
+  cancel: &CancelToken,
+) -> Result<usize, InjectionError> {
+  let mut injector = SkinInjector::for_planning(app, league_path)?;
+
+  // The same loadout an injection for these champions would use
+  let config_file = injector.app_dir.join("config").join("config.json");
+  let Some(config) = std::fs::read_to_string(config_file)
+    .ok()
+    .and_then(|data| serde_json::from_str::<SavedConfig>(&data).ok())
+  else {
+    return Ok(0);
+  };
+  let skins: Vec<Skin> = champion_ids
+    .iter()
+    .filter_map(|id| config.skins.iter().find(|s| s.champion_id == *id))
+    .map(|s| Skin {
+      champion_id: s.champion_id,
+      skin_id: s.skin_id,
+      chroma_id: s.chroma_id,
+      skin_file_path: s.skin_file.clone(),
+    })
+    .collect();
+  if skins.is_empty() {
+    return Ok(0);
+  }
+  let misc_items = crate::commands::misc_items::get_selected_misc_items(app).unwrap_or_default();
+  let skin_file_files_dir = injector.app_dir.join("champions");
+
+  let Resolution { pending, .. } =
+    injector.resolve_archives(&skins, &misc_items, &skin_file_files_dir)?;
+  let archives: Vec<PathBuf> = pending.iter().map(|item| item.archive.clone()).collect();
+  let limits = crate::commands::config::get_extraction_limits_from_config(app);
+  let results = prepare_mods(&injector.app_dir, &archives, &limits, cancel);
+  cancel.check("prebuild")?;
+  Ok(results.iter().filter(|result| result.is_ok()).count())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn a_new_hover_cancels_the_running_prebuild() {
+    let mut slot = PrebuildSlot::default();
+    let ahri = slot.replace(&[103]).unwrap();
+    // Same hover again, e.g. another session update
+    assert!(slot.replace(&[103]).is_none());
+    assert!(!ahri.is_cancelled());
+
+    let lux = slot.replace(&[99, 103]).unwrap();
+    assert!(ahri.is_cancelled());
+    assert!(!lux.is_cancelled());
+
+    slot.cancel();
+    assert!(lux.is_cancelled());
+    // Hovering the same champions after a cancel starts over
+    assert!(slot.replace(&[99, 103]).is_some());
+  }
+}
@@ -0,0 +1,346 @@
+use crate::injection::cancel::cancel_in_flight;
+use crate::injection::core::SkinInjector;
+use crate::injection::error::{InjectionError, MiscItem, Skin};
+use crate::injection::history::InjectionTrigger;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Condvar, Mutex, MutexGuard, Once};
+use tauri::AppHandle;
+
+// Injection job queue
+//
+// Every injection runs on one worker thread. Callers submit a job and block until it is done.
+// Each injection replaces all of Game/mods, so only the newest request matters: at most one
+// job waits behind the running one, a newer request supersedes it, and a request identical to
+// the running job is dropped. Dropped requests end with InjectionError::Superseded, and their
+// callers leave reporting to the job that replaced them.
+
+type JobResult = Result<(), InjectionError>;
+
+struct QueuedJob<J> {
+  id: String,
+  signature: String,
+  job: J,
+  done: Sender<JobResult>,
+}
+
+struct RunningJob {
+  id: String,
+  signature: String,
+  // Set by cancel_pending: a new request for the same skins must run again, not coalesce
+  cancelled: bool,
+}
+
+struct QueueState<J> {
+  running: Option<RunningJob>,
+  pending: Option<QueuedJob<J>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueStatus {
+  pub current_job_id: Option<String>,
+  // Jobs waiting behind the current one
+  pub queue_depth: usize,
+}
+
+pub(crate) struct JobQueue<J> {
+  state: Mutex<QueueState<J>>,
+  wake: Condvar,
+}
+
+impl<J> JobQueue<J> {
+  pub(crate) fn new() -> Self {
+    Self {
+      state: Mutex::new(QueueState {
+        running: None,
+        pending: None,
+      }),
+      wake: Condvar::new(),
+    }
+  }
+
+  fn lock(&self) -> MutexGuard<'_, QueueState<J>> {
+    self.state.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  // Queue `job` in place of the waiting one. Returns the job id and where its result arrives.
+  pub(crate) fn submit(&self, signature: String, job: J) -> (String, Receiver<JobResult>) {
+    let id = uuid::Uuid::new_v4().to_string();
+    let (done, result) = mpsc::channel();
+    let mut state = self.lock();
+
+    if let Some(superseded) = state.pending.take() {
+      let _ = superseded.done.send(Err(InjectionError::Superseded(format!(
+        "job {} was replaced by job {}",
+        superseded.id, id
+      ))));
+    }
+    match &state.running {
+      Some(running) if running.signature == signature && !running.cancelled => {
+        let _ = done.send(Err(InjectionError::Superseded(format!(
+          "job {} is already injecting the same skins",
+          running.id
+        ))));
+      }
+      _ => {
+        state.pending = Some(QueuedJob {
+          id: id.clone(),
+          signature,
+          job,
+          done,
+        });
+        self.wake.notify_all();
+      }
+    }
+    (id, result)
+  }
+
+  // Wait for a job and mark it as running
+  fn next(&self) -> QueuedJob<J> {
+    let mut state = self.lock();
+    loop {
+      if let Some(job) = state.pending.take() {
+        state.running = Some(RunningJob {
+          id: job.id.clone(),
+          signature: job.signature.clone(),
+          cancelled: false,
+        });
+        return job;
+      }
+      state = self.wake.wait(state).unwrap_or_else(|e| e.into_inner());
+    }
+  }
+
+  fn finish(&self) {
+    self.lock().running = None;
+  }
+
+  // Run jobs one after another, forever
+  pub(crate) fn work(&self, mut run: impl FnMut(J) -> JobResult) {
+    loop {
+      let QueuedJob { id, job, done, .. } = self.next();
+      // A panicking injection must not take the worker down with it
+      let result = panic::catch_unwind(AssertUnwindSafe(|| run(job))).unwrap_or_else(|_| {
+        Err(InjectionError::ProcessError(format!(
+          "injection job {} panicked",
+          id
+        )))
+      });
+      self.finish();
+      let _ = done.send(result);
+    }
+  }
+
+  // Drop the waiting job, if any, and stop coalescing with the running one, which the caller
+  // is about to cancel. Returns how many were dropped.
+  pub(crate) fn cancel_pending(&self) -> usize {
+    let mut state = self.lock();
+    if let Some(running) = state.running.as_mut() {
+      running.cancelled = true;
+    }
+    match state.pending.take() {
+      Some(job) => {
+        let _ = job.done.send(Err(InjectionError::Aborted(format!(
+          "job {} was cancelled before it started",
+          job.id
+        ))));
+        1
+      }
+      None => 0,
+    }
+  }
+
+  pub(crate) fn status(&self) -> QueueStatus {
+    let state = self.lock();
+    QueueStatus {
+      current_job_id: state.running.as_ref().map(|job| job.id.clone()),
+      queue_depth: usize::from(state.pending.is_some()),
+    }
+  }
+}
+
+pub(crate) struct InjectionJob {
+  pub(crate) app: AppHandle,
+  pub(crate) league_path: String,
+  pub(crate) skins: Vec<Skin>,
+  pub(crate) misc_items: Vec<MiscItem>,
+  pub(crate) skin_file_files_dir: PathBuf,
+  pub(crate) trigger: InjectionTrigger,
+  pub(crate) emit_events: bool,
+}
+
+impl InjectionJob {
+  // Two jobs with the same signature build the same Game/mods
+  fn signature(&self) -> String {
+    let mut parts: Vec<String> = self
+      .skins
+      .iter()
+      .map(|s| {
+        format!(
+          "skin:{}:{}:{:?}:{}",
+          s.champion_id,
+          s.skin_id,
+          s.chroma_id,
+          s.skin_file_path.as_deref().unwrap_or_default()
+        )
+      })
+      .chain(self.misc_items.iter().map(|m| format!("misc:{}", m.id)))
+      .collect();
+    parts.sort();
+    format!("{}|{}", self.league_path, parts.join(","))
+  }
+
+  fn run(self) -> JobResult {
+    let mut injector = SkinInjector::new(&self.app, &self.league_path)?;
+    injector.trigger = self.trigger;
+    injector.initialize()?;
+    if self.emit_events {
+      injector.inject_skins_and_misc(&self.skins, &self.misc_items, &self.skin_file_files_dir)
+    } else {
+      injector.inject_skins_and_misc_no_events(
+        &self.skins,
+        &self.misc_items,
+        &self.skin_file_files_dir,
+      )
+    }
+  }
+}
+
+static INJECTION_QUEUE: Lazy<JobQueue<InjectionJob>> = Lazy::new(JobQueue::new);
+static WORKER: Once = Once::new();
+
+// Run `job` on the injection worker and wait for it
+pub(crate) fn run_queued(job: InjectionJob) -> JobResult {
+  WORKER.call_once(|| {
+    std::thread::Builder::new()
+      .name("injection-worker".to_string())
+      .spawn(|| INJECTION_QUEUE.work(InjectionJob::run))
+      .expect("failed to start the injection worker");
+  });
+
+  let signature = job.signature();
+  let (id, result) = INJECTION_QUEUE.submit(signature, job);
+  crate::commands::lcu_watcher::append_global_log(&format!("[Injection] Queued job {}", id));
+  result
+    .recv()
+    .unwrap_or_else(|_| Err(InjectionError::Aborted(format!("job {} was lost", id))))
+}
+
+pub fn queue_status() -> QueueStatus {
+  INJECTION_QUEUE.status()
+}
+
+// Cancel the running injection and drop the one waiting behind it. Returns how many there were.
+pub fn cancel_injections() -> usize {
+  INJECTION_QUEUE.cancel_pending() + cancel_in_flight()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn expect_superseded(result: &Receiver<JobResult>) {
+    match result.try_recv() {
+      Ok(Err(InjectionError::Superseded(_))) => {}
+      other => panic!("expected Superseded, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn coalesces_waiting_jobs() {
+    let queue = JobQueue::new();
+    let (first_id, first) = queue.submit("ahri".to_string(), 1);
+    let running = queue.next();
+    assert_eq!(running.job, 1);
+    assert_eq!(queue.status().current_job_id, Some(first_id));
+
+    // Rerolls while the first job runs: only the newest one waits
+    let (_, second) = queue.submit("lux".to_string(), 2);
+    let (_, third) = queue.submit("zed".to_string(), 3);
+    expect_superseded(&second);
+    assert_eq!(queue.status().queue_depth, 1);
+
+    // Back to the champion being injected: nothing left to do
+    let (_, again) = queue.submit("ahri".to_string(), 4);
+    expect_superseded(&third);
+    expect_superseded(&again);
+    assert_eq!(queue.status().queue_depth, 0);
+
+    running.done.send(Ok(())).unwrap();
+    queue.finish();
+    assert!(first.recv().unwrap().is_ok());
+    assert_eq!(queue.status(), QueueStatus::default());
+
+    let (_, last) = queue.submit("zed".to_string(), 5);
+    assert_eq!(queue.cancel_pending(), 1);
+    assert!(matches!(
+      last.try_recv(),
+      Ok(Err(InjectionError::Aborted(_)))
+    ));
+  }
+
+  #[test]
+  fn a_cancelled_running_job_is_not_coalesced_with() {
+    let queue = JobQueue::new();
+    let (running_id, _first) = queue.submit("ahri".to_string(), 1);
+    let running = queue.next();
+    assert_eq!(queue.cancel_pending(), 0);
+
+    // The running job is being torn down, so the same skins have to be injected again
+    let (again_id, again) = queue.submit("ahri".to_string(), 2);
+    assert!(again.try_recv().is_err());
+    assert_eq!(queue.status().queue_depth, 1);
+
+    running.done.send(Ok(())).unwrap();
+    queue.finish();
+    let next = queue.next();
+    assert_eq!(next.id, again_id);
+    assert_ne!(next.id, running_id);
+    assert_eq!(next.job, 2);
+  }
+
+  #[test]
+  fn worker_survives_a_panicking_job() {
+    let queue = std::sync::Arc::new(JobQueue::new());
+    let worker = queue.clone();
+    std::thread::spawn(move || {
+      worker.work(|job: u32| {
+        assert_ne!(job, 0, "job 0 always fails");
+        Ok(())
+      })
+    });
+
+    let (_, crashed) = queue.submit("ahri".to_string(), 0);
+    assert!(matches!(
+      crashed.recv().unwrap(),
+      Err(InjectionError::ProcessError(_))
+    ));
+    let (_, fine) = queue.submit("lux".to_string(), 1);
+    assert!(fine.recv().unwrap().is_ok());
+    assert_eq!(queue.status(), QueueStatus::default());
+  }
+
+  #[test]
+  fn a_job_panicking_under_the_injection_lock_does_not_block_the_next() {
+    // Stands in for INJECTION_LOCK, which run_injection holds for the whole job
+    static LOCK: Mutex<()> = Mutex::new(());
+    let queue = std::sync::Arc::new(JobQueue::new());
+    let worker = queue.clone();
+    std::thread::spawn(move || {
+      worker.work(|job: u32| {
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        assert_ne!(job, 0, "job 0 always fails");
+        Ok(())
+      })
+    });
+
+    let (_, crashed) = queue.submit("ahri".to_string(), 0);
+    assert!(crashed.recv().unwrap().is_err());
+    assert!(LOCK.is_poisoned());
+    let (_, fine) = queue.submit("lux".to_string(), 1);
+    assert!(fine.recv().unwrap().is_ok());
+  }
+}
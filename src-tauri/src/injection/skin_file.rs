@@ -1,5 +1,10 @@
+use crate::injection::cancel::CancelToken;
 use crate::injection::error::{InjectionError, Skin};
+use crate::injection::mod_cache::{archive_hash, cache_key, ModCache};
+use crate::injection::path_policy::SkinPathPolicy;
+use rayon::prelude::*;
 use memmap2::MmapOptions;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs;
 use std::io;
@@ -10,6 +15,40 @@ use zip::ZipArchive;
 
 // Fantome file processing operations
 
+// Which lookup in find_skin_file_for_skin produced the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionMethod {
+  // Absolute path that exists as-is (friend skin on the same layout)
+  AbsolutePath,
+  // Path relative to the skin files dir (our own skins)
+  RelativePath,
+  // Portable `/ezrea/` prefix mapped onto app_dir/champions
+  EzreaMapped,
+  EzreaBasename,
+  EzreaAltExtension,
+  // Same file name found in the skin files dir
+  LocalBasename,
+  LocalAltExtension,
+  // Derived from the stored champion JSON
+  ChampionData,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedArchive {
+  pub path: PathBuf,
+  pub method: ResolutionMethod,
+}
+
+impl ResolvedArchive {
+  fn new(path: impl Into<PathBuf>, method: ResolutionMethod) -> Self {
+    Self {
+      path: path.into(),
+      method,
+    }
+  }
+}
+
 impl crate::injection::core::SkinInjector {
   fn sanitize_for_file_name(&self, s: &str) -> String {
     let mut out = s.to_lowercase().trim().to_string();
@@ -130,121 +169,14 @@ impl crate::injection::core::SkinInjector {
     }
     None
   }
-  // Extract .skin_file file (similar to utility::unzip in CSLOL Manager)
-  pub(crate) fn extract_skin_file(
-    &mut self,
-    skin_file_path: &Path,
-    output_dir: &Path,
-  ) -> Result<(), InjectionError> {
-    self.log(&format!(
-      "Extracting skin_file file: {}",
-      skin_file_path.display()
-    ));
-
-    // Create output directory if it doesn't exist
-    fs::create_dir_all(output_dir)?;
-
-    // Open and extract the zip file
-    let file = fs::File::open(skin_file_path)?;
-    let mut archive = ZipArchive::new(file)?;
-
-    for i in 0..archive.len() {
-      let mut file = archive.by_index(i)?;
-      let outpath = match file.enclosed_name() {
-        Some(path) => output_dir.join(path),
-        None => continue,
-      };
-
-      if file.name().ends_with('/') {
-        fs::create_dir_all(&outpath)?;
-      } else {
-        if let Some(p) = outpath.parent() {
-          if !p.exists() {
-            fs::create_dir_all(p)?;
-          }
-        }
-        let mut outfile = fs::File::create(&outpath)?;
-        io::copy(&mut file, &mut outfile)?;
-      }
-    }
-
-    Ok(())
-  }
 
-  // Add this memory-optimized extraction function
-  pub(crate) fn extract_skin_file_mmap(
-    &mut self,
-    skin_file_path: &Path,
-    output_dir: &Path,
-  ) -> Result<(), InjectionError> {
-    self.log(&format!(
-      "Extracting skin_file file with memory mapping: {}",
-      skin_file_path.display()
-    ));
-
-    // Create output directory if it doesn't exist
-    fs::create_dir_all(output_dir)?;
-
-    // Open the file for memory mapping
-    let file = fs::File::open(skin_file_path)?;
-    let file_size = file.metadata()?.len();
-
-    // Only use memory mapping for larger files (>1MB)
-    if file_size > 1_048_576 {
-      // Use memory mapping for better performance with large files
-      let mmap = unsafe { MmapOptions::new().map(&file)? };
-
-      // Use the memory-mapped data to create a zip archive
-      let mut archive = ZipArchive::new(std::io::Cursor::new(&mmap[..]))?;
-
-      for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let outpath = match file.enclosed_name() {
-          Some(path) => output_dir.join(path),
-          None => continue,
-        };
-
-        if file.name().ends_with('/') {
-          fs::create_dir_all(&outpath)?;
-        } else {
-          if let Some(p) = outpath.parent() {
-            if !p.exists() {
-              fs::create_dir_all(p)?;
-            }
-          }
-          let mut outfile = fs::File::create(&outpath)?;
-          io::copy(&mut file, &mut outfile)?;
-        }
-      }
-
-      // Memory-mapped file is automatically unmapped when dropped
-      self.log("Memory-mapped extraction completed successfully");
-    } else {
-      // For smaller files, use the standard approach
-      let mut archive = ZipArchive::new(file)?;
-
-      for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let outpath = match file.enclosed_name() {
-          Some(path) => output_dir.join(path),
-          None => continue,
-        };
-
-        if file.name().ends_with('/') {
-          fs::create_dir_all(&outpath)?;
-        } else {
-          if let Some(p) = outpath.parent() {
-            if !p.exists() {
-              fs::create_dir_all(p)?;
-            }
-          }
-          let mut outfile = fs::File::create(&outpath)?;
-          io::copy(&mut file, &mut outfile)?;
-        }
-      }
-    }
-
-    Ok(())
+  // Name used for a mod under Game/mods, derived from its archive file name
+  pub(crate) fn mod_name_for_skin_file(&self, skin_file_path: &Path) -> String {
+    skin_file_path
+      .file_stem()
+      .unwrap_or_default()
+      .to_string_lossy()
+      .to_string()
   }
 
   // Check if directory contains META/info.json to confirm it's a valid mod
@@ -252,13 +184,14 @@ impl crate::injection::core::SkinInjector {
     dir_path.join("META").join("info.json").exists()
   }
 
-  // Find appropriate .skin_file file for a skin - simplified without fallback
+  // Find appropriate .skin_file file for a skin, reporting which lookup matched
   pub(crate) fn find_skin_file_for_skin(
     &mut self,
     skin: &Skin,
     skin_file_files_dir: &Path,
-  ) -> Result<Option<PathBuf>, InjectionError> {
+  ) -> Result<Option<ResolvedArchive>, InjectionError> {
     self.log(&format!("[DEBUG] find_skin_file_for_skin: skin_id={}, champion_id={}, chroma_id={:?}, skin_file_path={:?}", skin.skin_id, skin.champion_id, skin.chroma_id, skin.skin_file_path));
+    let policy = SkinPathPolicy::for_app_dir(&self.app_dir).with_root(skin_file_files_dir);
     // Only use direct path from JSON - no fallback searching
     if let Some(skin_file_path) = &skin.skin_file_path {
       self.log(&format!(
@@ -269,113 +202,155 @@ impl crate::injection::core::SkinInjector {
       // Check if this is an absolute path (friend's skin) vs relative path (our skin)
       let path = std::path::Path::new(skin_file_path);
       if path.is_absolute() {
-        // This is likely a friend's skin with absolute path - check if file exists as-is
+        // This is likely a friend's skin with absolute path - only used as-is when it lies
+        // inside our own skin directories
         self.log(&format!(
           "[DEBUG] Checking absolute path (friend skin): {}",
           path.display()
         ));
-        if path.exists() {
-          self.log(&format!(
-            "✅ Found friend's skin_file file at absolute path: {}",
-            path.display()
-          ));
-          return Ok(Some(path.to_path_buf()));
-        } else {
-          self.log(&format!(
+        match policy.check(path) {
+          Ok(_) => {
+            self.log(&format!(
+              "✅ Found friend's skin_file file at absolute path: {}",
+              path.display()
+            ));
+            return Ok(Some(ResolvedArchive::new(
+              path,
+              ResolutionMethod::AbsolutePath,
+            )));
+          }
+          Err(rejection) if path.exists() => self.log(&format!(
+            "⛔ Ignoring friend's skin_file path {} ({})",
+            path.display(),
+            rejection
+          )),
+          Err(_) => self.log(&format!(
             "❌ Friend's skin_file file not found at absolute path: {}",
             path.display()
-          ));
-          // For friend skins, try to map portable prefixes to local directories and search
-          // Known portable prefix: /ezrea/ -> app champions dir (primary) or ASSETS/Skins (fallback)
-          let app_champions = self.app_dir.join("champions");
-          if let Some(filename) = path.file_name() {
-            // Try mapping by tail under champions
-            let lowered = path.to_string_lossy().replace('\\', "/");
-            if lowered.starts_with("/ezrea/") {
-              let tail = &lowered["/ezrea/".len()..];
-              let mapped = app_champions.join(tail);
-              self.log(&format!(
-                "[DEBUG] Trying mapped /ezrea path: {}",
-                mapped.display()
-              ));
-              if mapped.exists() {
-                return Ok(Some(mapped));
-              }
-              // Try by basename under champions
-              let by_name = app_champions.join(filename);
-              if by_name.exists() {
-                return Ok(Some(by_name));
+          )),
+        }
+        // For friend skins, try to map portable prefixes to local directories and search
+        // Known portable prefix: /ezrea/ -> app champions dir (primary) or ASSETS/Skins (fallback)
+        // Every candidate goes through the policy too, a symlink in our directories may point
+        // anywhere
+        let app_champions = self.app_dir.join("champions");
+        if let Some(filename) = path.file_name() {
+          // Try mapping by tail under champions
+          if let Some(mapped) = policy.map_portable(&path.to_string_lossy()) {
+            self.log(&format!(
+              "[DEBUG] Trying mapped /ezrea path: {}",
+              mapped.display()
+            ));
+            if policy.allows(&mapped) {
+              return Ok(Some(ResolvedArchive::new(
+                mapped,
+                ResolutionMethod::EzreaMapped,
+              )));
+            }
+            // Try by basename under champions
+            let by_name = app_champions.join(filename);
+            if policy.allows(&by_name) {
+              return Ok(Some(ResolvedArchive::new(
+                by_name,
+                ResolutionMethod::EzreaBasename,
+              )));
+            }
+            // Try alt extensions under champions
+            if let Some(stem) = Path::new(filename).file_stem().and_then(|s| s.to_str()) {
+              let zip_candidate = app_champions.join(format!("{}.zip", stem));
+              let skin_file_candidate = app_champions.join(format!("{}.skin_file", stem));
+              if policy.allows(&zip_candidate) {
+                return Ok(Some(ResolvedArchive::new(
+                  zip_candidate,
+                  ResolutionMethod::EzreaAltExtension,
+                )));
               }
-              // Try alt extensions under champions
-              if let Some(stem) = Path::new(filename).file_stem().and_then(|s| s.to_str()) {
-                let zip_candidate = app_champions.join(format!("{}.zip", stem));
-                let skin_file_candidate = app_champions.join(format!("{}.skin_file", stem));
-                if zip_candidate.exists() {
-                  return Ok(Some(zip_candidate));
-                }
-                if skin_file_candidate.exists() {
-                  return Ok(Some(skin_file_candidate));
-                }
+              if policy.allows(&skin_file_candidate) {
+                return Ok(Some(ResolvedArchive::new(
+                  skin_file_candidate,
+                  ResolutionMethod::EzreaAltExtension,
+                )));
               }
             }
           }
-          // Fallback to filename/alt-extension search in provided skin_file_files_dir
-          // For friend skins, try to find a similar file in our local directory by filename,
-          // accepting either .zip or .skin_file extensions
-          if let Some(filename) = path.file_name() {
-            // Try exact filename
-            let local_path = skin_file_files_dir.join(filename);
+        }
+        // Fallback to filename/alt-extension search in provided skin_file_files_dir
+        // For friend skins, try to find a similar file in our local directory by filename,
+        // accepting either .zip or .skin_file extensions
+        if let Some(filename) = path.file_name() {
+          // Try exact filename
+          let local_path = skin_file_files_dir.join(filename);
+          self.log(&format!(
+            "[DEBUG] Trying to find similar file locally: {}",
+            local_path.display()
+          ));
+          if policy.allows(&local_path) {
             self.log(&format!(
-              "[DEBUG] Trying to find similar file locally: {}",
+              "✅ Found similar local archive: {}",
               local_path.display()
             ));
-            if local_path.exists() {
+            return Ok(Some(ResolvedArchive::new(
+              local_path,
+              ResolutionMethod::LocalBasename,
+            )));
+          }
+
+          // Try swapping extensions between .zip <-> .skin_file
+          if let Some(stem) = Path::new(filename).file_stem().and_then(|s| s.to_str()) {
+            let zip_candidate = skin_file_files_dir.join(format!("{}.zip", stem));
+            let skin_file_candidate = skin_file_files_dir.join(format!("{}.skin_file", stem));
+            self.log(&format!(
+              "[DEBUG] Trying alt extensions: {} | {}",
+              zip_candidate.display(),
+              skin_file_candidate.display()
+            ));
+            if policy.allows(&zip_candidate) {
               self.log(&format!(
-                "✅ Found similar local archive: {}",
-                local_path.display()
+                "✅ Found local .zip for shared skin: {}",
+                zip_candidate.display()
               ));
-              return Ok(Some(local_path));
+              return Ok(Some(ResolvedArchive::new(
+                zip_candidate,
+                ResolutionMethod::LocalAltExtension,
+              )));
             }
-
-            // Try swapping extensions between .zip <-> .skin_file
-            if let Some(stem) = Path::new(filename).file_stem().and_then(|s| s.to_str()) {
-              let zip_candidate = skin_file_files_dir.join(format!("{}.zip", stem));
-              let skin_file_candidate = skin_file_files_dir.join(format!("{}.skin_file", stem));
+            if policy.allows(&skin_file_candidate) {
               self.log(&format!(
-                "[DEBUG] Trying alt extensions: {} | {}",
-                zip_candidate.display(),
+                "✅ Found local .skin_file for shared skin: {}",
                 skin_file_candidate.display()
               ));
-              if zip_candidate.exists() {
-                self.log(&format!(
-                  "✅ Found local .zip for shared skin: {}",
-                  zip_candidate.display()
-                ));
-                return Ok(Some(zip_candidate));
-              }
-              if skin_file_candidate.exists() {
-                self.log(&format!(
-                  "✅ Found local .skin_file for shared skin: {}",
-                  skin_file_candidate.display()
-                ));
-                return Ok(Some(skin_file_candidate));
-              }
+              return Ok(Some(ResolvedArchive::new(
+                skin_file_candidate,
+                ResolutionMethod::LocalAltExtension,
+              )));
             }
           }
         }
       } else {
-        // This is a relative path (our own skin) - check in skin_file_files_dir
+        // This is a relative path (our own skin) - check in skin_file_files_dir. It comes
+        // from config.json too, so `..` or a symlink must not lead out of the skin directories.
         let direct_path = skin_file_files_dir.join(skin_file_path);
         self.log(&format!(
           "[DEBUG] Checking relative path (our skin): {}",
           direct_path.display()
         ));
-        if direct_path.exists() {
-          self.log(&format!(
-            "✅ Found our skin_file file at relative path: {}",
-            direct_path.display()
-          ));
-          return Ok(Some(direct_path));
+        match policy.check(&direct_path) {
+          Ok(_) => {
+            self.log(&format!(
+              "✅ Found our skin_file file at relative path: {}",
+              direct_path.display()
+            ));
+            return Ok(Some(ResolvedArchive::new(
+              direct_path,
+              ResolutionMethod::RelativePath,
+            )));
+          }
+          Err(rejection) if direct_path.exists() => self.log(&format!(
+            "⛔ Ignoring skin_file path {} ({})",
+            direct_path.display(),
+            rejection
+          )),
+          Err(_) => {}
         }
       }
 
@@ -384,189 +359,331 @@ impl crate::injection::core::SkinInjector {
         skin.champion_id, skin.skin_id
       ));
       // Try deriving from stored champion data as a fallback
-      if let Some(derived) = self.derive_skin_path_from_champion_data(skin, skin_file_files_dir) {
+      if let Some(derived) = self
+        .derive_skin_path_from_champion_data(skin, skin_file_files_dir)
+        .filter(|derived| policy.allows(derived))
+      {
         self.log(&format!(
           "✅ Fallback resolved via champion data: {}",
           derived.display()
         ));
-        return Ok(Some(derived));
+        return Ok(Some(ResolvedArchive::new(
+          derived,
+          ResolutionMethod::ChampionData,
+        )));
       }
     } else {
       self.log("❌ No skin_file path provided in skin data");
       // Derive from stored champion JSON (first selection robustness)
-      if let Some(derived) = self.derive_skin_path_from_champion_data(skin, skin_file_files_dir) {
+      if let Some(derived) = self
+        .derive_skin_path_from_champion_data(skin, skin_file_files_dir)
+        .filter(|derived| policy.allows(derived))
+      {
         self.log(&format!(
           "✅ Derived skin path from champion data: {}",
           derived.display()
         ));
-        return Ok(Some(derived));
+        return Ok(Some(ResolvedArchive::new(
+          derived,
+          ResolutionMethod::ChampionData,
+        )));
       }
     }
     Ok(None)
   }
+}
 
-  // Create a mod directory structure from extracted skin_file files
-  pub(crate) fn create_mod_from_extracted(
-    &mut self,
-    extract_dir: &Path,
-    mod_dir: &Path,
-  ) -> Result<(), InjectionError> {
-    self.log(&format!(
-      "Creating mod from extracted files at: {}",
-      extract_dir.display()
-    ));
+// Upper bound on archives converted at once; conversion is mostly disk bound
+const MAX_PARALLEL_MODS: usize = 4;
+
+// Ratios are only checked once an entry has written this much; tiny files compress
+// arbitrarily well
+const MIN_RATIO_CHECK_BYTES: u64 = 1_048_576;
+
+// Caps applied while converting an archive, so a corrupted or malicious package can't fill
+// the disk. Configurable through `extraction_limits` in config.json.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtractionLimits {
+  // Total bytes written for one archive
+  pub max_total_bytes: u64,
+  // Bytes written for a single entry
+  pub max_entry_bytes: u64,
+  pub max_entries: usize,
+  // Uncompressed / compressed size of a single entry
+  pub max_compression_ratio: u64,
+}
 
-    // Create mod directories
-    fs::create_dir_all(mod_dir.join("META"))?;
-    fs::create_dir_all(mod_dir.join("WAD"))?;
+impl Default for ExtractionLimits {
+  fn default() -> Self {
+    Self {
+      max_total_bytes: 4 * 1024 * 1024 * 1024,
+      max_entry_bytes: 2 * 1024 * 1024 * 1024,
+      max_entries: 20_000,
+      max_compression_ratio: 200,
+    }
+  }
+}
 
-    // Check if there's already a META/info.json in the extracted content
-    let extracted_info_json = extract_dir.join("META").join("info.json");
-    let mod_info_json = mod_dir.join("META").join("info.json");
+// A processed mod ready to be copied into Game/mods
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedMod {
+  pub dir: PathBuf,
+  // True when the mod cache already had this archive
+  pub cached: bool,
+  pub stats: ConvertStats,
+  pub elapsed: std::time::Duration,
+}
 
-    if extracted_info_json.exists() {
-      // Copy the existing info.json
-      fs::copy(&extracted_info_json, &mod_info_json)?;
-    } else {
-      // Create a basic info.json
-      let info_json = format!(
-        r#"{{
-                "Name": "ExtractedMod",
-                "Version": "1.0.0",
-                "Author": "osskins",
-                "Description": "Extracted from skin_file file at {}"
-            }}"#,
-        chrono::Local::now().to_rfc3339()
-      );
+// Resolve an archive to its processed mod directory inside the mod cache, converting it on a
+// cache miss. Doesn't touch the injector so several archives can be prepared in parallel.
+pub(crate) fn prepare_mod(
+  app_dir: &Path,
+  skin_file_path: &Path,
+  limits: &ExtractionLimits,
+  cancel: &CancelToken,
+) -> Result<PreparedMod, InjectionError> {
+  cancel.check("extraction")?;
+  let started = std::time::Instant::now();
+
+  // Reuse the processed mod if this exact archive was converted before
+  let cache = ModCache::new(app_dir);
+  let key = cache_key(&archive_hash(skin_file_path)?);
+  // A prebuild may be converting the same archive right now, see prebuild.rs
+  let _claim = cache.claim(&key, cancel)?;
+  if let Some(dir) = cache.lookup(&key) {
+    return Ok(PreparedMod {
+      dir,
+      cached: true,
+      stats: ConvertStats::default(),
+      elapsed: started.elapsed(),
+    });
+  }
 
-      fs::write(&mod_info_json, info_json)?;
+  // Archive entries are written straight into a staging directory in the cache
+  let staging = cache.staging_dir(&key)?;
+  match convert_skin_file_to_mod(skin_file_path, &staging, limits, cancel) {
+    Ok(stats) => Ok(PreparedMod {
+      dir: cache.commit(&key, &staging)?,
+      cached: false,
+      stats,
+      elapsed: started.elapsed(),
+    }),
+    Err(e) => {
+      cache.discard(&staging);
+      Err(e)
     }
+  }
+}
 
-    // Look for WAD directory in extracted content
-    let extracted_wad_dir = extract_dir.join("WAD");
-    if extracted_wad_dir.exists() {
-      // Copy WAD files
-      for entry in WalkDir::new(&extracted_wad_dir) {
-        let entry = entry?;
-        let path = entry.path();
-        let rel_path = path
-          .strip_prefix(&extracted_wad_dir)
-          .map_err(|e| InjectionError::ProcessError(format!("Path error: {}", e)))?;
+// Prepare several archives with bounded parallelism. Results keep the input order and every
+// archive gets its own result, so one bad archive doesn't discard the others' work.
+pub(crate) fn prepare_mods(
+  app_dir: &Path,
+  archives: &[PathBuf],
+  limits: &ExtractionLimits,
+  cancel: &CancelToken,
+) -> Vec<Result<PreparedMod, InjectionError>> {
+  let threads = archives.len().clamp(1, MAX_PARALLEL_MODS);
+  match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+    Ok(pool) => pool.install(|| {
+      archives
+        .par_iter()
+        .map(|archive| prepare_mod(app_dir, archive, limits, cancel))
+        .collect()
+    }),
+    // Fall back to sequential processing if a pool can't be created
+    Err(_) => archives
+      .iter()
+      .map(|archive| prepare_mod(app_dir, archive, limits, cancel))
+      .collect(),
+  }
+}
 
-        let target_path = mod_dir.join("WAD").join(rel_path);
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct ConvertStats {
+  pub wad_files: usize,
+  pub bytes_written: u64,
+  pub generated_info: bool,
+}
 
-        if path.is_dir() {
-          fs::create_dir_all(&target_path)?;
-        } else if path.is_file() {
-          if let Some(parent) = target_path.parent() {
-            fs::create_dir_all(parent)?;
-          }
-          fs::copy(path, &target_path)?;
-        }
-      }
-    } else {
-      // If no WAD directory, look for WAD files in the root
-      for entry in WalkDir::new(extract_dir) {
-        let entry = entry?;
-        let path = entry.path();
+// Convert a .skin_file/.zip archive into the mod layout (META/info.json + WAD/) in one pass,
+// without extracting anything else to disk
+pub(crate) fn convert_skin_file_to_mod(
+  skin_file_path: &Path,
+  mod_dir: &Path,
+  limits: &ExtractionLimits,
+  cancel: &CancelToken,
+) -> Result<ConvertStats, InjectionError> {
+  let file = fs::File::open(skin_file_path)?;
+  let file_size = file.metadata()?.len();
+
+  // Only use memory mapping for larger files (>1MB)
+  if file_size > 1_048_576 {
+    let mmap = unsafe { MmapOptions::new().map(&file)? };
+    let mut archive = ZipArchive::new(io::Cursor::new(&mmap[..]))?;
+    convert_archive_to_mod(&mut archive, mod_dir, limits, cancel)
+  } else {
+    let mut archive = ZipArchive::new(file)?;
+    convert_archive_to_mod(&mut archive, mod_dir, limits, cancel)
+  }
+}
 
-        // Skip META directory
-        if path.starts_with(extract_dir.join("META")) {
-          continue;
-        }
+pub(crate) fn is_wad_file_name(name: &str) -> bool {
+  let lowered = name.to_lowercase();
+  lowered.ends_with(".wad") || lowered.ends_with(".wad.client")
+}
 
-        // Check if this is a WAD file
-        if path.is_file()
-          && (path.extension().and_then(|ext| ext.to_str()) == Some("wad")
-            || path.to_string_lossy().ends_with(".wad.client"))
-        {
-          let file_name = path.file_name().unwrap();
-          let target_path = mod_dir.join("WAD").join(file_name);
+pub(crate) fn first_component_is(path: &Path, name: &str) -> bool {
+  path
+    .components()
+    .next()
+    .map(|c| c.as_os_str().to_string_lossy().eq_ignore_ascii_case(name))
+    .unwrap_or(false)
+}
 
-          fs::copy(path, &target_path)?;
-        }
-      }
+// Stream one entry to `out`, failing as soon as it breaks a limit. `written_before` is what
+// earlier entries of the same archive already wrote.
+fn copy_within_limits(
+  entry: &mut impl io::Read,
+  out: &mut impl io::Write,
+  name: &str,
+  compressed_size: u64,
+  written_before: u64,
+  limits: &ExtractionLimits,
+) -> Result<u64, InjectionError> {
+  let mut buf = vec![0u8; 1 << 16];
+  let mut written: u64 = 0;
+  loop {
+    let read = entry.read(&mut buf)?;
+    if read == 0 {
+      return Ok(written);
+    }
+    written += read as u64;
+
+    if written > limits.max_entry_bytes {
+      return Err(InjectionError::ArchiveLimitExceeded(format!(
+        "{} is larger than {} bytes",
+        name, limits.max_entry_bytes
+      )));
     }
+    if written_before + written > limits.max_total_bytes {
+      return Err(InjectionError::ArchiveLimitExceeded(format!(
+        "archive expands to more than {} bytes",
+        limits.max_total_bytes
+      )));
+    }
+    if written > MIN_RATIO_CHECK_BYTES
+      && written / compressed_size.max(1) > limits.max_compression_ratio
+    {
+      return Err(InjectionError::ArchiveLimitExceeded(format!(
+        "{} expands more than {}x its compressed size",
+        name, limits.max_compression_ratio
+      )));
+    }
+    out.write_all(&buf[..read])?;
+  }
+}
 
-    Ok(())
+pub(crate) fn convert_archive_to_mod<R: io::Read + io::Seek>(
+  archive: &mut ZipArchive<R>,
+  mod_dir: &Path,
+  limits: &ExtractionLimits,
+  cancel: &CancelToken,
+) -> Result<ConvertStats, InjectionError> {
+  if archive.len() > limits.max_entries {
+    return Err(InjectionError::ArchiveLimitExceeded(format!(
+      "{} entries (limit {})",
+      archive.len(),
+      limits.max_entries
+    )));
   }
 
-  // Process .skin_file files to create proper mod structure with memory optimization
-  pub(crate) fn process_skin_file(
-    &mut self,
-    skin_file_path: &Path,
-  ) -> Result<PathBuf, InjectionError> {
-    self.log(&format!(
-      "Processing skin_file file: {}",
-      skin_file_path.display()
-    ));
+  fs::create_dir_all(mod_dir.join("META"))?;
+  fs::create_dir_all(mod_dir.join("WAD"))?;
 
-    // Create a unique temp extraction directory to avoid collisions when
-    // processing multiple skin_file files with the same name or concurrent runs.
-    let file_stem = skin_file_path
-      .file_stem()
-      .unwrap_or_default()
-      .to_string_lossy()
-      .to_string();
-    // Generate unique suffix using timestamp and subsecond nanoseconds
-    let now = chrono::Local::now();
-    let unique_suffix = now.timestamp() * 1_000_000_000 + now.timestamp_subsec_nanos() as i64;
-    let extract_dir = self
-      .app_dir
-      .join("temp")
-      .join(format!("{}-{}", file_stem, unique_suffix));
-    let mod_dir = self.app_dir.join("mods").join(&file_stem);
-
-    // Clean up any existing mod directory (we always recreate mods from scratch)
-    if mod_dir.exists() {
-      fs::remove_dir_all(&mod_dir)?;
-    }
+  // Mods normally ship a WAD/ folder; otherwise pick up loose .wad/.wad.client files
+  let has_wad_dir = archive
+    .file_names()
+    .any(|name| first_component_is(Path::new(name), "WAD") && name.contains('/'));
+
+  let mut stats = ConvertStats::default();
+  let mut has_info = false;
 
-    // Determine file size to pick extraction strategy
-    let file_size = match fs::metadata(skin_file_path) {
-      Ok(metadata) => metadata.len(),
-      Err(_) => 0,
+  for i in 0..archive.len() {
+    cancel.check("extraction")?;
+    let mut entry = archive.by_index(i)?;
+    let rel = match entry.enclosed_name() {
+      Some(path) => path.to_path_buf(),
+      None => continue,
     };
 
-    // Run extraction and mod creation inside a closure so we can always
-    // perform the extraction directory cleanup afterwards (like a finally block).
-    let result = (|| -> Result<(), InjectionError> {
-      // Ensure the extract directory is clean before extracting
-      if extract_dir.exists() {
-        let _ = fs::remove_dir_all(&extract_dir);
+    let target = if first_component_is(&rel, "META") {
+      let is_info = rel.components().count() == 2
+        && rel
+          .file_name()
+          .map(|n| n.to_string_lossy().eq_ignore_ascii_case("info.json"))
+          .unwrap_or(false);
+      if entry.is_dir() || !is_info {
+        continue;
       }
-      fs::create_dir_all(&extract_dir)?;
-
-      // Use memory-mapped extraction for larger files
-      if file_size > 1_048_576 {
-        self.extract_skin_file_mmap(skin_file_path, &extract_dir)?;
-      } else {
-        self.extract_skin_file(skin_file_path, &extract_dir)?;
+      has_info = true;
+      mod_dir.join("META").join("info.json")
+    } else if has_wad_dir {
+      if !first_component_is(&rel, "WAD") {
+        continue;
       }
-
-      // Create mod structure from the extracted content
-      self.create_mod_from_extracted(&extract_dir, &mod_dir)?;
-
-      Ok(())
-    })();
-
-    // Always try to remove the temporary extraction directory. Log but don't fail on cleanup.
-    if extract_dir.exists() {
-      if let Err(e) = fs::remove_dir_all(&extract_dir) {
-        self.log(&format!(
-          "Warning: failed to remove temporary extraction dir {}: {}",
-          extract_dir.display(),
-          e
-        ));
+      let inner: PathBuf = rel.components().skip(1).collect();
+      if entry.is_dir() {
+        fs::create_dir_all(mod_dir.join("WAD").join(inner))?;
+        continue;
       }
-    }
+      stats.wad_files += 1;
+      mod_dir.join("WAD").join(inner)
+    } else {
+      let file_name = match rel.file_name() {
+        Some(name) if !entry.is_dir() && is_wad_file_name(&name.to_string_lossy()) => {
+          name.to_os_string()
+        }
+        _ => continue,
+      };
+      stats.wad_files += 1;
+      mod_dir.join("WAD").join(file_name)
+    };
 
-    // Return the result of the processing (propagate any error), otherwise the mod_dir
-    match result {
-      Ok(_) => Ok(mod_dir),
-      Err(e) => Err(e),
+    if let Some(parent) = target.parent() {
+      fs::create_dir_all(parent)?;
     }
+    // Declared sizes can lie, so the limits are enforced on the bytes actually written
+    let compressed_size = entry.compressed_size();
+    let name = entry.name().to_string();
+    let mut out = fs::File::create(&target)?;
+    stats.bytes_written += copy_within_limits(
+      &mut entry,
+      &mut out,
+      &name,
+      compressed_size,
+      stats.bytes_written,
+      limits,
+    )?;
+  }
+
+  if !has_info {
+    // Create a basic info.json
+    let info_json = format!(
+      r#"{{
+                "Name": "ExtractedMod",
+                "Version": "1.0.0",
+                "Author": "osskins",
+                "Description": "Extracted from skin_file file at {}"
+            }}"#,
+      chrono::Local::now().to_rfc3339()
+    );
+    fs::write(mod_dir.join("META").join("info.json"), info_json)?;
+    stats.generated_info = true;
   }
+
+  Ok(stats)
 }
 
 // Add a function to check for and copy the pre-built default overlay
@@ -628,3 +745,200 @@ pub fn copy_default_overlay(
 
   Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+
+  fn archive(files: &[(&str, &[u8])]) -> ZipArchive<io::Cursor<Vec<u8>>> {
+    let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    for (name, data) in files {
+      if name.ends_with('/') {
+        writer.add_directory(*name, Default::default()).unwrap();
+      } else {
+        writer.start_file(*name, Default::default()).unwrap();
+        writer.write_all(data).unwrap();
+      }
+    }
+    ZipArchive::new(writer.finish().unwrap()).unwrap()
+  }
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("osskins-{}-{}", name, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn converts_wad_folder_and_keeps_only_mod_files() {
+    let dir = temp_dir("convert-wad-dir");
+    let mut zip = archive(&[
+      ("META/info.json", br#"{"Name":"Test"}"#),
+      ("META/image.png", b"png"),
+      ("WAD/", b""),
+      ("WAD/Ahri.wad.client", b"packed"),
+      ("WAD/Annie.wad.client/data/skin.bin", b"loose"),
+      ("README.txt", b"ignored"),
+      ("extra/Other.wad.client", b"ignored when WAD/ exists"),
+    ]);
+
+    let stats = convert_archive_to_mod(
+      &mut zip,
+      &dir,
+      &ExtractionLimits::default(),
+      &CancelToken::new(),
+    )
+    .unwrap();
+    assert_eq!(stats.wad_files, 2);
+    assert!(!stats.generated_info);
+    assert_eq!(
+      fs::read(dir.join("META").join("info.json")).unwrap(),
+      br#"{"Name":"Test"}"#
+    );
+    assert!(!dir.join("META").join("image.png").exists());
+    assert_eq!(fs::read(dir.join("WAD").join("Ahri.wad.client")).unwrap(), b"packed");
+    assert_eq!(
+      fs::read(
+        dir
+          .join("WAD")
+          .join("Annie.wad.client")
+          .join("data")
+          .join("skin.bin")
+      )
+      .unwrap(),
+      b"loose"
+    );
+    assert!(!dir.join("README.txt").exists());
+    assert!(!dir.join("WAD").join("Other.wad.client").exists());
+
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn falls_back_to_loose_wads_and_generates_info() {
+    let dir = temp_dir("convert-loose");
+    let mut zip = archive(&[
+      ("Ahri.wad.client", b"root"),
+      ("nested/Map11.WAD", b"legacy"),
+      ("notes.txt", b"ignored"),
+    ]);
+
+    let stats = convert_archive_to_mod(
+      &mut zip,
+      &dir,
+      &ExtractionLimits::default(),
+      &CancelToken::new(),
+    )
+    .unwrap();
+    assert_eq!(stats.wad_files, 2);
+    assert!(stats.generated_info);
+    assert!(dir.join("META").join("info.json").exists());
+    assert_eq!(fs::read(dir.join("WAD").join("Ahri.wad.client")).unwrap(), b"root");
+    assert_eq!(fs::read(dir.join("WAD").join("Map11.WAD")).unwrap(), b"legacy");
+    assert!(!dir.join("WAD").join("notes.txt").exists());
+
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn enforces_extraction_limits_while_streaming() {
+    let dir = temp_dir("convert-limits");
+    let limits = ExtractionLimits::default();
+    let never = CancelToken::new();
+
+    // 4 MiB of zeros deflates to a few KiB
+    let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    writer
+      .start_file(
+        "WAD/Ahri.wad.client",
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated),
+      )
+      .unwrap();
+    writer.write_all(&vec![0u8; 4 * 1_048_576]).unwrap();
+    let mut bomb = ZipArchive::new(writer.finish().unwrap()).unwrap();
+    let result = convert_archive_to_mod(&mut bomb, &dir.join("bomb"), &limits, &never);
+    assert!(matches!(
+      result,
+      Err(InjectionError::ArchiveLimitExceeded(_))
+    ));
+
+    let files = [
+      ("WAD/A.wad.client", &b"0123456789"[..]),
+      ("WAD/B.wad.client", b"0123456789"),
+    ];
+    let total = ExtractionLimits {
+      max_total_bytes: 15,
+      ..limits
+    };
+    let result = convert_archive_to_mod(&mut archive(&files), &dir.join("total"), &total, &never);
+    assert!(matches!(
+      result,
+      Err(InjectionError::ArchiveLimitExceeded(_))
+    ));
+
+    let entries = ExtractionLimits {
+      max_entries: 1,
+      ..limits
+    };
+    let result = convert_archive_to_mod(
+      &mut archive(&files),
+      &dir.join("entries"),
+      &entries,
+      &never,
+    );
+    assert!(matches!(
+      result,
+      Err(InjectionError::ArchiveLimitExceeded(_))
+    ));
+    assert!(!dir.join("entries").exists());
+
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn prepare_mods_reports_each_archive_and_reuses_cache() {
+    let dir = temp_dir("prepare-mods");
+    let good = dir.join("good.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&good).unwrap());
+    writer.start_file("WAD/Ahri.wad.client", Default::default()).unwrap();
+    writer.write_all(b"packed").unwrap();
+    writer.finish().unwrap();
+    let bad = dir.join("bad.zip");
+    fs::write(&bad, b"not a zip").unwrap();
+
+    let archives = vec![bad.clone(), good.clone()];
+    let cancel = CancelToken::new();
+    let results = prepare_mods(&dir, &archives, &ExtractionLimits::default(), &cancel);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_err());
+    let first = results[1].as_ref().unwrap();
+    assert!(!first.cached);
+    assert_eq!(first.stats.wad_files, 1);
+    assert!(first.dir.join("WAD").join("Ahri.wad.client").exists());
+
+    let again = prepare_mods(&dir, &[good.clone()], &ExtractionLimits::default(), &cancel);
+    let second = again[0].as_ref().unwrap();
+    assert!(second.cached);
+    assert_eq!(second.dir, first.dir);
+
+    // Once cancelled, cached archives aren't reused and new ones stop at the first entry
+    cancel.cancel();
+    let cancelled = prepare_mods(&dir, &[good], &ExtractionLimits::default(), &cancel);
+    assert!(matches!(cancelled[0], Err(InjectionError::Aborted(_))));
+    let other = dir.join("other.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&other).unwrap());
+    writer.start_file("WAD/Annie.wad.client", Default::default()).unwrap();
+    writer.write_all(b"packed").unwrap();
+    writer.finish().unwrap();
+    let result = convert_skin_file_to_mod(
+      &other,
+      &dir.join("converted"),
+      &ExtractionLimits::default(),
+      &cancel,
+    );
+    assert!(matches!(result, Err(InjectionError::Aborted(_))));
+
+    let _ = fs::remove_dir_all(dir);
+  }
+}
@@ -0,0 +1,320 @@
+use crate::injection::error::InjectionError;
+use crate::injection::game_config::GAME_CFG_BACKUP;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Transactional replacement of Game/mods
+//
+// Mods are copied into `Game/mods.staging-<id>` next to the live directory. Only once every
+// mod is in place is the old `Game/mods` renamed aside and the staging directory renamed
+// over it. Until `commit`, `rollback` (or dropping the transaction) puts back the previous
+// `Game/mods`, `Game.cfg` and its backup exactly as they were when the transaction began.
+
+const STAGING_PREFIX: &str = "mods.staging-";
+const BACKUP_PREFIX: &str = "mods.backup-";
+
+// Files under Game that are snapshotted along with Game/mods
+const SNAPSHOT_FILES: [&str; 2] = ["Game.cfg", GAME_CFG_BACKUP];
+
+pub(crate) struct ModsTransaction {
+  game_path: PathBuf,
+  staging: PathBuf,
+  backup: PathBuf,
+  // Content of each SNAPSHOT_FILES entry at the start; None when the file didn't exist
+  files: Vec<(PathBuf, Option<Vec<u8>>)>,
+  swapped: bool,
+  finished: bool,
+}
+
+impl ModsTransaction {
+  pub(crate) fn begin(game_path: &Path) -> Result<Self, InjectionError> {
+    remove_leftovers(game_path);
+
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let staging = game_path.join(format!("{}{}", STAGING_PREFIX, id));
+    fs::create_dir_all(&staging)?;
+
+    let mut files = Vec::new();
+    for name in SNAPSHOT_FILES {
+      let path = game_path.join(name);
+      let content = match fs::read(&path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => {
+          let _ = fs::remove_dir_all(&staging);
+          return Err(e.into());
+        }
+      };
+      files.push((path, content));
+    }
+
+    Ok(Self {
+      game_path: game_path.to_path_buf(),
+      staging,
+      backup: game_path.join(format!("{}{}", BACKUP_PREFIX, id)),
+      files,
+      swapped: false,
+      finished: false,
+    })
+  }
+
+  // Directory to copy the new mods into
+  pub(crate) fn staging_dir(&self) -> &Path {
+    &self.staging
+  }
+
+  fn live_dir(&self) -> PathBuf {
+    self.game_path.join("mods")
+  }
+
+  // Replace Game/mods with the staging directory
+  pub(crate) fn swap_in(&mut self) -> Result<(), InjectionError> {
+    let live = self.live_dir();
+    let had_live = live.exists();
+    if had_live {
+      fs::rename(&live, &self.backup)?;
+    }
+    if let Err(e) = fs::rename(&self.staging, &live) {
+      if had_live {
+        let _ = fs::rename(&self.backup, &live);
+      }
+      return Err(e.into());
+    }
+    self.swapped = true;
+    Ok(())
+  }
+
+  // Keep the new Game/mods and drop the previous one
+  pub(crate) fn commit(mut self) {
+    self.finished = true;
+    let _ = fs::remove_dir_all(&self.backup);
+    let _ = fs::remove_dir_all(&self.staging);
+  }
+
+  // Put back Game/mods and Game.cfg as they were when the transaction began
+  pub(crate) fn rollback(mut self) -> Result<(), InjectionError> {
+    self.finished = true;
+    self.restore()
+  }
+
+  fn restore(&mut self) -> Result<(), InjectionError> {
+    let live = self.live_dir();
+    if self.swapped {
+      if live.exists() {
+        fs::remove_dir_all(&live)?;
+      }
+      if self.backup.exists() {
+        fs::rename(&self.backup, &live)?;
+      }
+      self.swapped = false;
+    }
+    if self.staging.exists() {
+      fs::remove_dir_all(&self.staging)?;
+    }
+
+    for (path, snapshot) in &self.files {
+      match snapshot {
+        Some(content) => {
+          if fs::read(path).ok().as_ref() != Some(content) {
+            fs::write(path, content)?;
+          }
+        }
+        None => {
+          if path.exists() {
+            fs::remove_file(path)?;
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Drop for ModsTransaction {
+  // An early return without commit/rollback still leaves the game as it was
+  fn drop(&mut self) {
+    if !self.finished {
+      let _ = self.restore();
+    }
+  }
+}
+
+// Staging dirs are always incomplete and can go. A backup is the previous Game/mods: with a
+// live Game/mods next to it the swap finished and the backup can go too, but without one we
+// crashed between the two renames and the backup is the only copy, so it becomes Game/mods
+// again. Several backups and no live directory can't be told apart, so they are kept.
+fn remove_leftovers(game_path: &Path) {
+  let Ok(read_dir) = fs::read_dir(game_path) else {
+    return;
+  };
+  let mut backups = Vec::new();
+  for entry in read_dir.flatten() {
+    let name = entry.file_name().to_string_lossy().to_string();
+    if !entry.path().is_dir() {
+      continue;
+    }
+    if name.starts_with(STAGING_PREFIX) {
+      let _ = fs::remove_dir_all(entry.path());
+    } else if name.starts_with(BACKUP_PREFIX) {
+      backups.push(entry.path());
+    }
+  }
+
+  let live = game_path.join("mods");
+  if live.exists() {
+    for backup in backups {
+      let _ = fs::remove_dir_all(backup);
+    }
+  } else if let [backup] = backups.as_slice() {
+    let _ = fs::rename(backup, &live);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Game directory with one installed mod and a Game.cfg
+  fn fake_game(name: &str, game_cfg: Option<&str>) -> PathBuf {
+    let game = std::env::temp_dir().join(format!("osskins-{}-{}", name, uuid::Uuid::new_v4()));
+    fs::create_dir_all(game.join("mods").join("old_mod").join("META")).unwrap();
+    fs::write(
+      game
+        .join("mods")
+        .join("old_mod")
+        .join("META")
+        .join("info.json"),
+      "{}",
+    )
+    .unwrap();
+    if let Some(content) = game_cfg {
+      fs::write(game.join("Game.cfg"), content).unwrap();
+    }
+    game
+  }
+
+  fn stage_mod(txn: &ModsTransaction, name: &str) {
+    let dir = txn.staging_dir().join(name).join("META");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("info.json"), "{}").unwrap();
+  }
+
+  fn entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+      .unwrap()
+      .flatten()
+      .map(|e| e.file_name().to_string_lossy().to_string())
+      .collect();
+    names.sort();
+    names
+  }
+
+  #[test]
+  fn commit_replaces_mods_and_leaves_nothing_behind() {
+    let game = fake_game("staging-commit", Some("[General]\r\nEnableMods=0\r\n"));
+
+    let mut txn = ModsTransaction::begin(&game).unwrap();
+    stage_mod(&txn, "new_mod");
+    // Staging is invisible to the game until the swap
+    assert_eq!(entries(&game.join("mods")), vec!["old_mod"]);
+
+    txn.swap_in().unwrap();
+    fs::write(game.join("Game.cfg"), "[General]\r\nEnableMods=1\r\n").unwrap();
+    txn.commit();
+
+    assert_eq!(entries(&game.join("mods")), vec!["new_mod"]);
+    assert_eq!(entries(&game), vec!["Game.cfg", "mods"]);
+    assert_eq!(
+      fs::read_to_string(game.join("Game.cfg")).unwrap(),
+      "[General]\r\nEnableMods=1\r\n"
+    );
+
+    let _ = fs::remove_dir_all(game);
+  }
+
+  #[test]
+  fn rollback_after_swap_restores_mods_and_game_cfg() {
+    let game = fake_game("staging-rollback", Some("[General]\nEnableMods=0\n"));
+
+    let mut txn = ModsTransaction::begin(&game).unwrap();
+    stage_mod(&txn, "new_mod");
+    txn.swap_in().unwrap();
+    fs::write(game.join("Game.cfg"), "[General]\nEnableMods=1\n").unwrap();
+    txn.rollback().unwrap();
+
+    assert_eq!(entries(&game.join("mods")), vec!["old_mod"]);
+    assert!(game
+      .join("mods")
+      .join("old_mod")
+      .join("META")
+      .join("info.json")
+      .exists());
+    assert_eq!(entries(&game), vec!["Game.cfg", "mods"]);
+    assert_eq!(
+      fs::read_to_string(game.join("Game.cfg")).unwrap(),
+      "[General]\nEnableMods=0\n"
+    );
+
+    let _ = fs::remove_dir_all(game);
+  }
+
+  #[test]
+  fn dropped_transaction_removes_created_game_cfg_and_staging() {
+    let game = fake_game("staging-drop", None);
+
+    {
+      let mut txn = ModsTransaction::begin(&game).unwrap();
+      stage_mod(&txn, "new_mod");
+      txn.swap_in().unwrap();
+      fs::write(game.join("Game.cfg"), "[General]\nEnableMods=1\n").unwrap();
+      fs::write(game.join(GAME_CFG_BACKUP), "{}").unwrap();
+      // Dropped here as if an error had been returned with `?`
+    }
+
+    assert_eq!(entries(&game), vec!["mods"]);
+    assert_eq!(entries(&game.join("mods")), vec!["old_mod"]);
+
+    let _ = fs::remove_dir_all(game);
+  }
+
+  #[test]
+  fn begin_clears_leftovers_and_works_without_existing_mods() {
+    let game = std::env::temp_dir().join(format!("osskins-staging-fresh-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(game.join(format!("{}stale", STAGING_PREFIX))).unwrap();
+
+    let mut txn = ModsTransaction::begin(&game).unwrap();
+    assert_eq!(entries(&game).len(), 1);
+    stage_mod(&txn, "new_mod");
+    txn.swap_in().unwrap();
+    txn.commit();
+    assert_eq!(entries(&game), vec!["mods"]);
+    assert_eq!(entries(&game.join("mods")), vec!["new_mod"]);
+
+    let _ = fs::remove_dir_all(game);
+  }
+
+  #[test]
+  fn begin_restores_mods_left_in_a_backup_by_a_crash() {
+    let game = fake_game("staging-crash", None);
+    let backup = game.join(format!("{}crashed", BACKUP_PREFIX));
+    // Crash after moving Game/mods aside, before the staging dir took its place
+    fs::rename(game.join("mods"), &backup).unwrap();
+    fs::create_dir_all(game.join(format!("{}crashed", STAGING_PREFIX))).unwrap();
+
+    let txn = ModsTransaction::begin(&game).unwrap();
+    assert!(!backup.exists());
+    assert_eq!(entries(&game.join("mods")), vec!["old_mod"]);
+    txn.rollback().unwrap();
+    assert_eq!(entries(&game), vec!["mods"]);
+
+    // With a live Game/mods the swap finished, so a backup next to it is stale
+    fs::create_dir_all(&backup).unwrap();
+    let txn = ModsTransaction::begin(&game).unwrap();
+    assert!(!backup.exists());
+    assert_eq!(entries(&game.join("mods")), vec!["old_mod"]);
+    txn.rollback().unwrap();
+
+    let _ = fs::remove_dir_all(game);
+  }
+}
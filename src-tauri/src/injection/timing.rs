@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Per-injection timing report
+//
+// Each stage of the pipeline is measured with a wall clock and added to the report of the
+// running injection. The report goes out with the injection state when the injection returns,
+// and once more when the overlay reaches "Waiting for league".
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimingStage {
+  // Finding the archive of every skin and misc item
+  Resolve,
+  // Converting archives into mods (extraction and mod layout are a single pass), or reusing
+  // them from the mod cache
+  Extract,
+  // Staging the mods and swapping them in for Game/mods
+  CopyToGame,
+  // Conflict analysis and building the overlay WADs, or reusing a cached overlay
+  Mkoverlay,
+  // Starting runoverlay, including retries
+  OverlaySpawn,
+  // From runoverlay starting to it reporting "Waiting for league match to start"
+  WaitForLeague,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageDuration {
+  pub stage: TimingStage,
+  pub duration_ms: u64,
+}
+
+// Conversion time of a single archive. These run in parallel, so they can add up to more
+// than the Extract stage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModTiming {
+  pub mod_name: String,
+  pub cached: bool,
+  pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimingReport {
+  pub injection_id: String,
+  // In the order the stages first ran
+  pub stages: Vec<StageDuration>,
+  #[serde(default)]
+  pub mods: Vec<ModTiming>,
+  pub total_ms: u64,
+  pub slowest: Option<TimingStage>,
+}
+
+impl TimingReport {
+  pub fn new(injection_id: String) -> Self {
+    Self {
+      injection_id,
+      ..Self::default()
+    }
+  }
+
+  // A stage that runs more than once, e.g. a retried overlay spawn, is summed up
+  pub fn add(&mut self, stage: TimingStage, duration: Duration) {
+    let duration_ms = duration.as_millis() as u64;
+    match self.stages.iter_mut().find(|s| s.stage == stage) {
+      Some(existing) => existing.duration_ms += duration_ms,
+      None => self.stages.push(StageDuration { stage, duration_ms }),
+    }
+    self.total_ms = self.stages.iter().map(|s| s.duration_ms).sum();
+    self.slowest = self
+      .stages
+      .iter()
+      .max_by_key(|s| s.duration_ms)
+      .map(|s| s.stage);
+  }
+
+  pub fn add_mod(&mut self, mod_name: &str, cached: bool, duration: Duration) {
+    self.mods.push(ModTiming {
+      mod_name: mod_name.to_string(),
+      cached,
+      duration_ms: duration.as_millis() as u64,
+    });
+  }
+
+  pub fn duration_of(&self, stage: TimingStage) -> Option<u64> {
+    self
+      .stages
+      .iter()
+      .find(|s| s.stage == stage)
+      .map(|s| s.duration_ms)
+  }
+
+  // One line for the injection log
+  pub fn summary(&self) -> String {
+    let stages: Vec<String> = self
+      .stages
+      .iter()
+      .map(|s| format!("{} {} ms", stage_name(s.stage), s.duration_ms))
+      .collect();
+    format!("{} (total {} ms)", stages.join(", "), self.total_ms)
+  }
+}
+
+fn stage_name(stage: TimingStage) -> &'static str {
+  match stage {
+    TimingStage::Resolve => "resolve",
+    TimingStage::Extract => "extract",
+    TimingStage::CopyToGame => "copy_to_game",
+    TimingStage::Mkoverlay => "mkoverlay",
+    TimingStage::OverlaySpawn => "overlay_spawn",
+    TimingStage::WaitForLeague => "wait_for_league",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn aggregates_stages_into_a_report() {
+    let mut report = TimingReport::new("abc".into());
+    report.add(TimingStage::Resolve, Duration::from_millis(5));
+    report.add(TimingStage::Extract, Duration::from_millis(120));
+    report.add(TimingStage::OverlaySpawn, Duration::from_millis(40));
+    // Second spawn attempt after a failed one
+    report.add(TimingStage::OverlaySpawn, Duration::from_millis(100));
+    report.add_mod("Ahri_1", false, Duration::from_millis(110));
+
+    assert_eq!(report.duration_of(TimingStage::OverlaySpawn), Some(140));
+    assert_eq!(report.duration_of(TimingStage::Mkoverlay), None);
+    assert_eq!(report.stages.len(), 3);
+    assert_eq!(report.total_ms, 265);
+    assert_eq!(report.slowest, Some(TimingStage::OverlaySpawn));
+    assert_eq!(
+      report.summary(),
+      "resolve 5 ms, extract 120 ms, overlay_spawn 140 ms (total 265 ms)"
+    );
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["stages"][2]["stage"], "overlay_spawn");
+    assert_eq!(json["mods"][0]["cached"], false);
+  }
+}
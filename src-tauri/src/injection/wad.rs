@@ -0,0 +1,405 @@
+use crate::injection::error::InjectionError;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+// Minimal reader/writer for Riot WAD v3 archives (.wad.client)
+//
+// Layout (all little endian):
+//   "RW" | major u8 | minor u8 | signature [u8; 256] | checksum u64 | entry_count u32
+//   entry_count * 32 byte TOC entries, sorted by path hash
+//   entry data
+//
+// Only the table of contents is parsed; entry payloads are copied verbatim so we never
+// need to decompress anything to build an overlay.
+
+pub const WAD_HEADER_SIZE: u64 = 272;
+pub const WAD_ENTRY_SIZE: usize = 32;
+
+// Entry payload kinds stored in the low nibble of the type byte
+pub const WAD_KIND_RAW: u8 = 0;
+pub const WAD_KIND_ZSTD_MULTI: u8 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WadVersion {
+  pub major: u8,
+  pub minor: u8,
+}
+
+impl WadVersion {
+  pub const V3_3: WadVersion = WadVersion { major: 3, minor: 3 };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WadEntry {
+  pub path_hash: u64,
+  pub offset: u32,
+  pub compressed_size: u32,
+  pub size: u32,
+  pub kind: u8,
+  pub subchunk_count: u8,
+  pub subchunk_index: u32,
+  pub checksum: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct WadToc {
+  pub version: WadVersion,
+  pub entries: Vec<WadEntry>,
+}
+
+fn invalid_wad(path: &Path, msg: &str) -> InjectionError {
+  InjectionError::ProcessError(format!("Invalid WAD file {}: {}", path.display(), msg))
+}
+
+// Read only the header and table of contents of a WAD archive
+pub fn read_wad_toc(path: &Path) -> Result<WadToc, InjectionError> {
+  let file = File::open(path)?;
+  let file_len = file.metadata()?.len();
+  let mut reader = BufReader::new(file);
+
+  let mut header = [0u8; WAD_HEADER_SIZE as usize];
+  reader
+    .read_exact(&mut header)
+    .map_err(|_| invalid_wad(path, "truncated header"))?;
+
+  if &header[0..2] != b"RW" {
+    return Err(invalid_wad(path, "bad magic"));
+  }
+  let version = WadVersion {
+    major: header[2],
+    minor: header[3],
+  };
+  if version.major != 3 {
+    return Err(invalid_wad(
+      path,
+      &format!("unsupported version {}.{}", version.major, version.minor),
+    ));
+  }
+
+  let entry_count = u32::from_le_bytes(header[268..272].try_into().unwrap()) as usize;
+  // The count comes from the file itself; check it against the file size before allocating
+  let toc_len = entry_count as u64 * WAD_ENTRY_SIZE as u64;
+  if toc_len > file_len.saturating_sub(WAD_HEADER_SIZE) {
+    return Err(invalid_wad(
+      path,
+      &format!("{} entries don't fit in {} bytes", entry_count, file_len),
+    ));
+  }
+  let mut toc = vec![0u8; toc_len as usize];
+  reader
+    .read_exact(&mut toc)
+    .map_err(|_| invalid_wad(path, "truncated table of contents"))?;
+
+  let entries = toc
+    .chunks_exact(WAD_ENTRY_SIZE)
+    .map(|raw| decode_entry(raw, version))
+    .collect();
+
+  Ok(WadToc { version, entries })
+}
+
+fn decode_entry(raw: &[u8], version: WadVersion) -> WadEntry {
+  let u32_at = |at: usize| u32::from_le_bytes(raw[at..at + 4].try_into().unwrap());
+  let type_byte = raw[20];
+  let index_lo = u16::from_le_bytes(raw[22..24].try_into().unwrap()) as u32;
+  // 3.4 reuses the old "duplicate" byte as the high bits of a 24-bit subchunk index
+  let subchunk_index = if version.minor >= 4 {
+    ((raw[21] as u32) << 16) | index_lo
+  } else {
+    index_lo
+  };
+
+  WadEntry {
+    path_hash: u64::from_le_bytes(raw[0..8].try_into().unwrap()),
+    offset: u32_at(8),
+    compressed_size: u32_at(12),
+    size: u32_at(16),
+    kind: type_byte & 0x0F,
+    subchunk_count: type_byte >> 4,
+    subchunk_index,
+    checksum: u64::from_le_bytes(raw[24..32].try_into().unwrap()),
+  }
+}
+
+fn encode_entry(entry: &WadEntry, version: WadVersion) -> [u8; WAD_ENTRY_SIZE] {
+  let mut raw = [0u8; WAD_ENTRY_SIZE];
+  raw[0..8].copy_from_slice(&entry.path_hash.to_le_bytes());
+  raw[8..12].copy_from_slice(&entry.offset.to_le_bytes());
+  raw[12..16].copy_from_slice(&entry.compressed_size.to_le_bytes());
+  raw[16..20].copy_from_slice(&entry.size.to_le_bytes());
+  raw[20] = (entry.kind & 0x0F) | (entry.subchunk_count << 4);
+  // We never emit duplicate entries, so pre-3.4 archives always get 0 here
+  raw[21] = if version.minor >= 4 {
+    (entry.subchunk_index >> 16) as u8
+  } else {
+    0
+  };
+  raw[22..24].copy_from_slice(&(entry.subchunk_index as u16).to_le_bytes());
+  raw[24..32].copy_from_slice(&entry.checksum.to_le_bytes());
+  raw
+}
+
+// Where the payload of an entry being written comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntrySource {
+  // Copy `compressed_size` bytes at `offset` from an existing WAD archive
+  Archive { path: std::path::PathBuf, offset: u32 },
+  // Copy a whole loose file (stored uncompressed)
+  File { path: std::path::PathBuf },
+}
+
+#[derive(Debug, Clone)]
+pub struct WadWriteEntry {
+  pub entry: WadEntry,
+  pub source: EntrySource,
+}
+
+// Write a WAD archive. Entries must be unique by path hash; they are sorted here
+// because the game binary-searches the table of contents.
+pub fn write_wad(
+  dest: &Path,
+  version: WadVersion,
+  entries: &mut [WadWriteEntry],
+) -> Result<(), InjectionError> {
+  entries.sort_by_key(|e| e.entry.path_hash);
+
+  let mut offset = WAD_HEADER_SIZE + (entries.len() * WAD_ENTRY_SIZE) as u64;
+  for item in entries.iter_mut() {
+    if offset > u32::MAX as u64 {
+      return Err(InjectionError::ProcessError(format!(
+        "WAD file {} would exceed 4GB",
+        dest.display()
+      )));
+    }
+    item.entry.offset = offset as u32;
+    offset += item.entry.compressed_size as u64;
+  }
+
+  if let Some(parent) = dest.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  let mut out = io::BufWriter::new(File::create(dest)?);
+
+  let mut header = [0u8; WAD_HEADER_SIZE as usize];
+  header[0..2].copy_from_slice(b"RW");
+  header[2] = version.major;
+  header[3] = version.minor;
+  header[268..272].copy_from_slice(&(entries.len() as u32).to_le_bytes());
+  out.write_all(&header)?;
+
+  for item in entries.iter() {
+    out.write_all(&encode_entry(&item.entry, version))?;
+  }
+
+  // Keep the current source archive open while consecutive entries come from it
+  let mut open_source: Option<(std::path::PathBuf, File)> = None;
+  for item in entries.iter() {
+    let expected = item.entry.compressed_size as u64;
+    let copied = match &item.source {
+      EntrySource::Archive { path, offset } => {
+        if open_source.as_ref().map(|(p, _)| p != path).unwrap_or(true) {
+          open_source = Some((path.clone(), File::open(path)?));
+        }
+        let (_, file) = open_source.as_mut().unwrap();
+        file.seek(SeekFrom::Start(*offset as u64))?;
+        io::copy(&mut file.take(expected), &mut out)?
+      }
+      EntrySource::File { path } => io::copy(&mut File::open(path)?.take(expected), &mut out)?,
+    };
+    if copied != expected {
+      return Err(InjectionError::ProcessError(format!(
+        "Short read while writing {}: entry {:016x} expected {} bytes, got {}",
+        dest.display(),
+        item.entry.path_hash,
+        expected,
+        copied
+      )));
+    }
+  }
+
+  out.flush()?;
+  Ok(())
+}
+
+// Hash a game path the way WAD tables of contents do (XXH64 of the lowercase path)
+pub fn hash_wad_path(path: &str) -> u64 {
+  let normalized = path.replace('\\', "/").to_lowercase();
+  xxh64(normalized.as_bytes(), 0)
+}
+
+const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+fn xxh64_round(acc: u64, input: u64) -> u64 {
+  acc
+    .wrapping_add(input.wrapping_mul(PRIME64_2))
+    .rotate_left(31)
+    .wrapping_mul(PRIME64_1)
+}
+
+fn xxh64_merge_round(acc: u64, val: u64) -> u64 {
+  (acc ^ xxh64_round(0, val))
+    .wrapping_mul(PRIME64_1)
+    .wrapping_add(PRIME64_4)
+}
+
+pub fn xxh64(data: &[u8], seed: u64) -> u64 {
+  let read_u64 = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+  let read_u32 = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap());
+  let len = data.len();
+  let mut i = 0;
+
+  let mut hash = if len >= 32 {
+    let mut v1 = seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2);
+    let mut v2 = seed.wrapping_add(PRIME64_2);
+    let mut v3 = seed;
+    let mut v4 = seed.wrapping_sub(PRIME64_1);
+    while i + 32 <= len {
+      v1 = xxh64_round(v1, read_u64(i));
+      v2 = xxh64_round(v2, read_u64(i + 8));
+      v3 = xxh64_round(v3, read_u64(i + 16));
+      v4 = xxh64_round(v4, read_u64(i + 24));
+      i += 32;
+    }
+    let mut acc = v1
+      .rotate_left(1)
+      .wrapping_add(v2.rotate_left(7))
+      .wrapping_add(v3.rotate_left(12))
+      .wrapping_add(v4.rotate_left(18));
+    acc = xxh64_merge_round(acc, v1);
+    acc = xxh64_merge_round(acc, v2);
+    acc = xxh64_merge_round(acc, v3);
+    xxh64_merge_round(acc, v4)
+  } else {
+    seed.wrapping_add(PRIME64_5)
+  };
+
+  hash = hash.wrapping_add(len as u64);
+
+  while i + 8 <= len {
+    hash ^= xxh64_round(0, read_u64(i));
+    hash = hash
+      .rotate_left(27)
+      .wrapping_mul(PRIME64_1)
+      .wrapping_add(PRIME64_4);
+    i += 8;
+  }
+  if i + 4 <= len {
+    hash ^= (read_u32(i) as u64).wrapping_mul(PRIME64_1);
+    hash = hash
+      .rotate_left(23)
+      .wrapping_mul(PRIME64_2)
+      .wrapping_add(PRIME64_3);
+    i += 4;
+  }
+  while i < len {
+    hash ^= (data[i] as u64).wrapping_mul(PRIME64_5);
+    hash = hash.rotate_left(11).wrapping_mul(PRIME64_1);
+    i += 1;
+  }
+
+  hash ^= hash >> 33;
+  hash = hash.wrapping_mul(PRIME64_2);
+  hash ^= hash >> 29;
+  hash = hash.wrapping_mul(PRIME64_3);
+  hash ^= hash >> 32;
+  hash
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+  use std::path::PathBuf;
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("osskins-{}-{}", name, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn write_fixture_wad(path: &Path, files: &[(&str, &[u8])]) {
+    let data_dir = path.with_extension("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    let mut entries: Vec<WadWriteEntry> = files
+      .iter()
+      .map(|(name, bytes)| {
+        let file = data_dir.join(hash_wad_path(name).to_string());
+        fs::write(&file, bytes).unwrap();
+        WadWriteEntry {
+          entry: WadEntry {
+            path_hash: hash_wad_path(name),
+            offset: 0,
+            compressed_size: bytes.len() as u32,
+            size: bytes.len() as u32,
+            kind: WAD_KIND_RAW,
+            subchunk_count: 0,
+            subchunk_index: 0,
+            checksum: 0,
+          },
+          source: EntrySource::File { path: file },
+        }
+      })
+      .collect();
+    write_wad(path, WadVersion::V3_3, &mut entries).unwrap();
+  }
+
+  fn read_entry(path: &Path, name: &str) -> Option<Vec<u8>> {
+    let toc = read_wad_toc(path).unwrap();
+    let entry = toc
+      .entries
+      .iter()
+      .find(|e| e.path_hash == hash_wad_path(name))?;
+    let mut file = File::open(path).unwrap();
+    file.seek(SeekFrom::Start(entry.offset as u64)).unwrap();
+    let mut buf = vec![0u8; entry.compressed_size as usize];
+    file.read_exact(&mut buf).unwrap();
+    Some(buf)
+  }
+
+  #[test]
+  fn xxh64_matches_reference_vectors() {
+    assert_eq!(xxh64(b"", 0), 0xEF46_DB37_51D8_E999);
+    assert_eq!(xxh64(b"abc", 0), 0x44BC_2CF5_AD77_0999);
+    assert_eq!(
+      xxh64(b"Nobody inspects the spammish repetition", 0),
+      0xFBCE_A83C_8A37_8BF1
+    );
+  }
+
+  #[test]
+  fn wad_round_trip_preserves_entries() {
+    let dir = temp_dir("wad-roundtrip");
+    let wad = dir.join("Test.wad.client");
+    write_fixture_wad(&wad, &[("b.bin", b"second"), ("a.bin", b"first")]);
+
+    let toc = read_wad_toc(&wad).unwrap();
+    assert_eq!(toc.version, WadVersion::V3_3);
+    assert_eq!(toc.entries.len(), 2);
+    assert!(toc.entries[0].path_hash < toc.entries[1].path_hash);
+    assert_eq!(read_entry(&wad, "a.bin").unwrap(), b"first");
+    assert_eq!(read_entry(&wad, "b.bin").unwrap(), b"second");
+
+    let _ = fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn wad_claiming_more_entries_than_it_holds_is_rejected() {
+    let dir = temp_dir("wad-oversized-toc");
+    let wad = dir.join("Hostile.wad.client");
+    write_fixture_wad(&wad, &[("a.bin", b"first")]);
+    // Claim u32::MAX entries, i.e. a ~137 GB table of contents
+    let mut bytes = fs::read(&wad).unwrap();
+    bytes[268..272].copy_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(&wad, &bytes).unwrap();
+
+    let err = read_wad_toc(&wad).unwrap_err();
+    assert!(err.to_string().contains("Invalid WAD file"), "{}", err);
+
+    let _ = fs::remove_dir_all(dir);
+  }
+}
@@ -0,0 +1,298 @@
+use crate::injection::backend::{OverlayBackend, OverlayProcess};
+use crate::injection::error::InjectionError;
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Overlay watchdog
+//
+// Every overlay started by run_overlay is supervised. If it exits on its own before the game
+// was patched (AV kill, crash, client restart) while the gameflow phase still needs it, it is
+// relaunched against the same overlay with a growing delay. Stopping the overlay on purpose
+// disarms the watchdog first, so cleanups never trigger a restart.
+
+// Everything needed to start the same overlay again
+#[derive(Clone)]
+pub(crate) struct OverlayLaunch {
+  pub(crate) backend: Arc<dyn OverlayBackend>,
+  pub(crate) overlay_dir: PathBuf,
+  pub(crate) config_path: PathBuf,
+  pub(crate) game_path: PathBuf,
+}
+
+impl OverlayLaunch {
+  fn spawn(&self) -> Result<Box<dyn OverlayProcess>, InjectionError> {
+    self
+      .backend
+      .run_overlay(&self.overlay_dir, &self.config_path, &self.game_path)
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RestartPolicy {
+  pub(crate) max_attempts: u32,
+  pub(crate) initial_delay: Duration,
+  pub(crate) max_delay: Duration,
+}
+
+impl Default for RestartPolicy {
+  fn default() -> Self {
+    Self {
+      max_attempts: 3,
+      initial_delay: Duration::from_secs(1),
+      max_delay: Duration::from_secs(8),
+    }
+  }
+}
+
+impl RestartPolicy {
+  // Delay before restart `attempt` (1-based): doubles each time, capped at max_delay
+  pub(crate) fn delay_for(&self, attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    self
+      .initial_delay
+      .saturating_mul(factor)
+      .min(self.max_delay)
+  }
+}
+
+pub(crate) enum RelaunchOutcome {
+  Restarted {
+    attempt: u32,
+    process: Box<dyn OverlayProcess>,
+  },
+  // The phase moved on while we were backing off
+  NotNeeded,
+  GaveUp {
+    attempts: u32,
+    error: String,
+  },
+}
+
+// Try to start `launch` again, using the attempts left after `used` earlier restarts
+pub(crate) fn relaunch(
+  launch: &OverlayLaunch,
+  policy: &RestartPolicy,
+  used: u32,
+  still_needed: impl Fn() -> bool,
+  sleep: impl Fn(Duration),
+) -> RelaunchOutcome {
+  let mut last_error = "the overlay exited again after being restarted".to_string();
+  for attempt in used + 1..=policy.max_attempts {
+    sleep(policy.delay_for(attempt));
+    if !still_needed() {
+      return RelaunchOutcome::NotNeeded;
+    }
+    match launch.spawn() {
+      Ok(process) => return RelaunchOutcome::Restarted { attempt, process },
+      Err(e) => last_error = e.to_string(),
+    }
+  }
+  RelaunchOutcome::GaveUp {
+    attempts: policy.max_attempts,
+    error: last_error,
+  }
+}
+
+struct Supervised {
+  generation: u64,
+  launch: OverlayLaunch,
+  restarts: u32,
+}
+
+static SUPERVISED: Lazy<Mutex<Option<Supervised>>> = Lazy::new(|| Mutex::new(None));
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+// Start supervising a freshly launched overlay. The returned generation identifies it.
+pub(crate) fn arm(launch: OverlayLaunch) -> u64 {
+  let generation = NEXT_GENERATION.fetch_add(1, Ordering::SeqCst);
+  *SUPERVISED.lock().expect("SUPERVISED poisoned") = Some(Supervised {
+    generation,
+    launch,
+    restarts: 0,
+  });
+  generation
+}
+
+// The overlay is being stopped on purpose
+pub(crate) fn disarm() {
+  SUPERVISED.lock().expect("SUPERVISED poisoned").take();
+}
+
+#[cfg(test)]
+pub(crate) fn current_generation() -> Option<u64> {
+  SUPERVISED
+    .lock()
+    .expect("SUPERVISED poisoned")
+    .as_ref()
+    .map(|s| s.generation)
+}
+
+// The game got patched, so earlier crashes no longer count against the restart budget
+pub(crate) fn note_patched(generation: u64) {
+  if let Some(supervised) = SUPERVISED.lock().expect("SUPERVISED poisoned").as_mut() {
+    if supervised.generation == generation {
+      supervised.restarts = 0;
+    }
+  }
+}
+
+// Relaunch the overlay of `generation` after it exited unexpectedly. None when it is no
+// longer supervised, e.g. because it was stopped while we were waiting.
+pub(crate) fn relaunch_supervised(
+  generation: u64,
+  still_needed: impl Fn() -> bool,
+  sleep: impl Fn(Duration),
+) -> Option<RelaunchOutcome> {
+  let (launch, used) = {
+    let guard = SUPERVISED.lock().expect("SUPERVISED poisoned");
+    match guard.as_ref() {
+      Some(s) if s.generation == generation => (s.launch.clone(), s.restarts),
+      _ => return None,
+    }
+  };
+
+  // Don't hold the lock while backing off, stopping the overlay must not wait for us
+  let outcome = relaunch(
+    &launch,
+    &RestartPolicy::default(),
+    used,
+    still_needed,
+    sleep,
+  );
+
+  let mut guard = SUPERVISED.lock().expect("SUPERVISED poisoned");
+  match guard.as_mut() {
+    Some(s) if s.generation == generation => {
+      match &outcome {
+        RelaunchOutcome::Restarted { attempt, .. } => s.restarts = *attempt,
+        RelaunchOutcome::NotNeeded | RelaunchOutcome::GaveUp { .. } => *guard = None,
+      }
+      Some(outcome)
+    }
+    _ => {
+      drop(guard);
+      if let RelaunchOutcome::Restarted { mut process, .. } = outcome {
+        process.kill();
+        process.wait();
+      }
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::injection::backend::fake::{FakeBackend, FakeEnd, FakeScript};
+  use std::cell::RefCell;
+
+  fn launch(backend: Arc<FakeBackend>) -> OverlayLaunch {
+    OverlayLaunch {
+      backend,
+      overlay_dir: PathBuf::from("overlay"),
+      config_path: PathBuf::from("config.json"),
+      game_path: PathBuf::from("Game"),
+    }
+  }
+
+  #[test]
+  fn backoff_doubles_up_to_the_cap() {
+    let policy = RestartPolicy::default();
+    let delays: Vec<u64> = (1..=6)
+      .map(|attempt| policy.delay_for(attempt).as_secs())
+      .collect();
+    assert_eq!(delays, vec![1, 2, 4, 8, 8, 8]);
+  }
+
+  #[test]
+  fn relaunches_after_failed_attempts() {
+    let backend = FakeBackend::new(vec![FakeScript::new(FakeEnd::RunUntilStopped)]);
+    backend.fail_spawn(true);
+    let slept = RefCell::new(Vec::new());
+
+    let outcome = relaunch(
+      &launch(backend.clone()),
+      &RestartPolicy::default(),
+      0,
+      || true,
+      |delay| {
+        slept.borrow_mut().push(delay);
+        // The second attempt finds mod-tools working again
+        if slept.borrow().len() == 2 {
+          backend.fail_spawn(false);
+        }
+      },
+    );
+
+    match outcome {
+      RelaunchOutcome::Restarted {
+        attempt,
+        mut process,
+      } => {
+        assert_eq!(attempt, 2);
+        process.kill();
+        process.wait();
+      }
+      _ => panic!("expected a restart"),
+    }
+    assert_eq!(
+      *slept.borrow(),
+      vec![Duration::from_secs(1), Duration::from_secs(2)]
+    );
+    assert_eq!(
+      backend.runs.lock().unwrap()[0].overlay_dir,
+      PathBuf::from("overlay")
+    );
+  }
+
+  #[test]
+  fn gives_up_after_max_attempts() {
+    let backend = FakeBackend::new(Vec::new());
+    backend.fail_spawn(true);
+    let attempts = RefCell::new(0);
+
+    let outcome = relaunch(
+      &launch(backend.clone()),
+      &RestartPolicy::default(),
+      1,
+      || true,
+      |_| *attempts.borrow_mut() += 1,
+    );
+
+    match outcome {
+      RelaunchOutcome::GaveUp { attempts, error } => {
+        assert_eq!(attempts, 3);
+        assert!(error.contains("fake spawn failure"));
+      }
+      _ => panic!("expected the watchdog to give up"),
+    }
+    // One restart was already used, so only two more were tried
+    assert_eq!(*attempts.borrow(), 2);
+
+    let exhausted = relaunch(
+      &launch(backend),
+      &RestartPolicy::default(),
+      3,
+      || true,
+      |_| panic!("no attempts left"),
+    );
+    assert!(matches!(exhausted, RelaunchOutcome::GaveUp { .. }));
+  }
+
+  #[test]
+  fn stops_when_the_phase_no_longer_needs_the_overlay() {
+    let backend = FakeBackend::new(Vec::new());
+    let outcome = relaunch(
+      &launch(backend.clone()),
+      &RestartPolicy::default(),
+      0,
+      || false,
+      |_| {},
+    );
+    assert!(matches!(outcome, RelaunchOutcome::NotNeeded));
+    assert!(backend.runs.lock().unwrap().is_empty());
+  }
+}
@@ -152,12 +152,14 @@ fn main() {
       update_champion_data,
       save_skin_file,
       save_zip_file,
+      validate_skin_package,
       download_and_save_file,
       download_file_to_champion_with_progress,
       cancel_download,
       select_league_directory,
       inject_skins,
       inject_skins_with_misc,
+      plan_injection,
       inject_all_selected_skins,
       warmup_injection,
       ensure_mod_tools,
@@ -221,10 +223,19 @@ fn main() {
       exit_app,
       hide_window,
       get_injection_state,
+      get_injection_history,
+      clear_injection_history,
+      cancel_injection,
+      get_mod_conflicts,
+      set_conflict_winner,
+      get_load_order,
+      set_load_order,
       set_start_hidden,
       get_start_hidden,
       set_manual_injection_mode,
       get_manual_injection_mode,
+      set_persistent_overlay,
+      get_persistent_overlay,
     ])
     .plugin(tauri_plugin_fs::init())
     .plugin(tauri_plugin_opener::init())
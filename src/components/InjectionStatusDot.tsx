@@ -12,12 +12,17 @@ import {
   TooltipTrigger,
 } from "./ui/tooltip";
 import { cn } from "@/lib/utils";
+import {
+  commandErrorMessage,
+  type CommandError,
+} from "@/lib/types/command-error";
 
 type Status = InjectionStatus;
 
 type InjectionStateSnapshot = {
   status: Status;
   last_error: string | null;
+  last_error_detail?: CommandError | null;
   updated_at_ms: number;
 };
 
@@ -106,7 +111,9 @@ export function InjectionStatusDot({
         );
         if (cancelled) return;
         const status = snapshot?.status ?? "idle";
-        const lastError = snapshot?.last_error ?? null;
+        const lastError = snapshot?.last_error_detail
+          ? commandErrorMessage(snapshot.last_error_detail)
+          : snapshot?.last_error ?? null;
         setInjectionStatus(status);
         setLastInjectionError(lastError);
         persistSnapshot(status, lastError);
@@ -247,22 +254,25 @@ export function InjectionStatusDot({
         handleStatus(normalizedStatus);
       });
 
-      unlistenError = await listen<string>("skin-injection-error", (e) => {
-        const message = e.payload;
-        setInjectionStatus("error");
-        setLastInjectionError(message);
-        persistSnapshot("error", message);
-        if (errorTimeoutRef.current) {
-          clearTimeout(errorTimeoutRef.current);
+      unlistenError = await listen<CommandError | string>(
+        "skin-injection-error",
+        (e) => {
+          const message = commandErrorMessage(e.payload);
+          setInjectionStatus("error");
+          setLastInjectionError(message);
+          persistSnapshot("error", message);
+          if (errorTimeoutRef.current) {
+            clearTimeout(errorTimeoutRef.current);
+          }
+          errorTimeoutRef.current = window.setTimeout(() => {
+            setInjectionStatus("idle");
+            persistSnapshot("idle", null);
+            toastShownRef.current.success = false;
+            errorTimeoutRef.current = null;
+          }, 10000);
+          showErrorToast(message || t("injection.error"));
         }
-        errorTimeoutRef.current = window.setTimeout(() => {
-          setInjectionStatus("idle");
-          persistSnapshot("idle", null);
-          toastShownRef.current.success = false;
-          errorTimeoutRef.current = null;
-        }, 10000);
-        showErrorToast(message || t("injection.error"));
-      });
+      );
 
       const devHandler = (e: Event) => {
         const ce = e as CustomEvent<unknown>;
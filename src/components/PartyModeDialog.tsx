@@ -28,6 +28,7 @@ import type {
   PairedFriend,
   SkinShare,
 } from "@/lib/types/party-mode";
+import { commandErrorMessage } from "@/lib/types/command-error";
 import { useGameStore } from "@/lib/store";
 
 export default function PartyModeDialog() {
@@ -127,9 +128,7 @@ export default function PartyModeDialog() {
       toast.success("Friend added to party mode!");
     } catch (error) {
       console.error("Failed to add friend:", error);
-      const errorMessage =
-        error instanceof Error ? error.message : "Failed to add friend";
-      toast.error(errorMessage);
+      toast.error(commandErrorMessage(error) || "Failed to add friend");
     } finally {
       setIsLoading(false);
     }
@@ -8,6 +8,7 @@ import {
   transformChampionData,
   getChampionIdFromSkinId,
 } from "../data-utils";
+import { isCommandError } from "../types/command-error";
 import type {
   DataUpdateProgress,
   EnsureModToolsResult,
@@ -451,12 +452,13 @@ export function useDataUpdate() {
                     downloaded = true;
                   } catch (err) {
                     // If it's a 404, try the next extension
-                    const errStr = String(err);
-                    if (errStr.includes("404") && ext === "zip") {
+                    const notFound =
+                      isCommandError(err) && err.code === "download_not_found";
+                    if (notFound && ext === "zip") {
                       continue; // Try .fantome
                     }
                     // For non-404 errors on .zip, or any error on .fantome, skip this skin
-                    if (ext === "fantome" || !errStr.includes("404")) {
+                    if (ext === "fantome" || !notFound) {
                       // Skin doesn't exist in repo - this is expected for some skins
                       return;
                     }
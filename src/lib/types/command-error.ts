@@ -0,0 +1,62 @@
+// Command Error Types - matches CommandError in src-tauri/src/commands/error.rs
+
+export type ErrorCode =
+  | "mod_tools_missing"
+  | "invalid_game_path"
+  | "skin_file_missing"
+  | "overlay_dir_locked"
+  | "permission_denied"
+  | "file_system"
+  | "process_failed"
+  | "config"
+  | "invalid_argument"
+  | "timeout"
+  | "cancelled"
+  | "superseded"
+  | "archive_invalid"
+  | "archive_limit_exceeded"
+  | "download_failed"
+  | "download_not_found"
+  | "league_client_unavailable"
+  | "party_mode"
+  | "internal";
+
+export type RemediationAction =
+  | "install_mod_tools"
+  | "select_game_directory"
+  | "redownload_skin"
+  | "add_antivirus_exclusion"
+  | "raise_extraction_limits"
+  | "check_connection"
+  | "start_league_client";
+
+export interface Remediation {
+  action: RemediationAction;
+  hint: string;
+}
+
+export interface CommandError {
+  code: ErrorCode;
+  message: string;
+  context: Record<string, string>;
+  remediation: Remediation | null;
+}
+
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as CommandError).code === "string" &&
+    typeof (error as CommandError).message === "string"
+  );
+}
+
+// Text to show for anything a command rejected with, including the remediation hint
+export function commandErrorMessage(error: unknown): string {
+  if (isCommandError(error)) {
+    return error.remediation
+      ? `${error.message}. ${error.remediation.hint}`
+      : error.message;
+  }
+  return error instanceof Error ? error.message : String(error);
+}